@@ -16,12 +16,14 @@ pub use specs::{
 
 use byteorder::{ByteOrder, LittleEndian};
 use common::{
+    assets,
     character::{CharacterId, CharacterItem},
     comp::{
         self,
         chat::{KillSource, KillType},
-        group, ControlAction, ControlEvent, Controller, ControllerInputs, GroupManip,
-        InventoryManip, InventoryUpdateEvent,
+        group::{self, InviteKind},
+        ControlAction, ControlEvent, Controller, ControllerInputs, GroupManip, InventoryManip,
+        InventoryUpdateEvent,
     },
     event::{EventBus, LocalEvent},
     msg::{
@@ -31,7 +33,7 @@ use common::{
         ServerRegisterAnswer, MAX_BYTES_CHAT_MSG,
     },
     outcome::Outcome,
-    recipe::RecipeBook,
+    recipe::{CraftingStation, Recipe, RecipeBook},
     state::State,
     sync::{Uid, UidAllocator, WorldSyncExt},
     terrain::{block::Block, neighbors, TerrainChunk, TerrainChunkSize},
@@ -63,6 +65,8 @@ pub enum Event {
     Disconnect,
     DisconnectionNotification(u64),
     InventoryUpdated(InventoryUpdateEvent),
+    /// The contents of a storage container the player asked to open arrived
+    ContainerOpened,
     Kicked(String),
     Notification(Notification),
     SetViewDistance(u32),
@@ -101,10 +105,19 @@ pub struct Client {
     pub active_character_id: Option<CharacterId>,
     recipe_book: RecipeBook,
     available_recipes: HashSet<String>,
+    /// The recipe being crafted, if it takes time to craft, and when crafting
+    /// started
+    current_craft: Option<(String, Instant)>,
+    /// Position of the storage container the player has open, along with its
+    /// contents once the server has sent them
+    open_container: Option<(Vec3<i32>, Option<comp::Inventory>)>,
+    /// Custom assets of the server which are missing or differ locally,
+    /// requested once registered
+    missing_assets: HashMap<String, u64>,
 
     max_group_size: u32,
     // Client has received an invite (inviter uid, time out instant)
-    group_invite: Option<(Uid, std::time::Instant, std::time::Duration)>,
+    group_invite: Option<(Uid, std::time::Instant, std::time::Duration, InviteKind)>,
     group_leader: Option<Uid>,
     // Note: potentially representable as a client only component
     group_members: HashMap<Uid, group::Role>,
@@ -190,6 +203,7 @@ impl Client {
             lod_horizon,
             world_map,
             recipe_book,
+            missing_assets,
             max_group_size,
             client_timeout,
         ) = match block_on(register_stream.recv())? {
@@ -200,6 +214,7 @@ impl Client {
                 client_timeout,
                 world_map,
                 recipe_book,
+                custom_assets,
             } => {
                 // Initialize `State`
                 let mut state = State::default();
@@ -358,6 +373,7 @@ impl Client {
                     lod_horizon,
                     (world_map, map_size, map_bounds),
                     recipe_book,
+                    missing_assets(custom_assets),
                     max_group_size,
                     client_timeout,
                 ))
@@ -388,6 +404,9 @@ impl Client {
             active_character_id: None,
             recipe_book,
             available_recipes: HashSet::default(),
+            current_craft: None,
+            open_container: None,
+            missing_assets,
 
             max_group_size,
             group_invite: None,
@@ -454,6 +473,15 @@ impl Client {
             Err(RegisterError::Banned(reason)) => Err(Error::Banned(reason)),
             Ok(()) => {
                 self.registered = true;
+                if !self.missing_assets.is_empty() {
+                    debug!(
+                        "Requesting {} custom assets from the server",
+                        self.missing_assets.len()
+                    );
+                    let mut paths = self.missing_assets.keys().cloned().collect::<Vec<_>>();
+                    paths.sort();
+                    self.send_msg_err(ClientGeneral::RequestAssets(paths))?;
+                }
                 Ok(())
             },
         }
@@ -502,7 +530,8 @@ impl Client {
                     //Always possible
                     ClientGeneral::ChatMsg(_)
                     | ClientGeneral::Disconnect
-                    | ClientGeneral::Terminate => &mut self.general_stream,
+                    | ClientGeneral::Terminate
+                    | ClientGeneral::RequestAssets(_) => &mut self.general_stream,
                 };
                 stream.send(msg)
             },
@@ -555,6 +584,8 @@ impl Client {
     pub fn request_logout(&mut self) {
         debug!("Requesting logout from server");
         self.send_msg(ClientGeneral::Disconnect);
+        // The assets of this server must not be used with the next one
+        assets::remove_memory_roots();
     }
 
     /// Request a state transition to `ClientState::Registered` from an ingame
@@ -604,15 +635,63 @@ impl Client {
             .unwrap_or(false)
     }
 
+    /// Break the item in an inventory slot down into crafting ingredients
+    pub fn salvage_slot(&mut self, slot: usize) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryManip(
+            InventoryManip::Salvage(slot),
+        )));
+    }
+
+    /// Spend a skill point to unlock a skill
+    pub fn unlock_skill(&mut self, skill: comp::Skill) {
+        self.send_msg(ClientGeneral::UnlockSkill(skill));
+    }
+
+    /// Get back the skill point spent on a skill
+    pub fn refund_skill(&mut self, skill: comp::Skill) {
+        self.send_msg(ClientGeneral::RefundSkill(skill));
+    }
+
+    /// Whether the player is close enough to the station a recipe needs
+    pub fn station_in_range(&self, recipe: &Recipe) -> bool {
+        self.state
+            .read_storage::<comp::Pos>()
+            .get(self.entity)
+            .map_or(false, |pos| {
+                recipe.station_in_range(&*self.state.terrain(), pos.0)
+            })
+    }
+
+    /// Every kind of crafting station close enough to the player to be used
+    pub fn nearby_stations(&self) -> Vec<CraftingStation> {
+        self.state
+            .read_storage::<comp::Pos>()
+            .get(self.entity)
+            .map_or_else(Vec::new, |pos| {
+                CraftingStation::all_near(&*self.state.terrain(), pos.0)
+            })
+    }
+
     pub fn craft_recipe(&mut self, recipe: &str) -> bool {
-        if self.can_craft_recipe(recipe) {
-            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryManip(
-                InventoryManip::CraftRecipe(recipe.to_string()),
-            )));
-            true
-        } else {
-            false
+        let craft_time = match self.recipe_book.get(recipe) {
+            Some(r) if self.can_craft_recipe(recipe) && self.station_in_range(r) => r.craft_time,
+            _ => return false,
+        };
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryManip(
+            InventoryManip::CraftRecipe(recipe.to_string()),
+        )));
+        if craft_time > 0.0 {
+            self.current_craft = Some((recipe.to_string(), Instant::now()));
         }
+        true
+    }
+
+    /// How far along crafting a recipe is, from 0 to 1, if it is still being
+    /// crafted
+    pub fn craft_progress(&self, recipe: &str) -> Option<f32> {
+        let (current, started) = self.current_craft.as_ref().filter(|(r, _)| r == recipe)?;
+        let craft_time = self.recipe_book.get(current)?.craft_time;
+        Some(started.elapsed().as_secs_f32() / craft_time).filter(|progress| *progress < 1.0)
     }
 
     fn update_available_recipes(&mut self) {
@@ -624,6 +703,41 @@ impl Client {
             .collect();
     }
 
+    /// Ask the server for the contents of the storage container at `pos`
+    pub fn open_container(&mut self, pos: Vec3<i32>) {
+        self.open_container = Some((pos, None));
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryManip(
+            InventoryManip::OpenContainer(pos),
+        )));
+    }
+
+    pub fn close_container(&mut self) { self.open_container = None; }
+
+    /// The open storage container, if its contents have arrived
+    pub fn container(&self) -> Option<(Vec3<i32>, &comp::Inventory)> {
+        self.open_container
+            .as_ref()
+            .and_then(|(pos, container)| Some((*pos, container.as_ref()?)))
+    }
+
+    /// Move an item from the player's inventory into the open container
+    pub fn deposit(&mut self, slot: usize) {
+        if let Some((pos, _)) = self.open_container {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryManip(
+                InventoryManip::Deposit(pos, slot),
+            )));
+        }
+    }
+
+    /// Move an item from the open container into the player's inventory
+    pub fn withdraw(&mut self, slot: usize) {
+        if let Some((pos, _)) = self.open_container {
+            self.send_msg(ClientGeneral::ControlEvent(ControlEvent::InventoryManip(
+                InventoryManip::Withdraw(pos, slot),
+            )));
+        }
+    }
+
     pub fn enable_lantern(&mut self) {
         self.send_msg(ClientGeneral::ControlEvent(ControlEvent::EnableLantern));
     }
@@ -640,7 +754,9 @@ impl Client {
 
     pub fn max_group_size(&self) -> u32 { self.max_group_size }
 
-    pub fn group_invite(&self) -> Option<(Uid, std::time::Instant, std::time::Duration)> {
+    pub fn group_invite(
+        &self,
+    ) -> Option<(Uid, std::time::Instant, std::time::Duration, InviteKind)> {
         self.group_invite
     }
 
@@ -878,6 +994,10 @@ impl Client {
         )));
     }
 
+    pub fn toggle_sprite(&mut self, pos: Vec3<i32>) {
+        self.send_msg(ClientGeneral::ControlEvent(ControlEvent::ToggleSprite(pos)));
+    }
+
     /// Execute a single client tick, handle input and update the game state by
     /// the given duration.
     pub fn tick(
@@ -964,7 +1084,7 @@ impl Client {
         // Check if the group invite has timed out and remove if so
         if self
             .group_invite
-            .map_or(false, |(_, timeout, dur)| timeout.elapsed() > dur)
+            .map_or(false, |(_, timeout, dur, _)| timeout.elapsed() > dur)
         {
             self.group_invite = None;
         }
@@ -1257,6 +1377,24 @@ impl Client {
             ServerGeneral::Notification(n) => {
                 frontend_events.push(Event::Notification(n));
             },
+            ServerGeneral::CustomAssets(files) => {
+                // Only accept the gameplay assets that were requested, with the content that
+                // was advertised
+                let files = files
+                    .into_iter()
+                    .filter(|(path, data)| {
+                        let valid = assets::is_gameplay_asset_path(path)
+                            && self.missing_assets.remove(path) == Some(assets::content_hash(data));
+                        if !valid {
+                            warn!(?path, "Rejecting custom asset sent by the server");
+                        }
+                        valid
+                    })
+                    .collect::<HashMap<_, _>>();
+                if !files.is_empty() {
+                    assets::add_memory_root(files);
+                }
+            },
             _ => unreachable!("Not a general msg"),
         }
         Ok(())
@@ -1340,8 +1478,12 @@ impl Client {
                     },
                 }
             },
-            ServerGeneral::GroupInvite { inviter, timeout } => {
-                self.group_invite = Some((inviter, std::time::Instant::now(), timeout));
+            ServerGeneral::GroupInvite {
+                inviter,
+                timeout,
+                kind,
+            } => {
+                self.group_invite = Some((inviter, std::time::Instant::now(), timeout, kind));
             },
             ServerGeneral::InvitePending(uid) => {
                 if !self.pending_invites.insert(uid) {
@@ -1384,6 +1526,18 @@ impl Client {
 
                 frontend_events.push(Event::InventoryUpdated(event));
             },
+            ServerGeneral::ContainerUpdate(pos, mut container) => {
+                // Updates for containers other players have open nearby are ignored
+                if let Some((open_pos, open_container)) = &mut self.open_container {
+                    if *open_pos == pos {
+                        if open_container.is_none() {
+                            frontend_events.push(Event::ContainerOpened);
+                        }
+                        container.recount_items();
+                        *open_container = Some(container);
+                    }
+                }
+            },
             ServerGeneral::TerrainChunkUpdate { key, chunk } => {
                 if let Ok(chunk) = chunk {
                     self.state.insert_chunk(key, *chunk);
@@ -1604,6 +1758,9 @@ impl Client {
             .map(|u| u.into())
             .expect("Client doesn't have a Uid!!!");
 
+        self.open_container = None;
+        self.current_craft = None;
+
         // Clear ecs of all entities
         self.state.ecs_mut().delete_all();
         self.state.ecs_mut().maintain();
@@ -1629,25 +1786,28 @@ impl Client {
         }
     }
 
+    /// The alias a player is shown with in chat
+    pub fn player_alias(&self, uid: &Uid) -> String {
+        self.player_list
+            .get(uid)
+            .map_or("<?>".to_string(), |player_info| {
+                if player_info.is_admin {
+                    format!(
+                        "ADMIN - {}",
+                        self.personalize_alias(*uid, player_info.player_alias.clone())
+                    )
+                } else {
+                    self.personalize_alias(*uid, player_info.player_alias.clone())
+                }
+            })
+    }
+
     /// Format a message for the client (voxygen chat box or chat-cli)
     pub fn format_message(&self, msg: &comp::ChatMsg, character_name: bool) -> String {
         let comp::ChatMsg {
             chat_type, message, ..
         } = &msg;
-        let alias_of_uid = |uid| {
-            self.player_list
-                .get(uid)
-                .map_or("<?>".to_string(), |player_info| {
-                    if player_info.is_admin {
-                        format!(
-                            "ADMIN - {}",
-                            self.personalize_alias(*uid, player_info.player_alias.clone())
-                        )
-                    } else {
-                        self.personalize_alias(*uid, player_info.player_alias.clone())
-                    }
-                })
-        };
+        let alias_of_uid = |uid| self.player_alias(uid);
         let name_of_uid = |uid| {
             let ecs = self.state.ecs();
             (
@@ -1675,131 +1835,71 @@ impl Client {
             }
         };
         match chat_type {
-            // For ChatType::{Online, Offline, Kill} these message strings are localized
-            // in voxygen/src/hud/chat.rs before being formatted here.
+            // ChatType::{Online, Offline, Kill} are sent without a message and localized
+            // in voxygen/src/hud/chat.rs instead of being formatted here, so these
+            // default formats are for cli clients that don't have localization info.
             // Kill messages are generated in server/src/events/entity_manipulation.rs
             // fn handle_destroy
-            comp::ChatType::Online(uid) => {
-                // Default message formats if no localized message string is set by hud
-                // Needed for cli clients that don't set localization info
-                if message == "" {
-                    format!("[{}] came online", alias_of_uid(uid))
-                } else {
-                    message.replace("{name}", &alias_of_uid(uid))
-                }
-            },
-            comp::ChatType::Offline(uid) => {
-                // Default message formats if no localized message string is set by hud
-                // Needed for cli clients that don't set localization info
-                if message == "" {
-                    format!("[{}] went offline", alias_of_uid(uid))
-                } else {
-                    message.replace("{name}", &alias_of_uid(uid))
-                }
-            },
+            comp::ChatType::Online(uid) => format!("[{}] came online", alias_of_uid(uid)),
+            comp::ChatType::Offline(uid) => format!("[{}] went offline", alias_of_uid(uid)),
             comp::ChatType::CommandError => message.to_string(),
             comp::ChatType::CommandInfo => message.to_string(),
             comp::ChatType::Loot => message.to_string(),
             comp::ChatType::FactionMeta(_) => message.to_string(),
             comp::ChatType::GroupMeta(_) => message.to_string(),
-            comp::ChatType::Kill(kill_source, victim) => {
-                // Default message formats if no localized message string is set by hud
-                // Needed for cli clients that don't set localization info
-                if message == "" {
-                    match kill_source {
-                        KillSource::Player(attacker_uid, KillType::Melee) => format!(
-                            "[{}] killed [{}]",
-                            alias_of_uid(attacker_uid),
-                            alias_of_uid(victim)
-                        ),
-                        KillSource::Player(attacker_uid, KillType::Projectile) => format!(
-                            "[{}] shot [{}]",
-                            alias_of_uid(attacker_uid),
-                            alias_of_uid(victim)
-                        ),
-                        KillSource::Player(attacker_uid, KillType::Explosion) => format!(
-                            "[{}] blew up [{}]",
-                            alias_of_uid(attacker_uid),
-                            alias_of_uid(victim)
-                        ),
-                        KillSource::Player(attacker_uid, KillType::Energy) => format!(
-                            "[{}] used magic to kill [{}]",
-                            alias_of_uid(attacker_uid),
-                            alias_of_uid(victim)
-                        ),
-                        KillSource::Player(attacker_uid, KillType::Buff) => format!(
-                            "[{}] killed [{}]",
-                            alias_of_uid(attacker_uid),
-                            alias_of_uid(victim)
-                        ),
-                        KillSource::NonPlayer(attacker_name, KillType::Melee) => {
-                            format!("{} killed [{}]", attacker_name, alias_of_uid(victim))
-                        },
-                        KillSource::NonPlayer(attacker_name, KillType::Projectile) => {
-                            format!("{} shot [{}]", attacker_name, alias_of_uid(victim))
-                        },
-                        KillSource::NonPlayer(attacker_name, KillType::Explosion) => {
-                            format!("{} blew up [{}]", attacker_name, alias_of_uid(victim))
-                        },
-                        KillSource::NonPlayer(attacker_name, KillType::Energy) => format!(
-                            "{} used magic to kill [{}]",
-                            attacker_name,
-                            alias_of_uid(victim)
-                        ),
-                        KillSource::NonPlayer(attacker_name, KillType::Buff) => {
-                            format!("{} killed [{}]", attacker_name, alias_of_uid(victim))
-                        },
-                        KillSource::Environment(environment) => {
-                            format!("[{}] died in {}", alias_of_uid(victim), environment)
-                        },
-                        KillSource::FallDamage => {
-                            format!("[{}] died from fall damage", alias_of_uid(victim))
-                        },
-                        KillSource::Suicide => {
-                            format!("[{}] died from self-inflicted wounds", alias_of_uid(victim))
-                        },
-                        KillSource::Other => format!("[{}] died", alias_of_uid(victim)),
-                    }
-                } else {
-                    match kill_source {
-                        KillSource::Player(attacker_uid, KillType::Melee) => message
-                            .replace("{attacker}", &alias_of_uid(attacker_uid))
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::Player(attacker_uid, KillType::Projectile) => message
-                            .replace("{attacker}", &alias_of_uid(attacker_uid))
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::Player(attacker_uid, KillType::Explosion) => message
-                            .replace("{attacker}", &alias_of_uid(attacker_uid))
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::Player(attacker_uid, KillType::Energy) => message
-                            .replace("{attacker}", &alias_of_uid(attacker_uid))
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::Player(attacker_uid, KillType::Buff) => message
-                            .replace("{attacker}", &alias_of_uid(attacker_uid))
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::NonPlayer(attacker_name, KillType::Melee) => message
-                            .replace("{attacker}", attacker_name)
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::NonPlayer(attacker_name, KillType::Projectile) => message
-                            .replace("{attacker}", attacker_name)
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::NonPlayer(attacker_name, KillType::Explosion) => message
-                            .replace("{attacker}", attacker_name)
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::NonPlayer(attacker_name, KillType::Energy) => message
-                            .replace("{attacker}", attacker_name)
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::NonPlayer(attacker_name, KillType::Buff) => message
-                            .replace("{attacker}", attacker_name)
-                            .replace("{victim}", &alias_of_uid(victim)),
-                        KillSource::Environment(environment) => message
-                            .replace("{name}", &alias_of_uid(victim))
-                            .replace("{environment}", environment),
-                        KillSource::FallDamage => message.replace("{name}", &alias_of_uid(victim)),
-                        KillSource::Suicide => message.replace("{name}", &alias_of_uid(victim)),
-                        KillSource::Other => message.replace("{name}", &alias_of_uid(victim)),
-                    }
-                }
+            comp::ChatType::Kill(kill_source, victim) => match kill_source {
+                KillSource::Player(attacker_uid, KillType::Melee) => format!(
+                    "[{}] killed [{}]",
+                    alias_of_uid(attacker_uid),
+                    alias_of_uid(victim)
+                ),
+                KillSource::Player(attacker_uid, KillType::Projectile) => format!(
+                    "[{}] shot [{}]",
+                    alias_of_uid(attacker_uid),
+                    alias_of_uid(victim)
+                ),
+                KillSource::Player(attacker_uid, KillType::Explosion) => format!(
+                    "[{}] blew up [{}]",
+                    alias_of_uid(attacker_uid),
+                    alias_of_uid(victim)
+                ),
+                KillSource::Player(attacker_uid, KillType::Energy) => format!(
+                    "[{}] used magic to kill [{}]",
+                    alias_of_uid(attacker_uid),
+                    alias_of_uid(victim)
+                ),
+                KillSource::Player(attacker_uid, KillType::Buff) => format!(
+                    "[{}] killed [{}]",
+                    alias_of_uid(attacker_uid),
+                    alias_of_uid(victim)
+                ),
+                KillSource::NonPlayer(attacker_name, KillType::Melee) => {
+                    format!("{} killed [{}]", attacker_name, alias_of_uid(victim))
+                },
+                KillSource::NonPlayer(attacker_name, KillType::Projectile) => {
+                    format!("{} shot [{}]", attacker_name, alias_of_uid(victim))
+                },
+                KillSource::NonPlayer(attacker_name, KillType::Explosion) => {
+                    format!("{} blew up [{}]", attacker_name, alias_of_uid(victim))
+                },
+                KillSource::NonPlayer(attacker_name, KillType::Energy) => format!(
+                    "{} used magic to kill [{}]",
+                    attacker_name,
+                    alias_of_uid(victim)
+                ),
+                KillSource::NonPlayer(attacker_name, KillType::Buff) => {
+                    format!("{} killed [{}]", attacker_name, alias_of_uid(victim))
+                },
+                KillSource::Environment(environment) => {
+                    format!("[{}] died in {}", alias_of_uid(victim), environment)
+                },
+                KillSource::FallDamage => {
+                    format!("[{}] died from fall damage", alias_of_uid(victim))
+                },
+                KillSource::Suicide => {
+                    format!("[{}] died from self-inflicted wounds", alias_of_uid(victim))
+                },
+                KillSource::Other => format!("[{}] died", alias_of_uid(victim)),
             },
             comp::ChatType::Tell(from, to) => {
                 let from_alias = alias_of_uid(from);
@@ -1823,6 +1923,18 @@ impl Client {
     }
 }
 
+/// The gameplay assets advertised by the server which aren't available locally
+/// with the same content, along with the hash of their content on the server
+fn missing_assets(custom_assets: Vec<(String, u64)>) -> HashMap<String, u64> {
+    custom_assets
+        .into_iter()
+        .filter(|(path, hash)| {
+            assets::is_gameplay_asset_path(path)
+                && assets::read_file(path).map_or(true, |data| assets::content_hash(&data) != *hash)
+        })
+        .collect()
+}
+
 impl Drop for Client {
     fn drop(&mut self) {
         trace!("Dropping client");
@@ -1840,5 +1952,7 @@ impl Drop for Client {
         if let Err(e) = block_on(self.participant.take().unwrap().disconnect()) {
             warn!(?e, "error when disconnecting, couldn't send all data");
         }
+        // The assets of this server must not be used with the next one
+        assets::remove_memory_roots();
     }
 }
@@ -1,4 +1,5 @@
 //! Load assets (images or voxel data) from files
+pub mod pack;
 pub mod watch;
 
 use core::{any::Any, fmt, marker::PhantomData};
@@ -6,15 +7,17 @@ use dot_vox::DotVoxData;
 use hashbrown::HashMap;
 use image::DynamicImage;
 use lazy_static::lazy_static;
+use pack::AssetPack;
 use serde::Deserialize;
 use serde_json::Value;
 use std::{
+    collections::BTreeSet,
     fs::{self, File, ReadDir},
-    io::{BufReader, Read},
-    path::PathBuf,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
     sync::{Arc, RwLock},
 };
-use tracing::{error, trace};
+use tracing::{error, info, trace};
 
 /// The error returned by asset loading functions
 #[derive(Debug, Clone)]
@@ -84,7 +87,7 @@ pub trait Asset: Sized {
 
     const ENDINGS: &'static [&'static str];
     /// Parse the input file and return the correct Asset.
-    fn parse(buf_reader: BufReader<File>, specifier: &str) -> Result<Self::Output, Error>;
+    fn parse(buf_reader: BufReader<AssetFile>, specifier: &str) -> Result<Self::Output, Error>;
 
     // TODO: Remove this function. It's only used in world/ in a really ugly way.To
     // do this properly assets should have all their necessary data in one file. A
@@ -244,29 +247,27 @@ pub trait Asset: Sized {
     {
         let asset = Self::load(specifier)?;
 
-        // Determine path to watch
-        let path = unpack_specifier(specifier);
-        let mut path_with_extension = None;
-        for ending in Self::ENDINGS {
-            let mut path = path.clone();
-            path.set_extension(ending);
-
-            if path.exists() {
-                path_with_extension = Some(path);
-                break;
-            }
-        }
+        // Watch the file in every root that provides it, so that editing either an
+        // overlay or the asset it overrides triggers a reload. Packs and
+        // files in memory are not expected to change and are never watched.
+        let roots = asset_roots();
+        let paths = roots
+            .iter()
+            .filter_map(|root| match root {
+                AssetRoot::Dir(root) => Some(candidate_paths(root, specifier, Self::ENDINGS)),
+                AssetRoot::Pack(_) | AssetRoot::Memory(_) => None,
+            })
+            .flatten()
+            .filter(|path| path.is_file());
 
-        let owned_specifier = specifier.to_string();
-        indicator.add(
-            path_with_extension
-                .ok_or_else(|| Error::NotFound(path.to_string_lossy().into_owned()))?,
-            move || {
+        for path in paths {
+            let owned_specifier = specifier.to_string();
+            indicator.add(path, move || {
                 if let Err(e) = reload::<Self>(&owned_specifier) {
                     error!(?e, ?owned_specifier, "Error reloading owned_specifier");
                 }
-            },
-        );
+            });
+        }
 
         Ok(asset)
     }
@@ -275,7 +276,7 @@ pub trait Asset: Sized {
 impl Asset for DynamicImage {
     const ENDINGS: &'static [&'static str] = &["png", "jpg"];
 
-    fn parse(mut buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, Error> {
+    fn parse(mut buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, Error> {
         let mut buf = Vec::new();
         buf_reader.read_to_end(&mut buf)?;
         image::load_from_memory(&buf).map_err(Error::parse_error)
@@ -285,7 +286,7 @@ impl Asset for DynamicImage {
 impl Asset for DotVoxData {
     const ENDINGS: &'static [&'static str] = &["vox"];
 
-    fn parse(mut buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, Error> {
+    fn parse(mut buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, Error> {
         let mut buf = Vec::new();
         buf_reader.read_to_end(&mut buf)?;
         dot_vox::load_bytes(&buf).map_err(Error::parse_error)
@@ -296,7 +297,7 @@ impl Asset for DotVoxData {
 impl Asset for Value {
     const ENDINGS: &'static [&'static str] = &["json"];
 
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, Error> {
         serde_json::from_reader(buf_reader).map_err(Error::parse_error)
     }
 }
@@ -309,7 +310,7 @@ impl<T: Send + Sync + for<'de> Deserialize<'de>> Asset for Ron<T> {
 
     const ENDINGS: &'static [&'static str] = &["ron"];
 
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<T, Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<T, Error> {
         ron::de::from_reader(buf_reader).map_err(Error::parse_error)
     }
 }
@@ -344,7 +345,8 @@ where
 }
 
 lazy_static! {
-    /// Lazy static to find and cache where the asset directory is.
+    /// Lazy static to find and cache where the asset directory, or an
+    /// `assets.pack` archive in its place, is.
     /// Cases we need to account for:
     /// 1. Running through airshipper (`assets` next to binary)
     /// 2. Install with package manager and run (assets probably in `/usr/share/veloren/assets` while binary in `/usr/bin/`)
@@ -410,6 +412,13 @@ lazy_static! {
                 tracing::info!("Assets found path={}", path.display());
                 return path;
             }
+
+            // A packed archive of the assets can be used in place of the directory
+            let pack_path = path.with_extension("pack");
+            if pack_path.is_file() {
+                tracing::info!("Asset pack found path={}", pack_path.display());
+                return pack_path;
+            }
         }
 
         panic!(
@@ -423,86 +432,380 @@ lazy_static! {
     };
 }
 
+/// A location assets are loaded from
+#[derive(Clone, Debug)]
+pub enum AssetRoot {
+    /// A directory laid out like `assets/`
+    Dir(PathBuf),
+    /// An archive created with [`pack::write_pack`]
+    Pack(Arc<AssetPack>),
+    /// Files received at runtime (e.g. from a server), keyed by their path
+    /// relative to the root like `common/items/food/apple.ron`
+    Memory(Arc<HashMap<String, Vec<u8>>>),
+}
+
+impl AssetRoot {
+    /// Opens a directory, or an asset pack if `path` is a file
+    pub fn open(path: PathBuf) -> Result<Self, Error> {
+        if path.is_dir() {
+            Ok(AssetRoot::Dir(path))
+        } else if path.is_file() {
+            Ok(AssetRoot::Pack(Arc::new(AssetPack::open(&path)?)))
+        } else {
+            Err(Error::NotFound(path.to_string_lossy().into_owned()))
+        }
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            AssetRoot::Dir(path) => Some(path),
+            AssetRoot::Pack(pack) => Some(pack.path()),
+            AssetRoot::Memory(_) => None,
+        }
+    }
+
+    /// Opens the file for a specifier, trying each of the extensions in order
+    fn open_file(&self, specifier: &str, endings: &[&str]) -> Option<AssetFile> {
+        match self {
+            AssetRoot::Dir(root) => candidate_paths(root, specifier, endings).find_map(|path| {
+                trace!(?path, "Trying to access");
+                File::open(path).ok().map(AssetFile::Fs)
+            }),
+            AssetRoot::Pack(pack) => endings.iter().find_map(|ending| {
+                let path = format!("{}.{}", specifier.replace(".", "/"), ending);
+                match pack.read(&path)? {
+                    Ok(data) => Some(AssetFile::Memory(io::Cursor::new(data))),
+                    Err(e) => {
+                        error!(?e, ?path, "Failed to read file from asset pack");
+                        None
+                    },
+                }
+            }),
+            AssetRoot::Memory(files) => endings.iter().find_map(|ending| {
+                let path = format!("{}.{}", specifier.replace(".", "/"), ending);
+                files
+                    .get(&path)
+                    .map(|data| AssetFile::Memory(io::Cursor::new(data.clone())))
+            }),
+        }
+    }
+
+    /// Reads the file at `path` relative to the root, returning `None` if it
+    /// does not exist
+    fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>> {
+        match self {
+            AssetRoot::Dir(root) => {
+                let path = root.join(path);
+                path.is_file().then(|| fs::read(path))
+            },
+            AssetRoot::Pack(pack) => pack.read(path),
+            AssetRoot::Memory(files) => files.get(path).cloned().map(Ok),
+        }
+    }
+
+    /// The paths of all files in `dir` relative to the root, including those
+    /// in subdirectories
+    fn files_in(&self, dir: &str) -> Vec<String> {
+        fn walk(root: &Path, dir: &Path, files: &mut Vec<String>) {
+            for path in fs::read_dir(dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| Some(entry.ok()?.path()))
+            {
+                if path.is_dir() {
+                    walk(root, &path, files);
+                } else if let Ok(relative) = path.strip_prefix(root) {
+                    files.push(
+                        relative
+                            .components()
+                            .map(|c| c.as_os_str().to_string_lossy())
+                            .collect::<Vec<_>>()
+                            .join("/"),
+                    );
+                }
+            }
+        }
+
+        match self {
+            AssetRoot::Dir(root) => {
+                let mut files = Vec::new();
+                walk(root, &root.join(dir), &mut files);
+                files
+            },
+            AssetRoot::Pack(pack) => pack.files_in(dir).map(str::to_owned).collect(),
+            AssetRoot::Memory(files) => {
+                let prefix = format!("{}/", dir);
+                files
+                    .keys()
+                    .filter(|path| path.starts_with(&prefix))
+                    .cloned()
+                    .collect()
+            },
+        }
+    }
+
+    /// Whether the root contains the directory for a specifier
+    fn has_dir(&self, specifier: &str) -> bool {
+        let dir_path = specifier.replace(".", "/");
+        match self {
+            AssetRoot::Dir(root) => root.join(dir_path).is_dir(),
+            AssetRoot::Pack(pack) => pack.contains_dir(&dir_path),
+            AssetRoot::Memory(files) => {
+                let prefix = format!("{}/", dir_path);
+                files.keys().any(|path| path.starts_with(&prefix))
+            },
+        }
+    }
+}
+
+/// A file opened from an asset root, either on disk or already read into
+/// memory
+pub enum AssetFile {
+    Fs(File),
+    Memory(io::Cursor<Vec<u8>>),
+}
+
+impl Read for AssetFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            AssetFile::Fs(file) => file.read(buf),
+            AssetFile::Memory(cursor) => cursor.read(buf),
+        }
+    }
+}
+
+impl Seek for AssetFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            AssetFile::Fs(file) => file.seek(pos),
+            AssetFile::Memory(cursor) => cursor.seek(pos),
+        }
+    }
+}
+
+lazy_static! {
+    /// The roots assets are loaded from, in order of increasing priority. The
+    /// first one is always [`ASSETS_PATH`], followed by any overlays (e.g.
+    /// mods) which can override or add assets. Each root is either a
+    /// directory or an asset pack.
+    ///
+    /// Overlays can be supplied through the `VELOREN_ASSET_OVERLAYS`
+    /// environment variable, using the platform's path list separator, or
+    /// added at runtime with [`add_asset_root`].
+    static ref ASSET_ROOTS: RwLock<Vec<AssetRoot>> = RwLock::new({
+        let mut roots = vec![AssetRoot::open(ASSETS_PATH.clone()).expect("Failed to open assets")];
+        if let Some(var) = std::env::var_os("VELOREN_ASSET_OVERLAYS") {
+            roots.extend(std::env::split_paths(&var).filter_map(|path| {
+                AssetRoot::open(path.clone())
+                    .map_err(|e| error!(?e, ?path, "Failed to open asset overlay"))
+                    .ok()
+            }));
+        }
+        roots
+    });
+}
+
+/// Adds an overlay directory or asset pack with a higher priority than all
+/// current asset roots. Files in it override assets with the same specifier
+/// and new files are included in globs.
+///
+/// The asset cache is cleared, so assets loaded before this call are resolved
+/// again the next time they are loaded.
+pub fn add_asset_root(path: PathBuf) -> Result<(), Error> {
+    let mut roots = ASSET_ROOTS.write().unwrap();
+    if !roots.iter().any(|root| root.path() == Some(&path)) {
+        info!("Adding asset overlay path={}", path.display());
+        roots.push(AssetRoot::open(path)?);
+    }
+    drop(roots);
+
+    ASSETS.write().unwrap().clear();
+    Ok(())
+}
+
+/// Adds files received at runtime, keyed by their path relative to the asset
+/// root, with a higher priority than all current asset roots. The asset cache
+/// is cleared like in [`add_asset_root`].
+pub fn add_memory_root(files: HashMap<String, Vec<u8>>) {
+    info!("Adding {} assets from memory", files.len());
+    ASSET_ROOTS
+        .write()
+        .unwrap()
+        .push(AssetRoot::Memory(Arc::new(files)));
+    ASSETS.write().unwrap().clear();
+}
+
+/// Removes all files added with [`add_memory_root`], e.g. when disconnecting
+/// from the server that sent them. The asset cache is cleared like in
+/// [`add_asset_root`].
+pub fn remove_memory_roots() {
+    let mut roots = ASSET_ROOTS.write().unwrap();
+    let count = roots.len();
+    roots.retain(|root| !matches!(root, AssetRoot::Memory(_)));
+    let removed = count - roots.len();
+    drop(roots);
+
+    if removed > 0 {
+        info!("Removed {} asset roots from memory", removed);
+        ASSETS.write().unwrap().clear();
+    }
+}
+
+/// Gameplay assets are everything under `common/`, which is what the shared
+/// game logic (items, recipes, loadouts, ...) is loaded from. These are the
+/// only assets a server may provide to its clients.
+pub const GAMEPLAY_ASSET_DIR: &str = "common";
+
+/// Whether `path` is the path of a gameplay asset relative to the asset root,
+/// like `common/items/food/apple.ron`. Anything which could point outside of
+/// [`GAMEPLAY_ASSET_DIR`] is rejected.
+pub fn is_gameplay_asset_path(path: &str) -> bool {
+    let components = path.split('/').collect::<Vec<_>>();
+    components.len() > 1
+        && components[0] == GAMEPLAY_ASSET_DIR
+        && components.iter().all(|component| {
+            !component.is_empty()
+                && *component != "."
+                && *component != ".."
+                && component
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        })
+}
+
+/// Returns the asset roots in order of increasing priority
+pub fn asset_roots() -> Vec<AssetRoot> { ASSET_ROOTS.read().unwrap().clone() }
+
+/// Reads the raw contents of the file at `path` relative to the asset root,
+/// e.g. `common/items/food/apple.ron`, from the root with the highest priority
+/// that has it
+pub fn read_file(path: &str) -> Result<Vec<u8>, Error> {
+    asset_roots()
+        .iter()
+        .rev()
+        .find_map(|root| root.read(path))
+        .unwrap_or_else(|| Err(io::Error::new(io::ErrorKind::NotFound, path.to_owned())))
+        .map_err(Error::from)
+}
+
+/// The paths of all files in `dir` which are provided by overlays rather than
+/// the base assets, relative to the asset root. The result is sorted and
+/// unique.
+pub fn overlay_files(dir: &str) -> Vec<String> {
+    asset_roots()
+        .iter()
+        .skip(1)
+        .flat_map(|root| root.files_in(dir))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+/// A stable hash of file contents (64 bit FNV-1a), used to check whether two
+/// installations have the same version of an asset
+pub fn content_hash(data: &[u8]) -> u64 {
+    data.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 /// Converts a specifier like "core.backgrounds.city" to
-/// ".../veloren/assets/core/backgrounds/city".
+/// ".../veloren/assets/core/backgrounds/city" in the base asset root.
 fn unpack_specifier(specifier: &str) -> PathBuf {
     let mut path = ASSETS_PATH.clone();
     path.push(specifier.replace(".", "/"));
     path
 }
 
-/// Loads a file based on the specifier and possible extensions
-pub fn load_file(specifier: &str, endings: &[&str]) -> Result<BufReader<File>, Error> {
-    let path = unpack_specifier(specifier);
-    for ending in endings {
-        let mut path = path.clone();
-        path.set_extension(ending);
-
-        trace!(?path, "Trying to access");
-        if let Ok(file) = File::open(path) {
-            return Ok(BufReader::new(file));
-        }
-    }
+/// The paths a specifier could refer to in `root`, one for each extension
+fn candidate_paths<'a>(
+    root: &Path,
+    specifier: &str,
+    endings: &'a [&'a str],
+) -> impl Iterator<Item = PathBuf> + 'a {
+    let path = root.join(specifier.replace(".", "/"));
+    endings
+        .iter()
+        .map(move |ending| path.with_extension(ending))
+}
 
-    Err(Error::NotFound(path.to_string_lossy().into_owned()))
+/// Loads a file based on the specifier and possible extensions, from the asset
+/// root with the highest priority that has it
+pub fn load_file(specifier: &str, endings: &[&str]) -> Result<BufReader<AssetFile>, Error> {
+    asset_roots()
+        .iter()
+        .rev()
+        .find_map(|root| root.open_file(specifier, endings))
+        .map(BufReader::new)
+        .ok_or_else(|| Error::NotFound(unpack_specifier(specifier).to_string_lossy().into_owned()))
 }
 
 /// Loads a file based on the specifier and possible extensions
-pub fn load_file_glob(specifier: &str, endings: &[&str]) -> Result<BufReader<File>, Error> {
-    let path = unpack_specifier(specifier);
-    for ending in endings {
-        let mut path = path.clone();
-        path.set_extension(ending);
-
-        trace!(?path, "Trying to access");
-        if let Ok(file) = File::open(path) {
-            return Ok(BufReader::new(file));
-        }
-    }
-
-    Err(Error::NotFound(path.to_string_lossy().into_owned()))
+pub fn load_file_glob(specifier: &str, endings: &[&str]) -> Result<BufReader<AssetFile>, Error> {
+    load_file(specifier, endings)
 }
 
-/// Read directory from `veloren/assets/*`
+/// Read directory from `veloren/assets/*`, using the asset directory with the
+/// highest priority that contains it. Asset packs and files in memory are not
+/// searched.
 pub fn read_dir(specifier: &str) -> Result<ReadDir, Error> {
-    let dir_name = unpack_specifier(specifier);
-    if dir_name.exists() {
-        Ok(fs::read_dir(dir_name).expect("`read_dir` failed."))
+    asset_roots()
+        .iter()
+        .rev()
+        .filter_map(|root| match root {
+            AssetRoot::Dir(root) => Some(root.join(specifier.replace(".", "/"))),
+            AssetRoot::Pack(_) | AssetRoot::Memory(_) => None,
+        })
+        .find(|dir_name| dir_name.is_dir())
+        .map(|dir_name| fs::read_dir(dir_name).expect("`read_dir` failed."))
+        .ok_or_else(|| Error::NotFound(unpack_specifier(specifier).to_string_lossy().into_owned()))
+}
+
+/// Finds all files matching the provided glob specifier in any asset root,
+/// including files from subdirectories. The returned specifiers are sorted
+/// and unique.
+pub fn get_glob_matches(specifier: &str) -> Result<Vec<String>, Error> {
+    let specifier = specifier.trim_end_matches(".*");
+    let mut matches = BTreeSet::new();
+    let mut found = false;
+    for root in asset_roots() {
+        if !root.has_dir(specifier) {
+            continue;
+        }
+        found = true;
+        matches.extend(
+            root.files_in(&specifier.replace(".", "/"))
+                .iter()
+                .map(|path| {
+                    path.rsplitn(2, '.')
+                        .last()
+                        .unwrap_or(path)
+                        .replace("/", ".")
+                }),
+        );
+    }
+
+    if found {
+        Ok(matches.into_iter().collect())
     } else {
-        Err(Error::NotFound(dir_name.to_string_lossy().into_owned()))
+        Err(Error::NotFound(
+            unpack_specifier(specifier).to_string_lossy().into_owned(),
+        ))
     }
 }
 
-// Finds all files matching the provided glob specifier - includes files from
-// subdirectories
-fn get_glob_matches(specifier: &str) -> Result<Vec<String>, Error> {
-    let specifier = specifier.trim_end_matches(".*");
-    read_dir(specifier).map(|dir| {
-        dir.filter_map(|direntry| {
-            direntry.ok().and_then(|dir_entry| {
-                if dir_entry.path().is_dir() {
-                    let sub_dir_glob = format!(
-                        "{}.{}.*",
-                        specifier.to_string(),
-                        dir_entry.file_name().to_string_lossy()
-                    );
-                    Some(get_glob_matches(&sub_dir_glob).ok()?)
-                } else {
-                    Some(vec![format!(
-                        "{}.{}",
-                        specifier,
-                        dir_entry
-                            .file_name()
-                            .to_string_lossy()
-                            .rsplitn(2, '.')
-                            .last()
-                            .map(|s| s.to_owned())
-                            .unwrap()
-                    )])
-                }
-            })
-        })
-        .flat_map(|x| x)
-        .collect::<Vec<_>>()
-    })
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_gameplay_paths_are_accepted() {
+        assert!(is_gameplay_asset_path("common/items/food/apple.ron"));
+        assert!(!is_gameplay_asset_path("common"));
+        assert!(!is_gameplay_asset_path("voxygen/shaders/figure-frag.glsl"));
+        assert!(!is_gameplay_asset_path("common/../voxygen/i18n/en.ron"));
+        assert!(!is_gameplay_asset_path("/common/items/food/apple.ron"));
+        assert!(!is_gameplay_asset_path("common//items/apple.ron"));
+        assert!(!is_gameplay_asset_path("common\\..\\secret.ron"));
+    }
 }
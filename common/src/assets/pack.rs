@@ -0,0 +1,214 @@
+//! A single file archive of the asset directory
+//!
+//! The layout is a fixed header, followed by the lz4 compressed contents of
+//! every file, followed by a bincode encoded index which maps the relative path
+//! of each file (using `/` as separator, e.g. `common/items/food/apple.ron`) to
+//! the location of its data.
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    fs::{self, File},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+const MAGIC: [u8; 8] = *b"VELOPACK";
+const VERSION: u32 = 1;
+/// Magic, version and index offset
+const HEADER_LEN: u64 = 8 + 4 + 8;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry {
+    offset: u64,
+    len: u64,
+    uncompressed_len: u64,
+}
+
+/// An opened asset pack. Only the index is held in memory, file contents are
+/// read and decompressed on request.
+pub struct AssetPack {
+    path: PathBuf,
+    index: HashMap<String, Entry>,
+    file: Mutex<File>,
+}
+
+impl fmt::Debug for AssetPack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AssetPack")
+            .field("path", &self.path)
+            .field("files", &self.index.len())
+            .finish()
+    }
+}
+
+fn invalid_data<E: fmt::Debug>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", e))
+}
+
+impl AssetPack {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+
+        let mut magic = [0; 8];
+        file.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(invalid_data("Not an asset pack"));
+        }
+        let mut version = [0; 4];
+        file.read_exact(&mut version)?;
+        let version = u32::from_le_bytes(version);
+        if version != VERSION {
+            return Err(invalid_data(format!(
+                "Unsupported asset pack version {}, expected {}",
+                version, VERSION
+            )));
+        }
+        let mut index_offset = [0; 8];
+        file.read_exact(&mut index_offset)?;
+
+        file.seek(SeekFrom::Start(u64::from_le_bytes(index_offset)))?;
+        let index =
+            bincode::deserialize_from(io::BufReader::new(&mut file)).map_err(invalid_data)?;
+
+        Ok(Self {
+            path: path.to_owned(),
+            index,
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path { &self.path }
+
+    pub fn contains(&self, path: &str) -> bool { self.index.contains_key(path) }
+
+    /// Whether any file in the pack is inside the directory `dir`
+    pub fn contains_dir(&self, dir: &str) -> bool {
+        let prefix = format!("{}/", dir);
+        self.index.keys().any(|path| path.starts_with(&prefix))
+    }
+
+    /// The paths of all files inside the directory `dir`, including those in
+    /// subdirectories
+    pub fn files_in<'a>(&'a self, dir: &str) -> impl Iterator<Item = &'a str> + 'a {
+        let prefix = format!("{}/", dir);
+        self.index
+            .keys()
+            .filter(move |path| path.starts_with(&prefix))
+            .map(|path| path.as_str())
+    }
+
+    /// Reads and decompresses a file, returning `None` if it is not in the
+    /// pack
+    pub fn read(&self, path: &str) -> Option<io::Result<Vec<u8>>> {
+        let entry = self.index.get(path)?;
+        Some((|| {
+            let mut compressed = vec![0; entry.len as usize];
+            {
+                let mut file = self.file.lock().unwrap();
+                file.seek(SeekFrom::Start(entry.offset))?;
+                file.read_exact(&mut compressed)?;
+            }
+
+            let mut data = Vec::with_capacity(entry.uncompressed_len as usize);
+            lz_fear::raw::decompress_raw(
+                &compressed,
+                &[0; 0],
+                &mut data,
+                entry.uncompressed_len as usize,
+            )
+            .map_err(invalid_data)?;
+            Ok(data)
+        })())
+    }
+}
+
+/// Packs every file in the directory `root` into a new asset pack at `out`,
+/// returning the number of files written
+pub fn write_pack(root: &Path, out: &Path) -> io::Result<usize> {
+    fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                collect_files(&path, files)?;
+            } else {
+                files.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    let mut files = Vec::new();
+    collect_files(root, &mut files)?;
+    files.sort();
+
+    let mut writer = BufWriter::new(File::create(out)?);
+    writer.write_all(&MAGIC)?;
+    writer.write_all(&VERSION.to_le_bytes())?;
+    // Placeholder for the index offset, written once the data is in place
+    writer.write_all(&0u64.to_le_bytes())?;
+
+    let mut index = HashMap::new();
+    let mut offset = HEADER_LEN;
+    for path in &files {
+        let relative = path
+            .strip_prefix(root)
+            .map_err(invalid_data)?
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let data = fs::read(path)?;
+        let mut compressed = Vec::with_capacity(data.len() / 2 + 16);
+        let mut table = lz_fear::raw::U32Table::default();
+        lz_fear::raw::compress2(&data, 0, &mut table, &mut compressed).map_err(invalid_data)?;
+        writer.write_all(&compressed)?;
+
+        index.insert(relative, Entry {
+            offset,
+            len: compressed.len() as u64,
+            uncompressed_len: data.len() as u64,
+        });
+        offset += compressed.len() as u64;
+    }
+
+    bincode::serialize_into(&mut writer, &index).map_err(invalid_data)?;
+    writer.seek(SeekFrom::Start(HEADER_LEN - 8))?;
+    writer.write_all(&offset.to_le_bytes())?;
+    writer.flush()?;
+
+    Ok(files.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("veloren-pack-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("assets/common/items")).unwrap();
+        fs::write(dir.join("assets/common/items/apple.ron"), b"apple").unwrap();
+        fs::write(dir.join("assets/common/recipe_book.ron"), vec![7; 4096]).unwrap();
+
+        let out = dir.join("assets.pack");
+        assert_eq!(write_pack(&dir.join("assets"), &out).unwrap(), 2);
+
+        let pack = AssetPack::open(&out).unwrap();
+        assert_eq!(
+            pack.read("common/items/apple.ron").unwrap().unwrap(),
+            b"apple"
+        );
+        assert_eq!(
+            pack.read("common/recipe_book.ron").unwrap().unwrap(),
+            vec![7; 4096]
+        );
+        assert!(pack.read("common/items/pear.ron").is_none());
+        assert!(pack.contains_dir("common/items"));
+        assert_eq!(pack.files_in("common").count(), 2);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
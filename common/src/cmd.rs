@@ -3,7 +3,6 @@ use lazy_static::lazy_static;
 use std::{
     collections::HashMap,
     fmt::{self, Display},
-    path::Path,
     str::FromStr,
 };
 use tracing::warn;
@@ -40,35 +39,42 @@ pub enum ChatCommand {
     Ban,
     Build,
     Campfire,
+    CreateFaction,
     Debug,
     DebugColumn,
     Dummy,
     Explosion,
     Faction,
+    FactionInvite,
+    FactionKick,
+    FactionRank,
     GiveExp,
     GiveItem,
     Goto,
     Group,
     Health,
     Help,
-    JoinFaction,
     Jump,
     Kick,
     Kill,
     KillNpcs,
     Lantern,
+    LeaveFaction,
     Light,
     MakeBlock,
     MakeSprite,
     Motd,
+    NameItem,
     Object,
     Players,
+    Pvp,
     Region,
     RemoveLights,
     Say,
     SetLevel,
     SetMotd,
     Spawn,
+    Structure,
     Sudo,
     Tell,
     Time,
@@ -87,35 +93,42 @@ pub static CHAT_COMMANDS: &[ChatCommand] = &[
     ChatCommand::Ban,
     ChatCommand::Build,
     ChatCommand::Campfire,
+    ChatCommand::CreateFaction,
     ChatCommand::Debug,
     ChatCommand::DebugColumn,
     ChatCommand::Dummy,
     ChatCommand::Explosion,
     ChatCommand::Faction,
+    ChatCommand::FactionInvite,
+    ChatCommand::FactionKick,
+    ChatCommand::FactionRank,
     ChatCommand::GiveExp,
     ChatCommand::GiveItem,
     ChatCommand::Goto,
     ChatCommand::Group,
     ChatCommand::Health,
     ChatCommand::Help,
-    ChatCommand::JoinFaction,
     ChatCommand::Jump,
     ChatCommand::Kick,
     ChatCommand::Kill,
     ChatCommand::KillNpcs,
     ChatCommand::Lantern,
+    ChatCommand::LeaveFaction,
     ChatCommand::Light,
     ChatCommand::MakeBlock,
     ChatCommand::MakeSprite,
     ChatCommand::Motd,
+    ChatCommand::NameItem,
     ChatCommand::Object,
     ChatCommand::Players,
+    ChatCommand::Pvp,
     ChatCommand::Region,
     ChatCommand::RemoveLights,
     ChatCommand::Say,
     ChatCommand::SetLevel,
     ChatCommand::SetMotd,
     ChatCommand::Spawn,
+    ChatCommand::Structure,
     ChatCommand::Sudo,
     ChatCommand::Tell,
     ChatCommand::Time,
@@ -152,6 +165,11 @@ lazy_static! {
         .iter()
         .map(|o| o.to_string().to_string())
         .collect();
+    static ref FACTION_RANKS: Vec<String> = vec!["member", "officer", "leader"]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
     static ref TIMES: Vec<String> = vec![
         "midnight", "night", "dawn", "morning", "day", "noon", "dusk"
     ]
@@ -171,25 +189,11 @@ lazy_static! {
 
     /// List of item specifiers. Useful for tab completing
     static ref ITEM_SPECS: Vec<String> = {
-        let path = assets::ASSETS_PATH.join("common").join("items");
-        let mut items = vec![];
-        fn list_items (path: &Path, base: &Path, mut items: &mut Vec<String>) -> std::io::Result<()>{
-            for entry in std::fs::read_dir(path)? {
-                let path = entry?.path();
-                if path.is_dir(){
-                    list_items(&path, &base, &mut items)?;
-                } else if let Ok(path) = path.strip_prefix(base) {
-                    let path = path.to_string_lossy().trim_end_matches(".ron").replace('/', ".");
-                    items.push(path);
-                }
-            }
-            Ok(())
-        }
-        if list_items(&path, &assets::ASSETS_PATH, &mut items).is_err() {
-            warn!("There was a problem listing item assets");
-        }
-        items.sort();
-        items
+        // Includes items added by asset overlays
+        assets::get_glob_matches("common.items.*").unwrap_or_else(|e| {
+            warn!(?e, "There was a problem listing item assets");
+            Vec::new()
+        })
     };
 }
 
@@ -213,6 +217,11 @@ impl ChatCommand {
             ),
             ChatCommand::Build => cmd(vec![], "Toggles build mode on and off", Admin),
             ChatCommand::Campfire => cmd(vec![], "Spawns a campfire", Admin),
+            ChatCommand::CreateFaction => cmd(
+                vec![Any("name", Required)],
+                "Create a new faction and become its leader",
+                NoAdmin,
+            ),
             ChatCommand::Debug => cmd(vec![], "Place all debug items into your pack.", Admin),
             ChatCommand::DebugColumn => cmd(
                 vec![Integer("x", 15000, Required), Integer("y", 15000, Required)],
@@ -230,6 +239,24 @@ impl ChatCommand {
                 "Send messages to your faction",
                 NoAdmin,
             ),
+            ChatCommand::FactionInvite => cmd(
+                vec![PlayerName(Required)],
+                "Invite a player to your faction",
+                NoAdmin,
+            ),
+            ChatCommand::FactionKick => cmd(
+                vec![PlayerName(Required)],
+                "Remove a member from your faction, by player or character name",
+                NoAdmin,
+            ),
+            ChatCommand::FactionRank => cmd(
+                vec![
+                    PlayerName(Required),
+                    Enum("rank", FACTION_RANKS.clone(), Required),
+                ],
+                "Change the rank of a member of your faction, by player or character name",
+                NoAdmin,
+            ),
             ChatCommand::GiveExp => cmd(
                 vec![Integer("amount", 50, Required)],
                 "Give experience to yourself",
@@ -267,11 +294,6 @@ impl ChatCommand {
                 "Display information about commands",
                 NoAdmin,
             ),
-            ChatCommand::JoinFaction => ChatCommandData::new(
-                vec![Any("faction", Optional)],
-                "Join/leave the specified faction",
-                NoAdmin,
-            ),
             ChatCommand::Jump => cmd(
                 vec![
                     Float("x", 0.0, Required),
@@ -298,6 +320,7 @@ impl ChatCommand {
                 "Change your lantern's strength and color",
                 Admin,
             ),
+            ChatCommand::LeaveFaction => cmd(vec![], "Leave your faction", NoAdmin),
             ChatCommand::Light => cmd(
                 vec![
                     Float("r", 1.0, Optional),
@@ -326,12 +349,22 @@ impl ChatCommand {
                 "View the server description",
                 NoAdmin,
             ),
+            ChatCommand::NameItem => cmd(
+                vec![Message(Optional)],
+                "Name the item you are holding, or remove its name",
+                NoAdmin,
+            ),
             ChatCommand::Object => cmd(
                 vec![Enum("object", OBJECTS.clone(), Required)],
                 "Spawn an object",
                 Admin,
             ),
             ChatCommand::Players => cmd(vec![], "Lists players currently online", NoAdmin),
+            ChatCommand::Pvp => cmd(
+                vec![],
+                "Toggles whether you take part in PvP, on servers where players opt in to it",
+                NoAdmin,
+            ),
             ChatCommand::RemoveLights => cmd(
                 vec![Float("radius", 20.0, Optional)],
                 "Removes all lights spawned by players",
@@ -365,6 +398,25 @@ impl ChatCommand {
                 "Spawn a test entity",
                 Admin,
             ),
+            ChatCommand::Structure => cmd(
+                vec![
+                    Enum(
+                        "action",
+                        vec!["save".to_owned(), "place".to_owned()],
+                        Required,
+                    ),
+                    Any("name", Required),
+                    Integer("x", 0, Optional),
+                    Integer("y", 0, Optional),
+                    Integer("z", 0, Optional),
+                    Integer("x2", 0, Optional),
+                    Integer("y2", 0, Optional),
+                    Integer("z2", 0, Optional),
+                ],
+                "Save the blocks between two corners as a structure, or place a saved structure \
+                 at a position (defaults to your own)",
+                Admin,
+            ),
             ChatCommand::Sudo => cmd(
                 vec![PlayerName(Required), SubCommand],
                 "Run command as if you were another player",
@@ -415,35 +467,42 @@ impl ChatCommand {
             ChatCommand::Ban => "ban",
             ChatCommand::Build => "build",
             ChatCommand::Campfire => "campfire",
+            ChatCommand::CreateFaction => "create_faction",
             ChatCommand::Debug => "debug",
             ChatCommand::DebugColumn => "debug_column",
             ChatCommand::Dummy => "dummy",
             ChatCommand::Explosion => "explosion",
             ChatCommand::Faction => "faction",
+            ChatCommand::FactionInvite => "faction_invite",
+            ChatCommand::FactionKick => "faction_kick",
+            ChatCommand::FactionRank => "faction_rank",
             ChatCommand::GiveExp => "give_exp",
             ChatCommand::GiveItem => "give_item",
             ChatCommand::Goto => "goto",
             ChatCommand::Group => "group",
             ChatCommand::Health => "health",
-            ChatCommand::JoinFaction => "join_faction",
             ChatCommand::Help => "help",
             ChatCommand::Jump => "jump",
             ChatCommand::Kick => "kick",
             ChatCommand::Kill => "kill",
             ChatCommand::KillNpcs => "kill_npcs",
             ChatCommand::Lantern => "lantern",
+            ChatCommand::LeaveFaction => "leave_faction",
             ChatCommand::Light => "light",
             ChatCommand::MakeBlock => "make_block",
             ChatCommand::MakeSprite => "make_sprite",
             ChatCommand::Motd => "motd",
+            ChatCommand::NameItem => "name_item",
             ChatCommand::Object => "object",
             ChatCommand::Players => "players",
+            ChatCommand::Pvp => "pvp",
             ChatCommand::Region => "region",
             ChatCommand::RemoveLights => "remove_lights",
             ChatCommand::Say => "say",
             ChatCommand::SetLevel => "set_level",
             ChatCommand::SetMotd => "set_motd",
             ChatCommand::Spawn => "spawn",
+            ChatCommand::Structure => "structure",
             ChatCommand::Sudo => "sudo",
             ChatCommand::Tell => "tell",
             ChatCommand::Time => "time",
@@ -1,7 +1,10 @@
 use crate::{
     comp::{
-        item::{armor::Protection, Item, ItemKind},
-        Body, CharacterState, EnergySource, Gravity, LightEmitter, Projectile, StateUpdate,
+        item::{armor::Protection, Item, ItemKind, ToolKind},
+        projectile,
+        skills::{Skill, SkillGroupType, SkillSet},
+        Body, CharacterState, Element, EnergySource, Gravity, LightEmitter, Projectile,
+        StateUpdate,
     },
     states::{
         utils::{AbilityKey, StageSection},
@@ -206,6 +209,10 @@ pub enum CharacterAbility {
         energy_cost: u32,
         energy_drain: u32,
     },
+    Mine {
+        buildup_duration: Duration,
+        recover_duration: Duration,
+    },
 }
 
 impl CharacterAbility {
@@ -263,6 +270,210 @@ impl CharacterAbility {
     }
 }
 
+impl CharacterAbility {
+    /// The ability as a character with the given skills uses it with a tool of
+    /// the given kind
+    pub fn adjusted_by_skills(mut self, tool: &ToolKind, skill_set: &SkillSet) -> Self {
+        use Skill::*;
+
+        let group = SkillGroupType::for_tool(tool);
+        let has = |skill| skill_set.has_skill(skill);
+        let in_group = |skill_group, skill| group == Some(skill_group) && has(skill);
+
+        let mut damage = if has(GeneralDamage) { 1.1 } else { 1.0 };
+        let mut energy_cost = if has(GeneralEnergyCost) { 0.9 } else { 1.0 };
+        let (damage_skill, energy_cost_skill) = match group {
+            Some(SkillGroupType::Swords) => (Some(SwordDamage), Some(SwordEnergyCost)),
+            Some(SkillGroupType::Axes) => (Some(AxeDamage), Some(AxeEnergyCost)),
+            Some(SkillGroupType::Hammers) => (Some(HammerDamage), Some(HammerEnergyCost)),
+            Some(SkillGroupType::Bows) => (Some(BowDamage), Some(BowEnergyCost)),
+            Some(SkillGroupType::Staffs) => (Some(StaffDamage), Some(StaffEnergyCost)),
+            Some(SkillGroupType::Sceptres) => (None, Some(SceptreEnergyCost)),
+            Some(SkillGroupType::General) | None => (None, None),
+        };
+        if damage_skill.map_or(false, has) {
+            damage *= 1.2;
+        }
+        if energy_cost_skill.map_or(false, has) {
+            energy_cost *= 0.75;
+        }
+        let healing = if in_group(SkillGroupType::Sceptres, SceptreHealing) {
+            1.2
+        } else {
+            1.0
+        };
+        self.scale(damage, healing, energy_cost);
+
+        let extra_combo_stage = in_group(SkillGroupType::Swords, SwordComboStage);
+        let extra_spin = in_group(SkillGroupType::Axes, AxeExtraSpin);
+        let faster_charge = in_group(SkillGroupType::Hammers, HammerChargeSpeed);
+        let more_arrows = in_group(SkillGroupType::Bows, BowRepeaterArrows);
+        let larger_explosions = in_group(SkillGroupType::Staffs, StaffExplosionRadius);
+        let longer_beam = in_group(SkillGroupType::Sceptres, SceptreBeamRange);
+        match &mut self {
+            CharacterAbility::ComboMelee { stage_data, .. } if extra_combo_stage => {
+                // A faster, harder hitting repeat of the last stage
+                if let Some(last) = stage_data.last().cloned() {
+                    stage_data.push(combo_melee::Stage {
+                        stage: last.stage + 1,
+                        base_damage: last.base_damage * 5 / 4,
+                        max_damage: last.max_damage * 5 / 4,
+                        base_buildup_duration: last.base_buildup_duration * 4 / 5,
+                        ..last
+                    });
+                }
+            },
+            CharacterAbility::SpinMelee { num_spins, .. } if extra_spin => {
+                *num_spins += 1;
+            },
+            CharacterAbility::ChargedMelee {
+                charge_duration, ..
+            } if faster_charge => {
+                *charge_duration = *charge_duration * 7 / 10;
+            },
+            CharacterAbility::RepeaterRanged { reps_remaining, .. } if more_arrows => {
+                *reps_remaining += 2;
+            },
+            CharacterAbility::BasicRanged { projectile, .. } if larger_explosions => {
+                for effect in projectile
+                    .hit_solid
+                    .iter_mut()
+                    .chain(projectile.hit_entity.iter_mut())
+                {
+                    if let projectile::Effect::Explode(explosion) = effect {
+                        explosion.radius *= 1.3;
+                    }
+                }
+            },
+            CharacterAbility::BasicBeam { range, .. } if longer_beam => {
+                *range *= 1.2;
+            },
+            _ => {},
+        }
+        self
+    }
+
+    /// Multiplies the damage, healing and energy cost of the ability
+    fn scale(&mut self, damage: f32, healing: f32, energy_cost: f32) {
+        let scale = |value: &mut u32, by: f32| *value = (*value as f32 * by) as u32;
+        let scale_projectile = |projectile: &mut Projectile| {
+            for effect in projectile
+                .hit_solid
+                .iter_mut()
+                .chain(projectile.hit_entity.iter_mut())
+            {
+                match effect {
+                    projectile::Effect::Damage(healthchange) => {
+                        *healthchange = (*healthchange as f32 * damage) as i32;
+                    },
+                    projectile::Effect::Explode(explosion) => {
+                        scale(&mut explosion.max_damage, damage);
+                        scale(&mut explosion.min_damage, damage);
+                        scale(&mut explosion.max_heal, healing);
+                        scale(&mut explosion.min_heal, healing);
+                    },
+                    _ => {},
+                }
+            }
+        };
+        match self {
+            CharacterAbility::BasicMelee {
+                energy_cost: cost,
+                base_healthchange,
+                ..
+            } => {
+                scale(cost, energy_cost);
+                *base_healthchange = (*base_healthchange as f32 * damage) as i32;
+            },
+            CharacterAbility::BasicRanged {
+                energy_cost: cost,
+                projectile,
+                ..
+            }
+            | CharacterAbility::RepeaterRanged {
+                energy_cost: cost,
+                projectile,
+                ..
+            } => {
+                scale(cost, energy_cost);
+                scale_projectile(projectile);
+            },
+            CharacterAbility::DashMelee {
+                energy_cost: cost,
+                energy_drain,
+                base_damage,
+                max_damage,
+                ..
+            } => {
+                scale(cost, energy_cost);
+                scale(energy_drain, energy_cost);
+                scale(base_damage, damage);
+                scale(max_damage, damage);
+            },
+            CharacterAbility::ComboMelee { stage_data, .. } => {
+                for stage in stage_data {
+                    scale(&mut stage.base_damage, damage);
+                    scale(&mut stage.max_damage, damage);
+                    scale(&mut stage.damage_increase, damage);
+                }
+            },
+            CharacterAbility::LeapMelee {
+                energy_cost: cost,
+                base_damage,
+                ..
+            }
+            | CharacterAbility::SpinMelee {
+                energy_cost: cost,
+                base_damage,
+                ..
+            }
+            | CharacterAbility::Shockwave {
+                energy_cost: cost,
+                damage: base_damage,
+                ..
+            } => {
+                scale(cost, energy_cost);
+                scale(base_damage, damage);
+            },
+            CharacterAbility::ChargedMelee {
+                energy_cost: cost,
+                energy_drain,
+                initial_damage,
+                max_damage,
+                ..
+            }
+            | CharacterAbility::ChargedRanged {
+                energy_cost: cost,
+                energy_drain,
+                initial_damage,
+                max_damage,
+                ..
+            } => {
+                scale(cost, energy_cost);
+                scale(energy_drain, energy_cost);
+                scale(initial_damage, damage);
+                scale(max_damage, damage);
+            },
+            CharacterAbility::BasicBeam {
+                energy_cost: cost,
+                energy_drain,
+                base_dps,
+                base_hps,
+                ..
+            } => {
+                scale(cost, energy_cost);
+                scale(energy_drain, energy_cost);
+                scale(base_dps, damage);
+                scale(base_hps, healing);
+            },
+            CharacterAbility::Boost { .. }
+            | CharacterAbility::BasicBlock
+            | CharacterAbility::Roll
+            | CharacterAbility::Mine { .. } => {},
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct ItemConfig {
     pub item: Item,
@@ -275,7 +486,7 @@ pub struct ItemConfig {
 
 impl From<Item> for ItemConfig {
     fn from(item: Item) -> Self {
-        if let ItemKind::Tool(tool) = &item.kind() {
+        if let Some(tool) = item.tool() {
             let mut abilities = tool.get_abilities();
             let mut ability_drain = abilities.drain(..);
 
@@ -295,6 +506,7 @@ impl From<Item> for ItemConfig {
 
 #[derive(Arraygen, Clone, PartialEq, Default, Debug, Serialize, Deserialize)]
 #[gen_array(pub fn get_armor: &Option<Item>)]
+#[gen_array(pub fn get_armor_mut: &mut Option<Item>)]
 pub struct Loadout {
     pub active_item: Option<ItemConfig>,
     pub second_item: Option<ItemConfig>,
@@ -302,27 +514,27 @@ pub struct Loadout {
     pub lantern: Option<Item>,
     pub glider: Option<Item>,
 
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub shoulder: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub chest: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub belt: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub hand: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub pants: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub foot: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub back: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub ring: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub neck: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub head: Option<Item>,
-    #[in_array(get_armor)]
+    #[in_array(get_armor, get_armor_mut)]
     pub tabard: Option<Item>,
 }
 
@@ -332,13 +544,7 @@ impl Loadout {
             .get_armor()
             .iter()
             .flat_map(|armor| armor.as_ref())
-            .filter_map(|item| {
-                if let ItemKind::Armor(armor) = &item.kind() {
-                    Some(armor.get_protection())
-                } else {
-                    None
-                }
-            })
+            .filter_map(|item| item.protection())
             .map(|protection| match protection {
                 Protection::Normal(protection) => Some(protection),
                 Protection::Invincible => None,
@@ -349,6 +555,75 @@ impl Loadout {
             None => 1.0,
         }
     }
+
+    /// Fraction of damage of the given element that is resisted, on the same
+    /// curve as `get_damage_reduction`
+    pub fn get_resistance(&self, element: Element) -> f32 {
+        let resistance = self
+            .get_armor()
+            .iter()
+            .flat_map(|armor| armor.as_ref())
+            .filter_map(|item| item.resistances())
+            .map(|resistances| resistances.get(element))
+            .sum::<f32>();
+        resistance / (60.0 + resistance.abs())
+    }
+
+    /// Element of the damage dealt with the active item, if any
+    pub fn active_element(&self) -> Option<Element> {
+        self.active_item
+            .as_ref()
+            .and_then(|item_config| match item_config.item.kind() {
+                ItemKind::Tool(tool) => tool.element(),
+                _ => None,
+            })
+    }
+
+    /// Wear down every equipped piece of armor
+    pub fn wear_armor(&mut self, amount: u32) {
+        for item in self
+            .get_armor_mut()
+            .iter_mut()
+            .flat_map(|armor| armor.as_mut())
+        {
+            item.wear(amount);
+        }
+    }
+
+    /// Wear down the active item, returning `true` if this broke it
+    pub fn wear_active_item(&mut self, amount: u32) -> bool {
+        match &mut self.active_item {
+            Some(item_config) if item_config.item.wear(amount) => {
+                // Broken tools are weaker, so their abilities need to be rebuilt
+                *item_config = ItemConfig::from(item_config.item.clone());
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Repair every equipped item, returning `true` if anything was repaired
+    pub fn repair_all(&mut self) -> bool {
+        let mut repaired = false;
+        for item_config in self
+            .active_item
+            .iter_mut()
+            .chain(self.second_item.iter_mut())
+        {
+            if item_config.item.repair() {
+                *item_config = ItemConfig::from(item_config.item.clone());
+                repaired = true;
+            }
+        }
+        for item in self
+            .get_armor_mut()
+            .iter_mut()
+            .flat_map(|armor| armor.as_mut())
+        {
+            repaired |= item.repair();
+        }
+        repaired
+    }
 }
 
 impl From<(&CharacterAbility, AbilityKey)> for CharacterState {
@@ -685,6 +960,17 @@ impl From<(&CharacterAbility, AbilityKey)> for CharacterState {
                 particle_ori: None::<Vec3<f32>>,
                 offset: 0.0,
             }),
+            CharacterAbility::Mine {
+                buildup_duration,
+                recover_duration,
+            } => CharacterState::Mining(mining::Data {
+                static_data: mining::StaticData {
+                    buildup_duration: *buildup_duration,
+                    recover_duration: *recover_duration,
+                },
+                timer: Duration::default(),
+                stage_section: StageSection::Buildup,
+            }),
         }
     }
 }
@@ -133,9 +133,39 @@ impl<'a> From<&'a Body> for Psyche {
     }
 }
 
+/// A looping route that an agent walks along while it has nothing better to
+/// do, such as a guard's beat along a castle wall.
+#[derive(Clone, Debug, Default)]
+pub struct Patrol {
+    pub route: Vec<Vec3<f32>>,
+    /// Index of the waypoint currently being walked towards
+    pub next: usize,
+    pub chaser: Chaser,
+}
+
+impl Patrol {
+    pub fn new(route: Vec<Vec3<f32>>) -> Self {
+        Self {
+            route,
+            ..Default::default()
+        }
+    }
+
+    /// The waypoint currently being walked towards, if the route isn't empty.
+    pub fn waypoint(&self) -> Option<Vec3<f32>> {
+        self.route.get(self.next % self.route.len().max(1)).copied()
+    }
+
+    /// Move on to the next waypoint, looping back to the start of the route.
+    pub fn advance(&mut self) { self.next = (self.next + 1) % self.route.len().max(1); }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Agent {
     pub patrol_origin: Option<Vec3<f32>>,
+    /// Guards and other agents with a patrol walk their route when idle and
+    /// respond to alarms raised nearby.
+    pub patrol: Option<Patrol>,
     pub activity: Activity,
     /// Does the agent talk when e.g. hit by the player
     // TODO move speech patterns into a Behavior component
@@ -149,6 +179,11 @@ impl Agent {
         self
     }
 
+    pub fn with_patrol_route(mut self, route: Vec<Vec3<f32>>) -> Self {
+        self.patrol = Some(Patrol::new(route));
+        self
+    }
+
     pub fn new(origin: Vec3<f32>, can_speak: bool, body: &Body) -> Self {
         let patrol_origin = Some(origin);
         Agent {
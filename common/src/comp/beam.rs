@@ -1,4 +1,4 @@
-use crate::sync::Uid;
+use crate::{comp::Element, sync::Uid};
 use serde::{Deserialize, Serialize};
 use specs::{Component, FlaggedStorage};
 use specs_idvs::IdvStorage;
@@ -15,6 +15,7 @@ pub struct Properties {
     pub energy_cost: u32,
     pub duration: Duration,
     pub owner: Option<Uid>,
+    pub element: Option<Element>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
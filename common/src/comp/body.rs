@@ -13,14 +13,14 @@ pub mod quadruped_small;
 pub mod theropod;
 
 use crate::{
-    assets::{self, Asset},
+    assets::{self, Asset, AssetFile},
     make_case_elim,
     npc::NpcKind,
 };
 use serde::{Deserialize, Serialize};
 use specs::{Component, FlaggedStorage};
 use specs_idvs::IdvStorage;
-use std::{fs::File, io::BufReader};
+use std::io::BufReader;
 use vek::*;
 
 make_case_elim!(
@@ -127,7 +127,7 @@ impl<
 {
     const ENDINGS: &'static [&'static str] = &["json"];
 
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
         serde_json::de::from_reader(buf_reader).map_err(assets::Error::parse_error)
     }
 }
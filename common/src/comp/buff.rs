@@ -17,6 +17,43 @@ pub enum BuffKind {
     /// Prefixes an entity's name with "Cursed"
     /// Currently placeholder buff to show other stuff is possible
     Cursed,
+    /// Increases movement speed
+    Haste,
+    /// Decreases movement speed
+    Slowed,
+    /// Increases damage dealt
+    Empowered,
+    /// Decreases damage dealt
+    Weakened,
+    /// Decreases damage taken
+    Fortified,
+    /// Increases damage taken
+    Vulnerable,
+    /// Increases energy regeneration
+    Invigorated,
+    /// Prevents moving and using abilities
+    Stunned,
+    /// Prevents moving
+    Rooted,
+    /// Prevents using abilities
+    Silenced,
+    /// Lowers health over time, applied by fire damage
+    Burning,
+    /// Decreases movement speed, applied by frost damage
+    Chilled,
+    /// Lowers health over time, applied by poison damage
+    Poisoned,
+}
+
+/// How a new buff interacts with buffs of the same kind already applied
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BuffStacking {
+    /// Only the strongest buff of the kind takes effect
+    Strongest,
+    /// Every buff of the kind takes effect
+    Stack,
+    /// The new buff replaces the ones already applied
+    Refresh,
 }
 
 impl BuffKind {
@@ -26,12 +63,33 @@ impl BuffKind {
             BuffKind::Regeneration { .. } => true,
             BuffKind::Bleeding { .. } => false,
             BuffKind::Cursed { .. } => false,
+            BuffKind::Haste => true,
+            BuffKind::Slowed => false,
+            BuffKind::Empowered => true,
+            BuffKind::Weakened => false,
+            BuffKind::Fortified => true,
+            BuffKind::Vulnerable => false,
+            BuffKind::Invigorated => true,
+            BuffKind::Stunned => false,
+            BuffKind::Rooted => false,
+            BuffKind::Silenced => false,
+            BuffKind::Burning => false,
+            BuffKind::Chilled => false,
+            BuffKind::Poisoned => false,
+        }
+    }
+
+    pub fn stacking(self) -> BuffStacking {
+        match self {
+            BuffKind::Bleeding => BuffStacking::Stack,
+            BuffKind::Stunned | BuffKind::Rooted | BuffKind::Silenced => BuffStacking::Refresh,
+            _ => BuffStacking::Strongest,
         }
     }
 }
 
 // Struct used to store data relevant to a buff
-#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct BuffData {
     pub strength: f32,
     pub duration: Option<Duration>,
@@ -55,6 +113,17 @@ pub enum ModifierKind {
     Multiplicative,
 }
 
+/// Ways a de/buff can stop an entity from acting
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum CrowdControl {
+    /// No moving and no abilities
+    Stun,
+    /// No moving
+    Root,
+    /// No abilities
+    Silence,
+}
+
 /// Data indicating and configuring behaviour of a de/buff.
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum BuffEffect {
@@ -62,6 +131,16 @@ pub enum BuffEffect {
     HealthChangeOverTime { rate: f32, accumulated: f32 },
     /// Changes maximum health by a certain amount
     MaxHealthModifier { value: f32, kind: ModifierKind },
+    /// Multiplies movement speed
+    MovementSpeed(f32),
+    /// Multiplies damage dealt to others
+    DamageDealt(f32),
+    /// Multiplies damage taken from any source
+    DamageTaken(f32),
+    /// Multiplies energy regeneration
+    EnergyRegen(f32),
+    /// Stops the entity from moving and/or using abilities
+    CrowdControl(CrowdControl),
 }
 
 /// Actual de/buff.
@@ -116,7 +195,7 @@ impl Buff {
         source: BuffSource,
     ) -> Self {
         let (effects, time) = match kind {
-            BuffKind::Bleeding => (
+            BuffKind::Bleeding | BuffKind::Burning | BuffKind::Poisoned => (
                 vec![BuffEffect::HealthChangeOverTime {
                     rate: -data.strength,
                     accumulated: 0.0,
@@ -137,6 +216,46 @@ impl Buff {
                 }],
                 data.duration,
             ),
+            BuffKind::Haste => (
+                vec![BuffEffect::MovementSpeed(1.0 + data.strength)],
+                data.duration,
+            ),
+            BuffKind::Slowed | BuffKind::Chilled => (
+                vec![BuffEffect::MovementSpeed((1.0 - data.strength).max(0.0))],
+                data.duration,
+            ),
+            BuffKind::Empowered => (
+                vec![BuffEffect::DamageDealt(1.0 + data.strength)],
+                data.duration,
+            ),
+            BuffKind::Weakened => (
+                vec![BuffEffect::DamageDealt((1.0 - data.strength).max(0.0))],
+                data.duration,
+            ),
+            BuffKind::Fortified => (
+                vec![BuffEffect::DamageTaken((1.0 - data.strength).max(0.0))],
+                data.duration,
+            ),
+            BuffKind::Vulnerable => (
+                vec![BuffEffect::DamageTaken(1.0 + data.strength)],
+                data.duration,
+            ),
+            BuffKind::Invigorated => (
+                vec![BuffEffect::EnergyRegen(1.0 + data.strength)],
+                data.duration,
+            ),
+            BuffKind::Stunned => (
+                vec![BuffEffect::CrowdControl(CrowdControl::Stun)],
+                data.duration,
+            ),
+            BuffKind::Rooted => (
+                vec![BuffEffect::CrowdControl(CrowdControl::Root)],
+                data.duration,
+            ),
+            BuffKind::Silenced => (
+                vec![BuffEffect::CrowdControl(CrowdControl::Silence)],
+                data.duration,
+            ),
         };
         Buff {
             kind,
@@ -248,6 +367,9 @@ impl Buffs {
     }
 
     pub fn insert(&mut self, buff: Buff) -> BuffId {
+        if buff.kind.stacking() == BuffStacking::Refresh {
+            self.remove_kind(buff.kind);
+        }
         self.id_counter += 1;
         self.force_insert(self.id_counter, buff)
     }
@@ -261,24 +383,89 @@ impl Buffs {
             .map(move |id| (*id, &self.buffs[id]))
     }
 
-    // Iterates through all active buffs (the most powerful buff of each kind)
+    // Iterates through all active buffs (the most powerful buff of each kind, or
+    // every buff of kinds that stack)
     pub fn iter_active(&self) -> impl Iterator<Item = &Buff> + '_ {
         self.kinds
-            .values()
-            .map(move |ids| self.buffs.get(&ids[0]))
-            .filter(|buff| buff.is_some())
-            .map(|buff| buff.unwrap())
+            .iter()
+            .flat_map(move |(kind, ids)| active_ids(*kind, ids))
+            .filter_map(move |id| self.buffs.get(id))
+    }
+
+    /// Product of the multipliers `f` picks out of the active buffs' effects
+    fn modifier(&self, f: impl Fn(&BuffEffect) -> Option<f32>) -> f32 {
+        self.iter_active()
+            .flat_map(|buff| buff.effects.iter())
+            .filter_map(f)
+            .product()
+    }
+
+    pub fn movement_speed_modifier(&self) -> f32 {
+        self.modifier(|effect| match effect {
+            BuffEffect::MovementSpeed(m) => Some(*m),
+            _ => None,
+        })
+    }
+
+    pub fn damage_dealt_modifier(&self) -> f32 {
+        self.modifier(|effect| match effect {
+            BuffEffect::DamageDealt(m) => Some(*m),
+            _ => None,
+        })
+    }
+
+    pub fn damage_taken_modifier(&self) -> f32 {
+        self.modifier(|effect| match effect {
+            BuffEffect::DamageTaken(m) => Some(*m),
+            _ => None,
+        })
+    }
+
+    pub fn energy_regen_modifier(&self) -> f32 {
+        self.modifier(|effect| match effect {
+            BuffEffect::EnergyRegen(m) => Some(*m),
+            _ => None,
+        })
+    }
+
+    /// Whether an active buff applies this kind of crowd control
+    pub fn has_crowd_control(&self, cc: CrowdControl) -> bool {
+        self.iter_active()
+            .flat_map(|buff| buff.effects.iter())
+            .any(|effect| matches!(effect, BuffEffect::CrowdControl(c) if *c == cc))
+    }
+
+    /// Neither stunned nor rooted
+    pub fn can_move(&self) -> bool {
+        !self.has_crowd_control(CrowdControl::Stun) && !self.has_crowd_control(CrowdControl::Root)
+    }
+
+    /// Neither stunned nor silenced
+    pub fn can_use_abilities(&self) -> bool {
+        !self.has_crowd_control(CrowdControl::Stun)
+            && !self.has_crowd_control(CrowdControl::Silence)
     }
 
     // Gets most powerful buff of a given kind
     // pub fn get_active_kind(&self, kind: BuffKind) -> Buff
 
     pub fn remove(&mut self, buff_id: BuffId) {
-        let kind = self.buffs.remove(&buff_id).unwrap().kind;
-        self.kinds
-            .get_mut(&kind)
-            .map(|ids| ids.retain(|id| *id != buff_id));
-        self.sort_kind(kind);
+        // The buff may already be gone, e.g. if it expired right after being refreshed
+        if let Some(kind) = self.buffs.remove(&buff_id).map(|buff| buff.kind) {
+            self.kinds
+                .get_mut(&kind)
+                .map(|ids| ids.retain(|id| *id != buff_id));
+            self.sort_kind(kind);
+        }
+    }
+}
+
+/// The buffs of a kind that take effect, out of all buffs of the kind sorted
+/// strongest first
+pub fn active_ids(kind: BuffKind, ids: &[BuffId]) -> &[BuffId] {
+    match kind.stacking() {
+        BuffStacking::Stack => ids,
+        BuffStacking::Strongest | BuffStacking::Refresh => &ids[..ids.len().min(1)],
     }
 }
 
@@ -287,3 +474,69 @@ pub type BuffId = u64;
 impl Component for Buffs {
     type Storage = FlaggedStorage<Self, IdvStorage<Self>>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buff(kind: BuffKind, strength: f32) -> Buff {
+        Buff::new(
+            kind,
+            BuffData {
+                strength,
+                duration: Some(Duration::from_secs(10)),
+            },
+            Vec::new(),
+            BuffSource::Unknown,
+        )
+    }
+
+    #[test]
+    fn stacking_rules() {
+        let mut buffs = Buffs::default();
+
+        // Only the strongest haste counts
+        buffs.insert(buff(BuffKind::Haste, 0.2));
+        buffs.insert(buff(BuffKind::Haste, 0.5));
+        assert!((buffs.movement_speed_modifier() - 1.5).abs() < f32::EPSILON);
+
+        // Every bleed counts
+        buffs.insert(buff(BuffKind::Bleeding, 1.0));
+        buffs.insert(buff(BuffKind::Bleeding, 2.0));
+        assert_eq!(
+            buffs
+                .iter_active()
+                .filter(|buff| buff.kind == BuffKind::Bleeding)
+                .count(),
+            2
+        );
+
+        // Like other elemental debuffs, only the strongest poison ticks, however
+        // often a poison beam hits
+        for _ in 0..10 {
+            buffs.insert(buff(BuffKind::Poisoned, 1.0));
+        }
+        assert_eq!(
+            buffs
+                .iter_active()
+                .filter(|buff| buff.kind == BuffKind::Poisoned)
+                .count(),
+            1
+        );
+
+        // A new stun replaces the old one
+        buffs.insert(buff(BuffKind::Stunned, 1.0));
+        buffs.insert(buff(BuffKind::Stunned, 1.0));
+        assert_eq!(buffs.iter_kind(BuffKind::Stunned).count(), 1);
+        assert!(!buffs.can_move() && !buffs.can_use_abilities());
+    }
+
+    #[test]
+    fn damage_modifiers_multiply() {
+        let mut buffs = Buffs::default();
+        buffs.insert(buff(BuffKind::Vulnerable, 0.5));
+        buffs.insert(buff(BuffKind::Fortified, 0.5));
+        assert!((buffs.damage_taken_modifier() - 0.75).abs() < f32::EPSILON);
+        assert!((buffs.damage_dealt_modifier() - 1.0).abs() < f32::EPSILON);
+    }
+}
@@ -78,6 +78,8 @@ pub enum CharacterState {
     /// A continuous attack that affects all creatures in a cone originating
     /// from the source
     BasicBeam(basic_beam::Data),
+    /// Swing a pickaxe or hatchet at the block being aimed at
+    Mining(mining::Data),
 }
 
 impl CharacterState {
@@ -96,6 +98,7 @@ impl CharacterState {
             | CharacterState::RepeaterRanged(_)
             | CharacterState::Shockwave(_)
             | CharacterState::BasicBeam(_)
+            | CharacterState::Mining(_)
         )
     }
 
@@ -128,6 +131,7 @@ impl CharacterState {
             | CharacterState::RepeaterRanged(_)
             | CharacterState::Shockwave(_)
             | CharacterState::BasicBeam(_)
+            | CharacterState::Mining(_)
         )
     }
 
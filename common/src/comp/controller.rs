@@ -20,6 +20,14 @@ pub enum InventoryManip {
     Swap(Slot, Slot),
     Drop(Slot),
     CraftRecipe(String),
+    /// Request the contents of the container placed at a position
+    OpenContainer(Vec3<i32>),
+    /// Move an item from an inventory slot into a container
+    Deposit(Vec3<i32>, usize),
+    /// Move an item from a container slot into the inventory
+    Withdraw(Vec3<i32>, usize),
+    /// Break the item in an inventory slot down into crafting ingredients
+    Salvage(usize),
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -43,6 +51,9 @@ pub enum ControlEvent {
     GroupManip(GroupManip),
     RemoveBuff(BuffKind),
     Respawn,
+    /// Open or close a gate (or any other toggleable sprite) at the given
+    /// position
+    ToggleSprite(Vec3<i32>),
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -182,6 +193,8 @@ pub struct ControllerInputs {
     pub swimdown: Input,
     pub move_dir: Vec2<f32>,
     pub look_dir: Dir,
+    /// The block being aimed at, if any
+    pub select_pos: Option<Vec3<i32>>,
 }
 
 #[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
@@ -235,11 +248,32 @@ impl ControllerInputs {
         self.swimdown.update_with_new(new.swimdown);
         self.move_dir = new.move_dir;
         self.look_dir = new.look_dir;
+        self.select_pos = new.select_pos;
     }
 
     pub fn holding_ability_key(&self) -> bool {
         self.primary.is_pressed() || self.secondary.is_pressed() || self.ability3.is_pressed()
     }
+
+    /// Lets go of all inputs that move the character
+    pub fn release_movement(&mut self) {
+        self.jump.set_state(false);
+        self.roll.set_state(false);
+        self.glide.set_state(false);
+        self.wall_leap.set_state(false);
+        self.swimup.set_state(false);
+        self.swimdown.set_state(false);
+        self.climb = None;
+        self.move_dir = Vec2::zero();
+    }
+
+    /// Lets go of all ability inputs
+    pub fn release_abilities(&mut self) {
+        self.primary.set_state(false);
+        self.secondary.set_state(false);
+        self.ability3.set_state(false);
+        self.charge.set_state(false);
+    }
 }
 
 impl Controller {
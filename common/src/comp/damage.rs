@@ -1,11 +1,53 @@
-use crate::comp::Loadout;
+use crate::comp::{Buff, BuffCategory, BuffData, BuffKind, BuffSource, Loadout};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 pub const BLOCK_EFFICIENCY: f32 = 0.9;
 
 pub struct Damage {
     pub healthchange: f32,
     pub source: DamageSource,
+    /// Element of the damage, reduced by elemental resistance instead of by
+    /// protection. `None` for physical damage.
+    pub element: Option<Element>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Element {
+    Fire,
+    Frost,
+    Poison,
+    Arcane,
+}
+
+impl Element {
+    pub const ALL: [Element; 4] = [
+        Element::Fire,
+        Element::Frost,
+        Element::Poison,
+        Element::Arcane,
+    ];
+
+    /// Debuff applied to targets hit by damage of this element, scaled by the
+    /// damage dealt
+    fn on_hit_buff(self, damage: f32) -> Option<(BuffKind, BuffData)> {
+        match self {
+            Element::Fire => Some((BuffKind::Burning, BuffData {
+                strength: damage * 0.1,
+                duration: Some(Duration::from_secs(3)),
+            })),
+            Element::Frost => Some((BuffKind::Chilled, BuffData {
+                strength: (damage / 200.0).min(0.5),
+                duration: Some(Duration::from_secs(2)),
+            })),
+            Element::Poison => Some((BuffKind::Poisoned, BuffData {
+                strength: damage * 0.05,
+                duration: Some(Duration::from_secs(5)),
+            })),
+            Element::Arcane => None,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -19,21 +61,51 @@ pub enum DamageSource {
     Energy,
 }
 
+/// What happened to a [`Damage`] while it was mitigated, for the damage log
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DamageReport {
+    pub source: DamageSource,
+    pub element: Option<Element>,
+    /// Health change before block and armor were applied, critical hits
+    /// included
+    pub pre_mitigation: f32,
+    pub crit: bool,
+}
+
 impl Damage {
-    pub fn modify_damage(&mut self, block: bool, loadout: &Loadout) {
+    /// Fraction of this damage the target's armor absorbs
+    fn reduction(&self, loadout: &Loadout) -> f32 {
+        match self.element {
+            Some(element) => loadout.get_resistance(element),
+            None => loadout.get_damage_reduction(),
+        }
+    }
+
+    /// Apply critical hits, blocking and the target's armor. Critical hits are
+    /// rolled with `rng` so combat can be reproduced from a seed.
+    pub fn modify_damage(
+        &mut self,
+        block: bool,
+        loadout: &Loadout,
+        rng: &mut impl Rng,
+    ) -> DamageReport {
+        let mut crit = false;
+        let pre_mitigation;
         match self.source {
             DamageSource::Melee => {
                 // Critical hit
                 let mut critdamage = 0.0;
-                if rand::random() {
+                if rng.gen() {
+                    crit = true;
                     critdamage = self.healthchange * 0.3;
                 }
+                pre_mitigation = self.healthchange + critdamage;
                 // Block
                 if block {
                     self.healthchange *= 1.0 - BLOCK_EFFICIENCY
                 }
                 // Armor
-                let damage_reduction = loadout.get_damage_reduction();
+                let damage_reduction = self.reduction(loadout);
                 self.healthchange *= 1.0 - damage_reduction;
 
                 // Critical damage applies after armor for melee
@@ -43,37 +115,126 @@ impl Damage {
             },
             DamageSource::Projectile => {
                 // Critical hit
-                if rand::random() {
+                if rng.gen() {
+                    crit = true;
                     self.healthchange *= 1.2;
                 }
+                pre_mitigation = self.healthchange;
                 // Block
                 if block {
                     self.healthchange *= 1.0 - BLOCK_EFFICIENCY
                 }
                 // Armor
-                let damage_reduction = loadout.get_damage_reduction();
+                let damage_reduction = self.reduction(loadout);
                 self.healthchange *= 1.0 - damage_reduction;
             },
             DamageSource::Explosion => {
+                pre_mitigation = self.healthchange;
                 // Block
                 if block {
                     self.healthchange *= 1.0 - BLOCK_EFFICIENCY
                 }
                 // Armor
-                let damage_reduction = loadout.get_damage_reduction();
+                let damage_reduction = self.reduction(loadout);
                 self.healthchange *= 1.0 - damage_reduction;
             },
-            DamageSource::Shockwave => {
+            DamageSource::Shockwave | DamageSource::Energy => {
+                pre_mitigation = self.healthchange;
                 // Armor
-                let damage_reduction = loadout.get_damage_reduction();
+                let damage_reduction = self.reduction(loadout);
                 self.healthchange *= 1.0 - damage_reduction;
             },
-            DamageSource::Energy => {
-                // Armor
-                let damage_reduction = loadout.get_damage_reduction();
-                self.healthchange *= 1.0 - damage_reduction;
-            },
-            _ => {},
+            _ => pre_mitigation = self.healthchange,
+        }
+        DamageReport {
+            source: self.source,
+            element: self.element,
+            pre_mitigation,
+            crit,
+        }
+    }
+
+    /// The elemental debuff this damage inflicts, if it is elemental damage
+    /// that was not blocked
+    pub fn on_hit_buff(&self, block: bool, source: BuffSource) -> Option<Buff> {
+        if block || self.healthchange >= 0.0 {
+            return None;
+        }
+        let (kind, data) = self.element?.on_hit_buff(-self.healthchange)?;
+        Some(Buff::new(kind, data, vec![BuffCategory::Magical], source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{comp::Item, LoadoutBuilder};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn elemental_damage_is_reduced_by_resistance() {
+        // Druid's vest: a little protection and some poison resistance
+        let loadout = LoadoutBuilder::new()
+            .chest(Some(Item::new_from_asset_expect(
+                "common.items.armor.chest.druid",
+            )))
+            .build();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let mut physical = Damage {
+            healthchange: -100.0,
+            source: DamageSource::Explosion,
+            element: None,
+        };
+        physical.modify_damage(false, &loadout, &mut rng);
+        let mut poison = Damage {
+            healthchange: -100.0,
+            source: DamageSource::Explosion,
+            element: Some(Element::Poison),
+        };
+        poison.modify_damage(false, &loadout, &mut rng);
+        let mut fire = Damage {
+            healthchange: -100.0,
+            source: DamageSource::Explosion,
+            element: Some(Element::Fire),
+        };
+        fire.modify_damage(false, &loadout, &mut rng);
+
+        assert!(poison.healthchange > physical.healthchange);
+        assert!((fire.healthchange + 100.0).abs() < f32::EPSILON);
+        assert_eq!(
+            fire.on_hit_buff(false, BuffSource::World)
+                .map(|buff| buff.kind),
+            Some(BuffKind::Burning)
+        );
+        assert!(fire.on_hit_buff(true, BuffSource::World).is_none());
+        assert!(physical.on_hit_buff(false, BuffSource::World).is_none());
+    }
+
+    #[test]
+    fn seeded_crits_are_reproducible() {
+        let loadout = LoadoutBuilder::new().build();
+        let roll = |seed| {
+            let mut rng = StdRng::seed_from_u64(seed);
+            (0..32)
+                .map(|_| {
+                    let mut damage = Damage {
+                        healthchange: -100.0,
+                        source: DamageSource::Melee,
+                        element: None,
+                    };
+                    let report = damage.modify_damage(false, &loadout, &mut rng);
+                    (report.crit, damage.healthchange)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let hits = roll(42);
+        assert_eq!(hits, roll(42));
+        assert!(hits.iter().any(|(crit, _)| *crit));
+        assert!(hits.iter().any(|(crit, _)| !*crit));
+        for (crit, healthchange) in hits {
+            assert_eq!(crit, healthchange < -100.0);
         }
     }
 }
@@ -28,7 +28,18 @@ impl Component for Group {
     type Storage = FlaggedStorage<Self, IdvStorage<Self>>;
 }
 
-pub struct Invite(pub specs::Entity);
+/// What the invitee is being asked to join
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InviteKind {
+    Group,
+    /// Invite into the faction of the inviter
+    Faction,
+}
+
+pub struct Invite {
+    pub inviter: specs::Entity,
+    pub kind: InviteKind,
+}
 impl Component for Invite {
     type Storage = IdvStorage<Self>;
 }
@@ -1,3 +1,4 @@
+use crate::comp::Element;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -27,6 +28,8 @@ impl Armor {
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Stats {
     protection: Protection,
+    #[serde(default)]
+    resistances: Resistances,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -35,6 +38,37 @@ pub enum Protection {
     Normal(f32),
 }
 
+/// Resistance against each element. Elemental damage is reduced by the
+/// resistance to its element instead of by protection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Resistances {
+    pub fire: f32,
+    pub frost: f32,
+    pub poison: f32,
+    pub arcane: f32,
+}
+
+impl Resistances {
+    pub fn get(&self, element: Element) -> f32 {
+        match element {
+            Element::Fire => self.fire,
+            Element::Frost => self.frost,
+            Element::Poison => self.poison,
+            Element::Arcane => self.arcane,
+        }
+    }
+
+    /// Each element paired with the resistance against it
+    pub fn iter(&self) -> impl Iterator<Item = (Element, f32)> {
+        let resistances = *self;
+        let elements: &'static [Element] = &Element::ALL;
+        elements
+            .iter()
+            .map(move |element| (*element, resistances.get(*element)))
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Armor {
     pub kind: ArmorKind,
@@ -43,4 +77,6 @@ pub struct Armor {
 
 impl Armor {
     pub fn get_protection(&self) -> Protection { self.stats.protection }
+
+    pub fn get_resistances(&self) -> Resistances { self.stats.resistances }
 }
@@ -0,0 +1,206 @@
+use super::{
+    armor::{Protection, Resistances},
+    ItemDef, ItemKind, Quality,
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of a player-given item name, in bytes
+pub const MAX_CUSTOM_NAME_LEN: usize = 32;
+
+/// Power multiplier applied to broken tools
+const BROKEN_POWER_MULTIPLIER: f32 = 0.5;
+
+/// A stat modifier rolled for a particular item instance
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Affix {
+    /// Multiplies the power of a tool
+    Power(f32),
+    /// Adds to the protection of a piece of armor
+    Protection(f32),
+    /// Multiplies the maximum durability of the item
+    Durability(f32),
+}
+
+impl Affix {
+    /// Roll a random affix suitable for an item of the given kind
+    fn roll(kind: &ItemKind, rng: &mut impl Rng) -> Option<Self> {
+        Some(match (kind, rng.gen_range(0, 3)) {
+            (ItemKind::Tool(_), 0) => Affix::Durability(rng.gen_range(1.25, 2.0)),
+            (ItemKind::Tool(_), _) => Affix::Power(rng.gen_range(1.05, 1.25)),
+            (ItemKind::Armor(_), 0) => Affix::Durability(rng.gen_range(1.25, 2.0)),
+            (ItemKind::Armor(_), _) => Affix::Protection(rng.gen_range(1, 6) as f32),
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Durability {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Durability {
+    pub fn is_broken(&self) -> bool { self.current == 0 }
+}
+
+/// State belonging to a single item instance, as opposed to the state shared
+/// by every item with the same definition
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemState {
+    /// `None` for items that don't wear out
+    #[serde(default)]
+    pub durability: Option<Durability>,
+    #[serde(default)]
+    pub affixes: Vec<Affix>,
+    /// A name given to the item by a player
+    #[serde(default)]
+    pub custom_name: Option<String>,
+}
+
+impl ItemState {
+    /// The state of a brand new item with the given definition
+    pub fn new(item_def: &ItemDef) -> Self {
+        Self {
+            durability: item_def
+                .max_durability()
+                .map(|max| Durability { current: max, max }),
+            affixes: Vec::new(),
+            custom_name: None,
+        }
+    }
+
+    /// Roll random affixes for an item, with better quality items getting
+    /// more of them
+    pub fn roll_affixes(&mut self, item_def: &ItemDef, rng: &mut impl Rng) {
+        let max_affixes = match item_def.quality {
+            Quality::Low | Quality::Debug => 0,
+            Quality::Common | Quality::Moderate => 1,
+            Quality::High | Quality::Epic => 2,
+            Quality::Legendary | Quality::Artifact => 3,
+        };
+        let affix_count = rng.gen_range(0, max_affixes + 1);
+        self.affixes = (0..affix_count)
+            .filter_map(|_| Affix::roll(&item_def.kind, rng))
+            .collect();
+
+        if let Some(durability) = &mut self.durability {
+            let max = self
+                .affixes
+                .iter()
+                .filter_map(|affix| match affix {
+                    Affix::Durability(multiplier) => Some(*multiplier),
+                    _ => None,
+                })
+                .fold(durability.max as f32, |max, multiplier| max * multiplier)
+                as u32;
+            *durability = Durability { current: max, max };
+        }
+    }
+
+    pub fn is_broken(&self) -> bool { self.durability.map_or(false, |d| d.is_broken()) }
+
+    /// Reduce the durability of the item, returning `true` if this broke it
+    pub fn wear(&mut self, amount: u32) -> bool {
+        match &mut self.durability {
+            Some(durability) if !durability.is_broken() => {
+                durability.current = durability.current.saturating_sub(amount);
+                durability.is_broken()
+            },
+            _ => false,
+        }
+    }
+
+    /// Restore the durability of the item, returning `true` if it had any
+    /// durability to restore
+    pub fn repair(&mut self) -> bool {
+        match &mut self.durability {
+            Some(durability) if durability.current < durability.max => {
+                durability.current = durability.max;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    /// Multiplier applied to the base power of a tool
+    pub fn power_multiplier(&self) -> f32 {
+        let multiplier = self
+            .affixes
+            .iter()
+            .filter_map(|affix| match affix {
+                Affix::Power(multiplier) => Some(*multiplier),
+                _ => None,
+            })
+            .product::<f32>();
+
+        if self.is_broken() {
+            multiplier * BROKEN_POWER_MULTIPLIER
+        } else {
+            multiplier
+        }
+    }
+
+    /// The protection of a piece of armor with the given base protection.
+    /// Broken armor gives no protection.
+    pub fn protection(&self, base: Protection) -> Protection {
+        match base {
+            _ if self.is_broken() => Protection::Normal(0.0),
+            Protection::Normal(protection) => Protection::Normal(
+                protection
+                    + self
+                        .affixes
+                        .iter()
+                        .filter_map(|affix| match affix {
+                            Affix::Protection(protection) => Some(*protection),
+                            _ => None,
+                        })
+                        .sum::<f32>(),
+            ),
+            Protection::Invincible => Protection::Invincible,
+        }
+    }
+
+    /// The resistances of a piece of armor with the given base resistances.
+    /// Broken armor gives no resistance.
+    pub fn resistances(&self, base: Resistances) -> Resistances {
+        if self.is_broken() {
+            Resistances::default()
+        } else {
+            base
+        }
+    }
+}
+
+/// Whether `name` can be given to an item by a player
+pub fn is_valid_custom_name(name: &str) -> bool {
+    !name.trim().is_empty()
+        && name.len() <= MAX_CUSTOM_NAME_LEN
+        && !name.chars().any(|c| c.is_control())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assets::Asset;
+
+    #[test]
+    fn broken_items_lose_power_until_repaired() {
+        let item_def = ItemDef::load_expect("common.items.weapons.sword.starter_sword");
+        let mut state = ItemState::new(&item_def);
+        let max = state.durability.expect("Swords should wear out").max;
+
+        assert!(!state.wear(max - 1));
+        assert_eq!(state.power_multiplier(), 1.0);
+        assert!(state.wear(1));
+        assert!(state.is_broken());
+        assert!(!state.wear(1));
+        assert_eq!(state.power_multiplier(), BROKEN_POWER_MULTIPLIER);
+
+        assert!(state.repair());
+        assert!(!state.is_broken());
+        assert!(!state.repair());
+        assert_eq!(state.power_multiplier(), 1.0);
+    }
+}
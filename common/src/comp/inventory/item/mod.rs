@@ -1,12 +1,16 @@
 pub mod armor;
+pub mod instance;
 pub mod tool;
 
 // Reexports
+pub use instance::{Affix, Durability, ItemState};
 pub use tool::{Hands, Tool, ToolCategory, ToolKind};
 
 use crate::{
-    assets::{self, Asset, Error},
+    assets::{self, Asset, AssetFile, Error},
     effect::Effect,
+    farming::CropKind,
+    loot::{LootContext, LootTable},
     lottery::Lottery,
     terrain::{Block, SpriteKind},
 };
@@ -16,7 +20,6 @@ use serde::{Deserialize, Serialize};
 use specs::{Component, FlaggedStorage};
 use specs_idvs::IdvStorage;
 use std::{
-    fs::File,
     io::BufReader,
     num::{NonZeroU32, NonZeroU64},
     sync::Arc,
@@ -42,6 +45,11 @@ pub enum Reagent {
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Utility {
     Collar,
+    Seed(CropKind),
+    /// Restores the durability of every equipped item
+    RepairKit,
+    /// A container that can be placed in the world to store items in
+    StorageChest,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -62,7 +70,7 @@ impl Lantern {
 pub struct Glider {
     pub kind: String,
 }
-#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Copy)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Copy)]
 pub enum Quality {
     Low,       // Grey
     Common,    // UI Main Color
@@ -126,6 +134,9 @@ pub struct Item {
     /// amount is hidden because it needs to maintain the invariant that only
     /// stackable items can have > 1 amounts.
     amount: NonZeroU32,
+    /// Durability, affixes and name of this particular item
+    #[serde(default)]
+    state: ItemState,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -149,6 +160,28 @@ impl ItemDef {
             | ItemKind::Throwable { .. }
             | ItemKind::Utility { .. })
     }
+
+    /// The durability of a new item with this definition, or `None` if it
+    /// doesn't wear out
+    pub fn max_durability(&self) -> Option<u32> {
+        match &self.kind {
+            ItemKind::Tool(Tool {
+                kind: ToolKind::Empty | ToolKind::Debug(_),
+                ..
+            }) => None,
+            ItemKind::Tool(_) | ItemKind::Armor(_) => Some(match self.quality {
+                Quality::Low => 100,
+                Quality::Common => 150,
+                Quality::Moderate => 200,
+                Quality::High => 300,
+                Quality::Epic => 400,
+                Quality::Legendary => 600,
+                Quality::Artifact => 800,
+                Quality::Debug => return None,
+            }),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for Item {
@@ -160,7 +193,7 @@ impl PartialEq for Item {
 impl Asset for ItemDef {
     const ENDINGS: &'static [&'static str] = &["ron"];
 
-    fn parse(buf_reader: BufReader<File>, specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, specifier: &str) -> Result<Self, assets::Error> {
         let item: Result<Self, Error> =
             ron::de::from_reader(buf_reader).map_err(Error::parse_error);
 
@@ -183,6 +216,7 @@ impl Item {
     pub fn new(inner_item: Arc<ItemDef>) -> Self {
         Item {
             item_id: Arc::new(AtomicCell::new(None)),
+            state: ItemState::new(&inner_item),
             item_def: inner_item,
             amount: NonZeroU32::new(1).unwrap(),
         }
@@ -216,7 +250,12 @@ impl Item {
     }
 
     /// Duplicates an item, creating an exact copy but with a new item ID
-    pub fn duplicate(&self) -> Self { Item::new(Arc::clone(&self.item_def)) }
+    pub fn duplicate(&self) -> Self {
+        Item {
+            state: self.state.clone(),
+            ..Item::new(Arc::clone(&self.item_def))
+        }
+    }
 
     /// FIXME: HACK: In order to set the entity ID asynchronously, we currently
     /// start it at None, and then atomically set it when it's saved for the
@@ -286,7 +325,73 @@ impl Item {
 
     pub fn is_stackable(&self) -> bool { self.item_def.is_stackable() }
 
-    pub fn name(&self) -> &str { &self.item_def.name }
+    /// The name given to the item by a player, or the name of its definition
+    pub fn name(&self) -> &str {
+        self.state
+            .custom_name
+            .as_deref()
+            .unwrap_or(&self.item_def.name)
+    }
+
+    pub fn state(&self) -> &ItemState { &self.state }
+
+    /// Replaces the per-instance state of the item, e.g. when loading it from
+    /// the database
+    pub fn set_state(&mut self, state: ItemState) { self.state = state; }
+
+    /// Give the item a name, or remove its name if `None`
+    pub fn set_custom_name(&mut self, name: Option<String>) -> Result<(), ()> {
+        match name {
+            Some(name) if !instance::is_valid_custom_name(&name) => Err(()),
+            name => {
+                self.state.custom_name = name.map(|name| name.trim().to_owned());
+                Ok(())
+            },
+        }
+    }
+
+    /// Roll random affixes for the item, used for items found in the world
+    pub fn roll_affixes(&mut self, rng: &mut impl Rng) {
+        self.state.roll_affixes(&self.item_def, rng);
+    }
+
+    /// Reduce the durability of the item, returning `true` if this broke it
+    pub fn wear(&mut self, amount: u32) -> bool { self.state.wear(amount) }
+
+    /// Restore the durability of the item, returning `true` if it was damaged
+    pub fn repair(&mut self) -> bool { self.state.repair() }
+
+    pub fn is_broken(&self) -> bool { self.state.is_broken() }
+
+    /// The tool stats of the item, with its affixes and durability taken into
+    /// account
+    pub fn tool(&self) -> Option<Tool> {
+        match &self.item_def.kind {
+            ItemKind::Tool(tool) => Some(
+                tool.clone()
+                    .with_power(tool.base_power() * self.state.power_multiplier()),
+            ),
+            _ => None,
+        }
+    }
+
+    /// The protection of the item, with its affixes and durability taken into
+    /// account. Broken armor gives no protection.
+    pub fn protection(&self) -> Option<armor::Protection> {
+        match &self.item_def.kind {
+            ItemKind::Armor(armor) => Some(self.state.protection(armor.get_protection())),
+            _ => None,
+        }
+    }
+
+    /// The elemental resistances of the item. Broken armor gives no
+    /// resistance.
+    pub fn resistances(&self) -> Option<armor::Resistances> {
+        match &self.item_def.kind {
+            ItemKind::Armor(armor) => Some(self.state.resistances(armor.get_resistances())),
+            _ => None,
+        }
+    }
 
     pub fn description(&self) -> &str { &self.item_def.description }
 
@@ -302,6 +407,7 @@ impl Item {
         Some(Item::new_from_asset_expect(match block.get_sprite()? {
             SpriteKind::Apple => "common.items.food.apple",
             SpriteKind::Mushroom => "common.items.food.mushroom",
+            SpriteKind::GlowMushroom => "common.items.crafting_ing.glowcap",
             SpriteKind::Velorite => "common.items.ore.velorite",
             SpriteKind::VeloriteFrag => "common.items.ore.veloritefrag",
             SpriteKind::BlueFlower => "common.items.flowers.blue",
@@ -315,29 +421,26 @@ impl Item {
             SpriteKind::MediumGrass => "common.items.grasses.medium",
             SpriteKind::ShortGrass => "common.items.grasses.short",
             SpriteKind::Coconut => "common.items.food.coconut",
+            SpriteKind::StorageChest => "common.items.utility.storage_chest",
             SpriteKind::Chest => {
-                chosen = Lottery::<String>::load_expect(match rng.gen_range(0, 7) {
-                    0 => "common.loot_tables.loot_table_weapon_uncommon",
-                    1 => "common.loot_tables.loot_table_weapon_common",
-                    2 => "common.loot_tables.loot_table_armor_light",
-                    3 => "common.loot_tables.loot_table_armor_cloth",
-                    4 => "common.loot_tables.loot_table_armor_heavy",
-                    _ => "common.loot_tables.loot_table_armor_misc",
-                });
-                chosen.choose()
+                let mut item = LootTable::load_expect("common.loot_tables.sprite.chest")
+                    .roll(&LootContext::default(), &mut rng)?;
+                item.roll_affixes(&mut rng);
+                return Some(item);
             },
             SpriteKind::Crate => {
-                chosen = Lottery::<String>::load_expect(match rng.gen_range(0, 4) {
-                    0 => "common.loot_tables.loot_table_crafting",
-                    _ => "common.loot_tables.loot_table_food",
-                });
-                chosen.choose()
+                return LootTable::load_expect("common.loot_tables.sprite.crate")
+                    .roll(&LootContext::default(), &mut rng);
             },
             SpriteKind::Beehive => "common.items.crafting_ing.honey",
             SpriteKind::Stones => "common.items.crafting_ing.stones",
             SpriteKind::Twigs => "common.items.crafting_ing.twigs",
             SpriteKind::ShinyGem => "common.items.crafting_ing.shiny_gem",
-            _ => return None,
+            sprite => {
+                chosen =
+                    Lottery::<String>::load_expect(CropKind::from_grown(sprite)?.harvest_table());
+                chosen.choose()
+            },
         }))
     }
 }
@@ -349,16 +452,21 @@ pub trait ItemDesc {
     fn name(&self) -> &str;
     fn kind(&self) -> &ItemKind;
     fn quality(&self) -> &Quality;
+    /// Per-instance state, only available for actual items rather than item
+    /// definitions
+    fn state(&self) -> Option<&ItemState> { None }
 }
 
 impl ItemDesc for Item {
     fn description(&self) -> &str { &self.item_def.description }
 
-    fn name(&self) -> &str { &self.item_def.name }
+    fn name(&self) -> &str { Item::name(self) }
 
     fn kind(&self) -> &ItemKind { &self.item_def.kind }
 
     fn quality(&self) -> &Quality { &self.item_def.quality }
+
+    fn state(&self) -> Option<&ItemState> { Some(&self.state) }
 }
 
 impl ItemDesc for ItemDef {
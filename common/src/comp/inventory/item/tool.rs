@@ -2,7 +2,10 @@
 // version in voxygen\src\meta.rs in order to reset save files to being empty
 
 use crate::{
-    comp::{body::object, projectile, Body, CharacterAbility, Gravity, LightEmitter, Projectile},
+    comp::{
+        body::object, projectile, Body, CharacterAbility, Element, Gravity, LightEmitter,
+        Projectile,
+    },
     states::combo_melee,
     Explosion,
 };
@@ -22,6 +25,10 @@ pub enum ToolKind {
     NpcWeapon(String),
     Debug(String),
     Farming(String),
+    /// Pickaxes, for mining rock, earth and ore
+    Pick(String),
+    /// Hatchets, for chopping wood
+    Hatchet(String),
     /// This is an placeholder item, it is used by non-humanoid npcs to attack
     Empty,
 }
@@ -40,6 +47,8 @@ impl ToolKind {
             ToolKind::NpcWeapon(_) => Hands::TwoHand,
             ToolKind::Debug(_) => Hands::TwoHand,
             ToolKind::Farming(_) => Hands::TwoHand,
+            ToolKind::Pick(_) => Hands::TwoHand,
+            ToolKind::Hatchet(_) => Hands::TwoHand,
             ToolKind::Empty => Hands::OneHand,
         }
     }
@@ -63,6 +72,8 @@ pub enum ToolCategory {
     NpcWeapon,
     Debug,
     Farming,
+    Pick,
+    Hatchet,
     Empty,
 }
 
@@ -80,6 +91,8 @@ impl From<&ToolKind> for ToolCategory {
             ToolKind::NpcWeapon(_) => ToolCategory::NpcWeapon,
             ToolKind::Debug(_) => ToolCategory::Debug,
             ToolKind::Farming(_) => ToolCategory::Farming,
+            ToolKind::Pick(_) => ToolCategory::Pick,
+            ToolKind::Hatchet(_) => ToolCategory::Hatchet,
             ToolKind::Empty => ToolCategory::Empty,
         }
     }
@@ -89,6 +102,8 @@ impl From<&ToolKind> for ToolCategory {
 pub struct Stats {
     equip_time_millis: u32,
     power: f32,
+    #[serde(default)]
+    element: Option<Element>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -105,6 +120,7 @@ impl Tool {
             stats: Stats {
                 equip_time_millis: 0,
                 power: 1.00,
+                element: None,
             },
         }
     }
@@ -112,6 +128,15 @@ impl Tool {
     // Keep power between 0.5 and 2.00
     pub fn base_power(&self) -> f32 { self.stats.power }
 
+    /// The same tool with a different power, used to apply per-item modifiers
+    pub fn with_power(mut self, power: f32) -> Self {
+        self.stats.power = power;
+        self
+    }
+
+    /// Element of the damage dealt by the tool's abilities, if any
+    pub fn element(&self) -> Option<Element> { self.stats.element }
+
     pub fn equip_time(&self) -> Duration {
         Duration::from_millis(self.stats.equip_time_millis as u64)
     }
@@ -287,6 +312,21 @@ impl Tool {
                 range: 3.5,
                 max_angle: 20.0,
             }],
+            Pick(_) | Hatchet(_) => vec![
+                Mine {
+                    buildup_duration: Duration::from_millis(500),
+                    recover_duration: Duration::from_millis(300),
+                },
+                BasicMelee {
+                    energy_cost: 0,
+                    buildup_duration: Duration::from_millis(600),
+                    recover_duration: Duration::from_millis(300),
+                    base_healthchange: (-40.0 * self.base_power()) as i32,
+                    knockback: 0.0,
+                    range: 3.5,
+                    max_angle: 20.0,
+                },
+            ],
             Bow(_) => vec![
                 BasicRanged {
                     energy_cost: 0,
@@ -304,6 +344,7 @@ impl Tool {
                         time_left: Duration::from_secs(15),
                         owner: None,
                         ignore_group: true,
+                        element: self.element(),
                     },
                     projectile_body: Body::Object(object::Body::Arrow),
                     projectile_light: None,
@@ -344,6 +385,7 @@ impl Tool {
                         time_left: Duration::from_secs(15),
                         owner: None,
                         ignore_group: true,
+                        element: self.element(),
                     },
                     projectile_body: Body::Object(object::Body::Arrow),
                     projectile_light: None,
@@ -391,6 +433,7 @@ impl Tool {
                                 min_heal: (50.0 * self.base_power()) as u32,
                                 terrain_destruction_power: 0.0,
                                 energy_regen: 0,
+                                element: self.element(),
                             }),
                             projectile::Effect::Vanish,
                         ],
@@ -403,12 +446,14 @@ impl Tool {
                                 min_heal: (50.0 * self.base_power()) as u32,
                                 terrain_destruction_power: 0.0,
                                 energy_regen: 0,
+                                element: self.element(),
                             }),
                             projectile::Effect::Vanish,
                         ],
                         time_left: Duration::from_secs(20),
                         owner: None,
                         ignore_group: true,
+                        element: self.element(),
                     },
                     projectile_body: Body::Object(object::Body::BoltNature),
                     projectile_light: Some(LightEmitter {
@@ -435,6 +480,7 @@ impl Tool {
                                 min_heal: 0,
                                 terrain_destruction_power: 0.0,
                                 energy_regen: 50,
+                                element: self.element(),
                             }),
                             projectile::Effect::Vanish,
                         ],
@@ -447,12 +493,14 @@ impl Tool {
                                 min_heal: 0,
                                 terrain_destruction_power: 0.0,
                                 energy_regen: 50,
+                                element: self.element(),
                             }),
                             projectile::Effect::Vanish,
                         ],
                         time_left: Duration::from_secs(20),
                         owner: None,
                         ignore_group: true,
+                        element: self.element(),
                     },
                     projectile_body: Body::Object(object::Body::BoltFire),
                     projectile_light: Some(LightEmitter {
@@ -577,6 +625,7 @@ impl Tool {
                                 time_left: Duration::from_secs(10),
                                 owner: None,
                                 ignore_group: false,
+                                element: None,
                             },
                             projectile_body: Body::Object(object::Body::ArrowSnake),
                             projectile_light: Some(LightEmitter {
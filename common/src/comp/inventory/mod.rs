@@ -34,6 +34,15 @@ impl Inventory {
         }
     }
 
+    /// An empty inventory with `size` slots, used for containers placed in
+    /// the world
+    pub fn new_with_size(size: usize) -> Inventory {
+        Inventory {
+            slots: vec![None; size],
+            amount: 0,
+        }
+    }
+
     pub fn slots(&self) -> &[Option<Item>] { &self.slots }
 
     pub fn len(&self) -> usize { self.slots.len() }
@@ -287,6 +296,7 @@ pub enum InventoryUpdateEvent {
     Possession,
     Debug,
     Craft,
+    Salvaged,
 }
 
 impl Default for InventoryUpdateEvent {
@@ -18,6 +18,7 @@ mod misc;
 mod phys;
 mod player;
 pub mod projectile;
+mod pvp;
 pub mod shockwave;
 pub mod skills;
 mod stats;
@@ -33,8 +34,8 @@ pub use body::{
     quadruped_low, quadruped_medium, quadruped_small, theropod, AllBodies, Body, BodyData,
 };
 pub use buff::{
-    Buff, BuffCategory, BuffChange, BuffData, BuffEffect, BuffId, BuffKind, BuffSource, Buffs,
-    ModifierKind,
+    Buff, BuffCategory, BuffChange, BuffData, BuffEffect, BuffId, BuffKind, BuffSource,
+    BuffStacking, Buffs, CrowdControl, ModifierKind,
 };
 pub use character_state::{Attacking, CharacterState, StateUpdate};
 pub use chat::{
@@ -44,7 +45,7 @@ pub use controller::{
     Climb, ControlAction, ControlEvent, Controller, ControllerInputs, GroupManip, Input,
     InventoryManip, MountState, Mounting,
 };
-pub use damage::{Damage, DamageSource};
+pub use damage::{Damage, DamageReport, DamageSource, Element};
 pub use energy::{Energy, EnergySource};
 pub use group::Group;
 pub use inputs::CanBuild;
@@ -59,6 +60,7 @@ pub use misc::Object;
 pub use phys::{Collider, ForceUpdate, Gravity, Mass, Ori, PhysicsState, Pos, Scale, Sticky, Vel};
 pub use player::{Player, MAX_MOUNT_RANGE_SQR};
 pub use projectile::Projectile;
+pub use pvp::{PvpFlag, PvpMode, PvpRules};
 pub use shockwave::{Shockwave, ShockwaveHitEntities};
 pub use skills::{Skill, SkillGroup, SkillGroupType, SkillSet};
 pub use stats::{Exp, HealthChange, HealthSource, Level, Stats};
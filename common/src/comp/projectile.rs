@@ -1,4 +1,4 @@
-use crate::{sync::Uid, Explosion};
+use crate::{comp::Element, sync::Uid, Explosion};
 use serde::{Deserialize, Serialize};
 use specs::{Component, FlaggedStorage};
 use specs_idvs::IdvStorage;
@@ -26,6 +26,8 @@ pub struct Projectile {
     /// Whether projectile collides with entities in the same group as its
     /// owner
     pub ignore_group: bool,
+    /// Element of the damage the projectile deals
+    pub element: Option<Element>,
 }
 
 impl Component for Projectile {
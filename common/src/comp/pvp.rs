@@ -0,0 +1,112 @@
+use crate::comp::{Player, Pos};
+use serde::{Deserialize, Serialize};
+use specs::{Component, Entity, NullStorage, ReadStorage};
+use vek::*;
+
+/// Whether players may harm each other
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PvpMode {
+    Off,
+    On,
+    /// Only players who opted in with a [`PvpFlag`] may harm each other
+    Flagged,
+    /// On, except inside settlements
+    OutsideSettlements,
+}
+
+impl Default for PvpMode {
+    fn default() -> Self { PvpMode::On }
+}
+
+/// Marks a player that opted in to PvP under [`PvpMode::Flagged`]
+#[derive(Clone, Copy, Default)]
+pub struct PvpFlag;
+
+impl Component for PvpFlag {
+    type Storage = NullStorage<Self>;
+}
+
+/// A resource with the PvP rules of the server and of the zones that override
+/// them
+#[derive(Clone, Debug, Default)]
+pub struct PvpRules {
+    pub mode: PvpMode,
+    /// Areas, in world coordinates, with a mode of their own
+    pub zones: Vec<(Aabr<i32>, PvpMode)>,
+}
+
+impl PvpRules {
+    pub fn new(mode: PvpMode) -> Self {
+        Self {
+            mode,
+            zones: Vec::new(),
+        }
+    }
+
+    /// The mode in effect at `pos`
+    pub fn mode_at(&self, pos: Vec3<f32>) -> PvpMode {
+        let wpos = pos.xy().map(|e| e.floor() as i32);
+        self.zones
+            .iter()
+            .find(|(bounds, _)| bounds.contains_point(wpos))
+            .map_or(self.mode, |(_, mode)| *mode)
+    }
+
+    /// Whether a player at `pos` takes part in PvP
+    fn takes_part(&self, pos: Option<Vec3<f32>>, flagged: bool) -> bool {
+        match pos.map_or(self.mode, |pos| self.mode_at(pos)) {
+            PvpMode::Off => false,
+            PvpMode::On | PvpMode::OutsideSettlements => true,
+            PvpMode::Flagged => flagged,
+        }
+    }
+
+    /// Whether `attacker` may harm `target`. The rules only concern players
+    /// harming other players, and both of them have to take part in PvP where
+    /// they stand.
+    pub fn allows(
+        &self,
+        players: &ReadStorage<Player>,
+        flags: &ReadStorage<PvpFlag>,
+        positions: &ReadStorage<Pos>,
+        attacker: Entity,
+        target: Entity,
+    ) -> bool {
+        if attacker == target || !players.contains(attacker) || !players.contains(target) {
+            return true;
+        }
+        [attacker, target].iter().all(|entity| {
+            self.takes_part(
+                positions.get(*entity).map(|pos| pos.0),
+                flags.contains(*entity),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zones_override_the_server_mode() {
+        let rules = PvpRules {
+            mode: PvpMode::OutsideSettlements,
+            zones: vec![(
+                Aabr {
+                    min: Vec2::new(0, 0),
+                    max: Vec2::new(100, 100),
+                },
+                PvpMode::Off,
+            )],
+        };
+
+        assert_eq!(rules.mode_at(Vec3::new(50.0, 50.0, 0.0)), PvpMode::Off);
+        assert!(!rules.takes_part(Some(Vec3::new(50.0, 50.0, 0.0)), true));
+        assert!(rules.takes_part(Some(Vec3::new(150.0, 50.0, 0.0)), false));
+
+        let flagged = PvpRules::new(PvpMode::Flagged);
+        assert!(flagged.takes_part(None, true));
+        assert!(!flagged.takes_part(Some(Vec3::new(50.0, 50.0, 0.0)), false));
+    }
+}
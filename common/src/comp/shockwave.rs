@@ -1,4 +1,4 @@
-use crate::sync::Uid;
+use crate::{comp::Element, sync::Uid};
 use serde::{Deserialize, Serialize};
 use specs::{Component, FlaggedStorage};
 use specs_idvs::IdvStorage;
@@ -14,6 +14,7 @@ pub struct Properties {
     pub requires_ground: bool,
     pub duration: Duration,
     pub owner: Option<Uid>,
+    pub element: Option<Element>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
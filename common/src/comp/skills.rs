@@ -1,3 +1,7 @@
+use crate::{
+    assets::{Asset, Ron},
+    comp::item::tool::ToolKind,
+};
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use std::{
@@ -9,54 +13,90 @@ use tracing::warn;
 lazy_static! {
     // Determines the skills that comprise each skill group - this data is used to determine
     // which of a player's skill groups a particular skill should be added to when a skill unlock
-    // is requested. TODO: Externalise this data in a RON file for ease of modification
-    pub static ref SKILL_GROUP_DEFS: HashMap<SkillGroupType, HashSet<Skill>> = {
-        let mut defs = HashMap::new();
-        defs.insert(SkillGroupType::T1, [ Skill::TestT1Skill1,
-                                         Skill::TestT1Skill2,
-                                         Skill::TestT1Skill3,
-                                         Skill::TestT1Skill4,
-                                         Skill::TestT1Skill5]
-                                         .iter().cloned().collect::<HashSet<Skill>>());
-
-        defs.insert(SkillGroupType::Swords, [ Skill::TestSwordSkill1,
-                                         Skill::TestSwordSkill2,
-                                         Skill::TestSwordSkill3]
-                                         .iter().cloned().collect::<HashSet<Skill>>());
-
-        defs.insert(SkillGroupType::Axes, [ Skill::TestAxeSkill1,
-                                         Skill::TestAxeSkill2,
-                                         Skill::TestAxeSkill3]
-                                         .iter().cloned().collect::<HashSet<Skill>>());
-
-        defs
-    };
+    // is requested.
+    pub static ref SKILL_GROUP_DEFS: HashMap<SkillGroupType, HashSet<Skill>> =
+        SkillGroupsManifest::load_expect("common.skill_trees.skill_groups")
+            .iter()
+            .map(|(group, skills)| (*group, skills.iter().copied().collect()))
+            .collect();
+
+    // Skills that have to be unlocked before a skill can be unlocked
+    pub static ref SKILL_PREREQUISITES: HashMap<Skill, HashSet<Skill>> =
+        SkillPrerequisitesManifest::load_expect("common.skill_trees.skill_prerequisites")
+            .iter()
+            .map(|(skill, prerequisites)| (*skill, prerequisites.iter().copied().collect()))
+            .collect();
 }
 
+type SkillGroupsManifest = Ron<HashMap<SkillGroupType, Vec<Skill>>>;
+type SkillPrerequisitesManifest = Ron<HashMap<Skill, Vec<Skill>>>;
+
+/// Experience needed for the first skill point of a skill group. Each further
+/// point needs this much more than the one before.
+pub const SKILL_POINT_EXP_FACTOR: u32 = 50;
+
 /// Represents a skill that a player can unlock, that either grants them some
 /// kind of active ability, or a passive effect etc. Obviously because this is
-/// an enum it doesn't describe what the skill actually -does-, this will be
-/// handled by dedicated ECS systems.
+/// an enum it doesn't describe what the skill actually -does-, this is
+/// handled where abilities are adjusted by skills, see
+/// `CharacterAbility::adjusted_by_skills`.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Skill {
-    TestT1Skill1,
-    TestT1Skill2,
-    TestT1Skill3,
-    TestT1Skill4,
-    TestT1Skill5,
-    TestSwordSkill1,
-    TestSwordSkill2,
-    TestSwordSkill3,
-    TestAxeSkill1,
-    TestAxeSkill2,
-    TestAxeSkill3,
+    /// More damage with every weapon
+    GeneralDamage,
+    /// Abilities of every weapon cost less energy
+    GeneralEnergyCost,
+    SwordDamage,
+    SwordEnergyCost,
+    /// Adds a fourth stage to the sword combo
+    SwordComboStage,
+    AxeDamage,
+    AxeEnergyCost,
+    /// The axe spin attack spins once more
+    AxeExtraSpin,
+    HammerDamage,
+    HammerEnergyCost,
+    /// The hammer charges up faster
+    HammerChargeSpeed,
+    BowDamage,
+    BowEnergyCost,
+    /// The bow repeater fires more arrows
+    BowRepeaterArrows,
+    StaffDamage,
+    StaffEnergyCost,
+    /// Staff fireballs explode in a larger radius
+    StaffExplosionRadius,
+    /// Sceptre abilities heal more
+    SceptreHealing,
+    SceptreEnergyCost,
+    /// The sceptre beam reaches further
+    SceptreBeamRange,
 }
 
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
 pub enum SkillGroupType {
-    T1,
+    General,
     Swords,
     Axes,
+    Hammers,
+    Bows,
+    Staffs,
+    Sceptres,
+}
+
+impl SkillGroupType {
+    /// The skill group experience with a tool of the given kind goes to
+    pub fn for_tool(kind: &ToolKind) -> Option<Self> {
+        match kind {
+            ToolKind::Sword(_) => Some(SkillGroupType::Swords),
+            ToolKind::Axe(_) => Some(SkillGroupType::Axes),
+            ToolKind::Hammer(_) => Some(SkillGroupType::Hammers),
+            ToolKind::Bow(_) => Some(SkillGroupType::Bows),
+            ToolKind::Staff(_) => Some(SkillGroupType::Staffs),
+            ToolKind::Sceptre(_) => Some(SkillGroupType::Sceptres),
+            _ => None,
+        }
+    }
 }
 
 /// A group of skills that have been unlocked by a player. Each skill group has
@@ -67,6 +107,8 @@ pub struct SkillGroup {
     pub skill_group_type: SkillGroupType,
     pub exp: u32,
     pub available_sp: u8,
+    /// Skill points earned with experience so far, spent or not
+    pub earned_sp: u8,
 }
 
 impl SkillGroup {
@@ -75,8 +117,12 @@ impl SkillGroup {
             skill_group_type,
             exp: 0,
             available_sp: 0,
+            earned_sp: 0,
         }
     }
+
+    /// Experience needed to earn the next skill point in this group
+    pub fn exp_for_next_sp(&self) -> u32 { SKILL_POINT_EXP_FACTOR * (self.earned_sp as u32 + 1) }
 }
 
 /// Contains all of a player's skill groups and skills. Provides methods for
@@ -91,11 +137,11 @@ pub struct SkillSet {
 impl Default for SkillSet {
     /// Instantiate a new skill set with the default skill groups with no
     /// unlocked skills in them - used when adding a skill set to a new
-    /// player
+    /// player. Weapon skill groups are unlocked once experience is earned
+    /// with the weapon.
     fn default() -> Self {
-        // TODO: Default skill groups for new players?
         Self {
-            skill_groups: Vec::new(),
+            skill_groups: vec![SkillGroup::new(SkillGroupType::General)],
             skills: HashSet::new(),
         }
     }
@@ -143,12 +189,16 @@ impl SkillSet {
     /// skillset.unlock_skill_group(SkillGroupType::Axes);
     /// skillset.add_skill_points(SkillGroupType::Axes, 1);
     ///
-    /// skillset.unlock_skill(Skill::TestAxeSkill2);
+    /// skillset.unlock_skill(Skill::AxeDamage);
     ///
     /// assert_eq!(skillset.skills.len(), 1);
     /// ```
     pub fn unlock_skill(&mut self, skill: Skill) {
         if !self.skills.contains(&skill) {
+            if !self.prerequisites_met(skill) {
+                warn!(?skill, "Tried to unlock skill without its prerequisites");
+                return;
+            }
             if let Some(skill_group_type) = SkillSet::get_skill_group_type_for_skill(&skill) {
                 if let Some(mut skill_group) = self
                     .skill_groups
@@ -184,14 +234,22 @@ impl SkillSet {
     /// let mut skillset = SkillSet::new();
     /// skillset.unlock_skill_group(SkillGroupType::Axes);
     /// skillset.add_skill_points(SkillGroupType::Axes, 1);
-    /// skillset.unlock_skill(Skill::TestAxeSkill2);
+    /// skillset.unlock_skill(Skill::AxeDamage);
     ///
-    /// skillset.refund_skill(Skill::TestAxeSkill2);
+    /// skillset.refund_skill(Skill::AxeDamage);
     ///
     /// assert_eq!(skillset.skills.len(), 0);
     /// ```
     pub fn refund_skill(&mut self, skill: Skill) {
         if self.skills.contains(&skill) {
+            if self.skills.iter().any(|unlocked| {
+                SKILL_PREREQUISITES
+                    .get(unlocked)
+                    .map_or(false, |prerequisites| prerequisites.contains(&skill))
+            }) {
+                warn!(?skill, "Tried to refund skill that other skills depend on");
+                return;
+            }
             if let Some(skill_group_type) = SkillSet::get_skill_group_type_for_skill(&skill) {
                 if let Some(mut skill_group) = self
                     .skill_groups
@@ -214,6 +272,17 @@ impl SkillSet {
         }
     }
 
+    /// Whether the skill has been unlocked
+    pub fn has_skill(&self, skill: Skill) -> bool { self.skills.contains(&skill) }
+
+    /// Whether all the skills that have to be unlocked before the given skill
+    /// are unlocked
+    pub fn prerequisites_met(&self, skill: Skill) -> bool {
+        SKILL_PREREQUISITES
+            .get(&skill)
+            .map_or(true, |prerequisites| prerequisites.is_subset(&self.skills))
+    }
+
     /// Returns the skill group type for a skill from the static skill group
     /// definitions.
     fn get_skill_group_type_for_skill(skill: &Skill) -> Option<SkillGroupType> {
@@ -253,6 +322,48 @@ impl SkillSet {
             warn!("Tried to add skill points to a skill group that player does not have");
         }
     }
+
+    /// Adds experience to a skill group, unlocking the group if the player
+    /// doesn't have it yet. Every time the experience reaches the amount
+    /// needed for the next skill point, that point is earned. Returns the
+    /// number of skill points earned.
+    ///
+    /// ```
+    /// use veloren_common::comp::skills::{SkillGroupType, SkillSet};
+    ///
+    /// let mut skillset = SkillSet::new();
+    /// let earned = skillset.add_experience(SkillGroupType::Axes, 60);
+    ///
+    /// assert_eq!(earned, 1);
+    /// assert_eq!(skillset.skill_groups[0].available_sp, 1);
+    /// assert_eq!(skillset.skill_groups[0].exp, 10);
+    /// ```
+    pub fn add_experience(&mut self, skill_group_type: SkillGroupType, exp: u32) -> u8 {
+        if !self
+            .skill_groups
+            .iter()
+            .any(|x| x.skill_group_type == skill_group_type)
+        {
+            self.unlock_skill_group(skill_group_type);
+        }
+        let mut earned = 0;
+        if let Some(skill_group) = self
+            .skill_groups
+            .iter_mut()
+            .find(|x| x.skill_group_type == skill_group_type)
+        {
+            skill_group.exp += exp;
+            while skill_group.exp >= skill_group.exp_for_next_sp()
+                && skill_group.earned_sp < u8::MAX
+            {
+                skill_group.exp -= skill_group.exp_for_next_sp();
+                skill_group.earned_sp += 1;
+                skill_group.available_sp = skill_group.available_sp.saturating_add(1);
+                earned += 1;
+            }
+        }
+        earned
+    }
 }
 
 #[cfg(test)]
@@ -264,19 +375,19 @@ mod tests {
         let mut skillset = SkillSet::new();
         skillset.unlock_skill_group(SkillGroupType::Axes);
         skillset.add_skill_points(SkillGroupType::Axes, 1);
-        skillset.unlock_skill(Skill::TestAxeSkill2);
+        skillset.unlock_skill(Skill::AxeDamage);
 
         assert_eq!(skillset.skill_groups[0].available_sp, 0);
         assert_eq!(skillset.skills.len(), 1);
         assert_eq!(
-            skillset.skills.get(&Skill::TestAxeSkill2),
-            Some(&Skill::TestAxeSkill2)
+            skillset.skills.get(&Skill::AxeDamage),
+            Some(&Skill::AxeDamage)
         );
 
-        skillset.refund_skill(Skill::TestAxeSkill2);
+        skillset.refund_skill(Skill::AxeDamage);
 
         assert_eq!(skillset.skill_groups[0].available_sp, 1);
-        assert_eq!(skillset.skills.get(&Skill::TestAxeSkill2), None);
+        assert_eq!(skillset.skills.get(&Skill::AxeDamage), None);
     }
 
     #[test]
@@ -302,20 +413,20 @@ mod tests {
         assert_eq!(skillset.skills.len(), 0);
 
         // Try unlocking a skill with enough skill points
-        skillset.unlock_skill(Skill::TestAxeSkill2);
+        skillset.unlock_skill(Skill::AxeDamage);
 
         assert_eq!(skillset.skill_groups[0].available_sp, 0);
         assert_eq!(skillset.skills.len(), 1);
         assert_eq!(
-            skillset.skills.get(&Skill::TestAxeSkill2),
-            Some(&Skill::TestAxeSkill2)
+            skillset.skills.get(&Skill::AxeDamage),
+            Some(&Skill::AxeDamage)
         );
 
         // Try unlocking a skill without enough skill points
-        skillset.unlock_skill(Skill::TestAxeSkill1);
+        skillset.unlock_skill(Skill::AxeEnergyCost);
 
         assert_eq!(skillset.skills.len(), 1);
-        assert_eq!(skillset.skills.get(&Skill::TestAxeSkill1), None);
+        assert_eq!(skillset.skills.get(&Skill::AxeEnergyCost), None);
     }
 
     #[test]
@@ -326,4 +437,71 @@ mod tests {
 
         assert_eq!(skillset.skill_groups[0].available_sp, 1);
     }
+
+    #[test]
+    fn test_skill_prerequisites() {
+        let mut skillset = SkillSet::new();
+        skillset.unlock_skill_group(SkillGroupType::Axes);
+        skillset.add_skill_points(SkillGroupType::Axes, 2);
+
+        // Can't unlock a skill before its prerequisites
+        skillset.unlock_skill(Skill::AxeExtraSpin);
+        assert!(!skillset.has_skill(Skill::AxeExtraSpin));
+
+        skillset.unlock_skill(Skill::AxeDamage);
+        skillset.unlock_skill(Skill::AxeExtraSpin);
+        assert!(skillset.has_skill(Skill::AxeExtraSpin));
+
+        // Can't refund a skill other skills depend on
+        skillset.refund_skill(Skill::AxeDamage);
+        assert!(skillset.has_skill(Skill::AxeDamage));
+    }
+
+    #[test]
+    fn test_skill_trees() {
+        for prerequisite in SKILL_PREREQUISITES.values().flatten() {
+            assert!(
+                SkillSet::get_skill_group_type_for_skill(prerequisite).is_some(),
+                "Prerequisite {:?} is not in any skill group",
+                prerequisite
+            );
+        }
+        let skills = SKILL_GROUP_DEFS.values().map(HashSet::len).sum::<usize>();
+        let distinct = SKILL_GROUP_DEFS.values().flatten().collect::<HashSet<_>>();
+        assert_eq!(skills, distinct.len(), "A skill is in more than one group");
+    }
+
+    #[test]
+    fn test_skills_adjust_abilities() {
+        use crate::comp::{CharacterAbility, Item};
+
+        let axe = Item::new_from_asset_expect("common.items.weapons.axe.starter_axe");
+        let tool = axe.tool().expect("Axe is a tool");
+        let spin = tool.get_abilities()[1].clone();
+
+        let mut skillset = SkillSet::new();
+        skillset.unlock_skill_group(SkillGroupType::Axes);
+        skillset.add_skill_points(SkillGroupType::Axes, 2);
+        skillset.unlock_skill(Skill::AxeDamage);
+        skillset.unlock_skill(Skill::AxeExtraSpin);
+
+        match (spin.clone(), spin.adjusted_by_skills(&tool.kind, &skillset)) {
+            (
+                CharacterAbility::SpinMelee {
+                    base_damage,
+                    num_spins,
+                    ..
+                },
+                CharacterAbility::SpinMelee {
+                    base_damage: skilled_damage,
+                    num_spins: skilled_spins,
+                    ..
+                },
+            ) => {
+                assert!(skilled_damage > base_damage);
+                assert_eq!(skilled_spins, num_spins + 1);
+            },
+            _ => panic!("Second axe ability should be a spin"),
+        }
+    }
 }
@@ -1,11 +1,12 @@
-use crate::comp;
+use crate::comp::{self, BuffData, BuffKind};
 use serde::{Deserialize, Serialize};
 
 /// An effect that may be applied to an entity
-#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Effect {
     Health(comp::HealthChange),
     Xp(i64),
+    Buff { kind: BuffKind, data: BuffData },
 }
 
 impl Effect {
@@ -13,6 +14,10 @@ impl Effect {
         match self {
             Effect::Health(c) => format!("{:+} health", c.amount),
             Effect::Xp(n) => format!("{:+} exp", n),
+            Effect::Buff { kind, data } => match data.duration {
+                Some(duration) => format!("{:?} for {}s", kind, duration.as_secs()),
+                None => format!("{:?}", kind),
+            },
         }
     }
 }
@@ -37,6 +37,9 @@ pub enum ServerEvent {
     Damage {
         uid: Uid,
         change: comp::HealthChange,
+        /// How the change came about, for the damage log. `None` for changes
+        /// that weren't mitigated, like damage over time.
+        report: Option<comp::DamageReport>,
     },
     Destroy {
         entity: EcsEntity,
@@ -110,6 +113,21 @@ pub enum ServerEvent {
         entity: EcsEntity,
         buff_change: comp::BuffChange,
     },
+    /// Open or close the gate (or other toggleable sprite) at `pos`
+    ToggleSprite {
+        entity: EcsEntity,
+        pos: Vec3<i32>,
+    },
+    /// Strike the block at `pos` with the mining tool `entity` is wielding
+    MineBlock {
+        entity: EcsEntity,
+        pos: Vec3<i32>,
+    },
+    /// Finish crafting a recipe that took time to craft
+    FinishCraft {
+        entity: EcsEntity,
+        recipe: String,
+    },
 }
 
 pub struct EventBus<E> {
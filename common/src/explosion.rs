@@ -1,3 +1,4 @@
+use crate::comp::Element;
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -9,4 +10,6 @@ pub struct Explosion {
     pub min_heal: u32,
     pub terrain_destruction_power: f32,
     pub energy_regen: u32,
+    /// Element of the damage the explosion deals
+    pub element: Option<Element>,
 }
@@ -0,0 +1,161 @@
+//! Crops that can be planted from seeds and harvested once fully grown.
+
+use crate::terrain::{Block, BlockKind, SpriteKind};
+use enum_iterator::IntoEnumIterator;
+use serde::{Deserialize, Serialize};
+use vek::Rgb;
+
+/// Length of an in-game day, in units of `TimeOfDay`
+pub const DAY_LENGTH: f64 = 24.0 * 60.0 * 60.0;
+
+/// Colour of the earth left behind when grass is tilled for planting
+pub const TILLED_SOIL_COLOR: Rgb<u8> = Rgb::new(86, 59, 38);
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize, IntoEnumIterator)]
+pub enum CropKind {
+    Wheat,
+    Flax,
+    Corn,
+    Cabbage,
+    Carrot,
+    Tomato,
+    Radish,
+    Turnip,
+    Pumpkin,
+}
+
+impl CropKind {
+    /// The sprites the crop passes through as it grows, ending with the
+    /// harvestable one.
+    pub fn stages(self) -> &'static [SpriteKind] {
+        match self {
+            CropKind::Wheat => &[
+                SpriteKind::Seedling,
+                SpriteKind::WheatGreen,
+                SpriteKind::WheatYellow,
+            ],
+            CropKind::Flax => &[SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Flax],
+            CropKind::Corn => &[SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Corn],
+            CropKind::Cabbage => &[
+                SpriteKind::Seedling,
+                SpriteKind::Sprout,
+                SpriteKind::Cabbage,
+            ],
+            CropKind::Carrot => &[SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Carrot],
+            CropKind::Tomato => &[SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Tomato],
+            CropKind::Radish => &[SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Radish],
+            CropKind::Turnip => &[SpriteKind::Seedling, SpriteKind::Sprout, SpriteKind::Turnip],
+            CropKind::Pumpkin => &[
+                SpriteKind::Seedling,
+                SpriteKind::Sprout,
+                SpriteKind::Pumpkin,
+            ],
+        }
+    }
+
+    /// The harvestable sprite of the crop
+    pub fn grown(self) -> SpriteKind { *self.stages().last().unwrap() }
+
+    /// How long the crop takes to become harvestable, in units of `TimeOfDay`
+    pub fn growth_time(self) -> f64 {
+        DAY_LENGTH
+            * match self {
+                CropKind::Radish => 1.0,
+                CropKind::Carrot | CropKind::Turnip => 1.5,
+                CropKind::Wheat | CropKind::Flax | CropKind::Cabbage | CropKind::Tomato => 2.0,
+                CropKind::Corn | CropKind::Pumpkin => 3.0,
+            }
+    }
+
+    /// The sprite of a crop planted at `planted_at`, at the time of day `now`
+    pub fn stage_at(self, planted_at: f64, now: f64) -> SpriteKind {
+        let stages = self.stages();
+        let progress = ((now - planted_at) / self.growth_time()).max(0.0);
+        // The final stage is only reached once the growth time has fully elapsed
+        let stage = (progress * (stages.len() - 1) as f64) as usize;
+        stages[stage.min(stages.len() - 1)]
+    }
+
+    /// The crop that `sprite` is the harvestable stage of, if any
+    pub fn from_grown(sprite: SpriteKind) -> Option<Self> {
+        CropKind::into_enum_iter().find(|crop| crop.grown() == sprite)
+    }
+
+    pub fn seed_item(self) -> &'static str {
+        match self {
+            CropKind::Wheat => "common.items.seeds.wheat",
+            CropKind::Flax => "common.items.seeds.flax",
+            CropKind::Corn => "common.items.seeds.corn",
+            CropKind::Cabbage => "common.items.seeds.cabbage",
+            CropKind::Carrot => "common.items.seeds.carrot",
+            CropKind::Tomato => "common.items.seeds.tomato",
+            CropKind::Radish => "common.items.seeds.radish",
+            CropKind::Turnip => "common.items.seeds.turnip",
+            CropKind::Pumpkin => "common.items.seeds.pumpkin",
+        }
+    }
+
+    /// Loot table rolled when the grown crop is harvested
+    pub fn harvest_table(self) -> &'static str {
+        match self {
+            CropKind::Wheat => "common.loot_tables.farming.wheat",
+            CropKind::Flax => "common.loot_tables.farming.flax",
+            CropKind::Corn => "common.loot_tables.farming.corn",
+            CropKind::Cabbage => "common.loot_tables.farming.cabbage",
+            CropKind::Carrot => "common.loot_tables.farming.carrot",
+            CropKind::Tomato => "common.loot_tables.farming.tomato",
+            CropKind::Radish => "common.loot_tables.farming.radish",
+            CropKind::Turnip => "common.loot_tables.farming.turnip",
+            CropKind::Pumpkin => "common.loot_tables.farming.pumpkin",
+        }
+    }
+}
+
+/// Whether seeds can be planted on top of this block
+pub fn is_farmland(block: Block) -> bool {
+    matches!(block.kind(), BlockKind::Earth | BlockKind::Grass)
+}
+
+/// Whether a crop can be planted in this block, which must be empty apart from
+/// small decorative sprites like grass
+pub fn can_plant_in(block: Block) -> bool {
+    block.is_air()
+        && block.get_sprite().map_or(true, |sprite| {
+            sprite == SpriteKind::Empty
+                || (!sprite.is_collectible()
+                    && sprite.solid_height().is_none()
+                    && sprite.toggled().is_none()
+                    && !sprite.has_ori()
+                    && !CropKind::into_enum_iter().any(|crop| crop.stages().contains(&sprite)))
+        })
+}
+
+/// The block left behind once grass or earth is tilled for planting
+pub fn tilled(block: Block) -> Block {
+    if is_farmland(block) {
+        Block::new(BlockKind::Earth, TILLED_SOIL_COLOR)
+    } else {
+        block
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crops_grow_through_every_stage() {
+        for crop in CropKind::into_enum_iter() {
+            let stages = crop.stages();
+            assert_eq!(crop.stage_at(0.0, 0.0), stages[0]);
+            assert_eq!(crop.stage_at(0.0, -DAY_LENGTH), stages[0]);
+            assert_eq!(
+                crop.stage_at(0.0, crop.growth_time() * 0.99),
+                stages[stages.len() - 2]
+            );
+            assert_eq!(crop.stage_at(0.0, crop.growth_time()), crop.grown());
+            assert_eq!(crop.stage_at(0.0, crop.growth_time() * 10.0), crop.grown());
+            assert_eq!(CropKind::from_grown(crop.grown()), Some(crop));
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use crate::{
+    assets::Asset,
     comp::{self, humanoid, Alignment, Body, Item},
+    loot::{LootContext, LootTable},
     npc::{self, NPC_NAMES},
 };
+use rand::Rng;
 use vek::*;
 
 pub enum EntityTemplate {
@@ -21,6 +24,8 @@ pub struct EntityInfo {
     pub scale: f32,
     pub level: Option<u32>,
     pub loot_drop: Option<Item>,
+    /// Waypoints the entity will walk between when idle
+    pub patrol_route: Vec<Vec3<f32>>,
 }
 
 impl EntityInfo {
@@ -38,6 +43,7 @@ impl EntityInfo {
             scale: 1.0,
             level: None,
             loot_drop: None,
+            patrol_route: Vec::new(),
         }
     }
 
@@ -93,6 +99,13 @@ impl EntityInfo {
         self
     }
 
+    /// Roll the entity's drop from a loot table. If the roll comes up empty the
+    /// entity drops whatever its body usually does.
+    pub fn with_loot_table(mut self, table: &str, ctx: &LootContext, rng: &mut impl Rng) -> Self {
+        self.loot_drop = LootTable::load_expect(table).roll(ctx, rng);
+        self
+    }
+
     pub fn with_scale(mut self, scale: f32) -> Self {
         self.scale = scale;
         self
@@ -103,6 +116,11 @@ impl EntityInfo {
         self
     }
 
+    pub fn with_patrol_route(mut self, patrol_route: Vec<Vec3<f32>>) -> Self {
+        self.patrol_route = patrol_route;
+        self
+    }
+
     pub fn with_automatic_name(mut self) -> Self {
         self.name = match &self.body {
             Body::Humanoid(body) => Some(get_npc_name(&NPC_NAMES.humanoid, body.species)),
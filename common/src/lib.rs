@@ -26,9 +26,11 @@ pub mod comp;
 pub mod effect;
 pub mod event;
 pub mod explosion;
+pub mod farming;
 pub mod figure;
 pub mod generation;
 pub mod loadout_builder;
+pub mod loot;
 pub mod lottery;
 pub mod metrics;
 pub mod msg;
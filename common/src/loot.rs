@@ -0,0 +1,391 @@
+//! Data-driven loot tables
+//!
+//! A [`LootTable`] is a weighted list of [`LootSpec`]s loaded from
+//! `common.loot_tables.*`. Entries can name an item, a range of an item, or
+//! another table to roll instead, and can be restricted to certain levels,
+//! biomes or sites with [`LootSpec::Conditional`].
+
+use crate::{
+    assets::{self, Asset, AssetFile},
+    comp::{biped_large, quadruped_medium, quadruped_small, Body, Item},
+    lottery::Lottery,
+    terrain::BiomeKind,
+};
+use hashbrown::HashMap;
+use rand::prelude::*;
+use serde::Deserialize;
+use std::io::BufReader;
+use tracing::warn;
+
+/// How many loot tables deep a roll can follow [`LootSpec::LootTable`], so that
+/// tables naming each other in a cycle can't recurse forever.
+const MAX_TABLE_DEPTH: usize = 8;
+
+/// The kind of site loot is rolled in
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum LootSite {
+    Settlement,
+    Dungeon,
+    Castle,
+}
+
+/// What is known about where and for whom loot is being rolled
+#[derive(Copy, Clone, Debug, Default)]
+pub struct LootContext {
+    pub level: Option<u32>,
+    pub biome: Option<BiomeKind>,
+    pub site: Option<LootSite>,
+}
+
+/// A requirement for an entry of a loot table to be rolled at all. A
+/// condition on something the context doesn't know about never holds.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum LootCondition {
+    MinLevel(u32),
+    MaxLevel(u32),
+    Biome(BiomeKind),
+    Site(LootSite),
+}
+
+impl LootCondition {
+    pub fn holds(&self, ctx: &LootContext) -> bool {
+        match self {
+            LootCondition::MinLevel(min) => ctx.level.map_or(false, |level| level >= *min),
+            LootCondition::MaxLevel(max) => ctx.level.map_or(false, |level| level <= *max),
+            LootCondition::Biome(biome) => ctx.biome == Some(*biome),
+            LootCondition::Site(site) => ctx.site == Some(*site),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub enum LootSpec {
+    /// A single item
+    Item(String),
+    /// An inclusive range of a stackable item
+    ItemQuantity(String, u32, u32),
+    /// Roll another loot table
+    LootTable(String),
+    /// Choose from a flat `Lottery` of item specifiers
+    Lottery(String),
+    /// Only take part in the roll when all of the conditions hold
+    Conditional(Vec<LootCondition>, Box<LootSpec>),
+    Nothing,
+}
+
+impl LootSpec {
+    fn applies(&self, ctx: &LootContext) -> bool {
+        match self {
+            LootSpec::Conditional(conditions, _) => conditions.iter().all(|c| c.holds(ctx)),
+            _ => true,
+        }
+    }
+
+    /// Quantity ranges have to be non-empty, including those of conditional
+    /// entries. Nested tables are checked when they are loaded themselves.
+    fn validate(&self) -> Result<(), String> {
+        match self {
+            LootSpec::ItemQuantity(item, min, max) if min > max => Err(format!(
+                "Empty quantity range {}..={} of '{}'",
+                min, max, item
+            )),
+            LootSpec::Conditional(_, spec) => spec.validate(),
+            _ => Ok(()),
+        }
+    }
+
+    fn to_item(&self, ctx: &LootContext, rng: &mut impl Rng, depth: usize) -> Option<Item> {
+        match self {
+            LootSpec::Item(item) => Some(Item::new_from_asset_expect(item)),
+            LootSpec::ItemQuantity(item, min, max) => {
+                let mut item = Item::new_from_asset_expect(item);
+                // Unstackable items just drop once
+                let _ = item.set_amount(rng.gen_range(*min, *max + 1));
+                Some(item)
+            },
+            LootSpec::LootTable(table) => {
+                if depth < MAX_TABLE_DEPTH {
+                    LootTable::load_expect(table).roll_nested(ctx, rng, depth + 1)
+                } else {
+                    warn!(?table, "Loot tables nest too deeply, dropping nothing");
+                    None
+                }
+            },
+            LootSpec::Lottery(table) => {
+                let lottery = Lottery::<String>::load_expect(table);
+                Some(Item::new_from_asset_expect(
+                    lottery.choose_seeded(rng.gen()),
+                ))
+            },
+            LootSpec::Conditional(_, spec) => spec.to_item(ctx, rng, depth),
+            LootSpec::Nothing => None,
+        }
+    }
+
+    fn nesting(&self, depth: usize) -> usize {
+        match self {
+            LootSpec::LootTable(_) if depth >= MAX_TABLE_DEPTH => depth,
+            LootSpec::LootTable(table) => LootTable::load_expect(table).nesting(depth + 1),
+            LootSpec::Conditional(_, spec) => spec.nesting(depth),
+            _ => depth,
+        }
+    }
+
+    /// Every item specifier this spec can produce, following nested tables
+    pub fn item_specifiers(&self) -> Vec<String> { self.item_specifiers_nested(0) }
+
+    fn item_specifiers_nested(&self, depth: usize) -> Vec<String> {
+        match self {
+            LootSpec::Item(item) | LootSpec::ItemQuantity(item, _, _) => vec![item.clone()],
+            LootSpec::LootTable(_) if depth >= MAX_TABLE_DEPTH => Vec::new(),
+            LootSpec::LootTable(table) => {
+                LootTable::load_expect(table).item_specifiers_nested(depth + 1)
+            },
+            LootSpec::Lottery(table) => Lottery::<String>::load_expect(table)
+                .iter()
+                .map(|(_, item)| item.clone())
+                .collect(),
+            LootSpec::Conditional(_, spec) => spec.item_specifiers_nested(depth),
+            LootSpec::Nothing => Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LootTable {
+    entries: Vec<(f32, LootSpec)>,
+}
+
+impl Asset for LootTable {
+    const ENDINGS: &'static [&'static str] = &["ron"];
+
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
+        let table = ron::de::from_reader::<BufReader<AssetFile>, Vec<(f32, LootSpec)>>(buf_reader)
+            .map(|entries| LootTable { entries })
+            .map_err(assets::Error::parse_error)?;
+        table.validate().map_err(assets::Error::parse_error)?;
+        Ok(table)
+    }
+}
+
+impl LootTable {
+    /// Pick an entry among those whose conditions hold in `ctx`. Returns `None`
+    /// if no entry applies or the chosen one drops nothing.
+    pub fn roll(&self, ctx: &LootContext, rng: &mut impl Rng) -> Option<Item> {
+        self.roll_nested(ctx, rng, 0)
+    }
+
+    /// Roll a table that was named by `depth` other tables
+    fn roll_nested(&self, ctx: &LootContext, rng: &mut impl Rng, depth: usize) -> Option<Item> {
+        let entries = self
+            .entries
+            .iter()
+            .filter(|(_, spec)| spec.applies(ctx))
+            .map(|(weight, spec)| (*weight, spec))
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            return None;
+        }
+        let lottery = Lottery::from_rates(entries.into_iter());
+        lottery.choose_seeded(rng.gen()).to_item(ctx, rng, depth)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.entries
+            .iter()
+            .try_for_each(|(_, spec)| spec.validate())
+    }
+
+    /// How deep the tables nested in this one go when this table is itself
+    /// `depth` tables deep, up to [`MAX_TABLE_DEPTH`]
+    fn nesting(&self, depth: usize) -> usize {
+        self.entries
+            .iter()
+            .map(|(_, spec)| spec.nesting(depth))
+            .max()
+            .unwrap_or(depth)
+    }
+
+    pub fn entries(&self) -> &[(f32, LootSpec)] { &self.entries }
+
+    /// Roll the table `rolls` times, for balancing. Returns how many rolls
+    /// dropped each item and how many of it dropped in total, most common
+    /// first.
+    pub fn simulate(&self, ctx: &LootContext, rolls: u32) -> Vec<(String, u32, u32)> {
+        let mut rng = thread_rng();
+        let mut drops = HashMap::<String, (u32, u32)>::new();
+        for item in (0..rolls).filter_map(|_| self.roll(ctx, &mut rng)) {
+            let entry = drops
+                .entry(item.item_definition_id().to_owned())
+                .or_default();
+            entry.0 += 1;
+            entry.1 += item.amount();
+        }
+        let mut drops = drops
+            .into_iter()
+            .map(|(item, (times, amount))| (item, times, amount))
+            .collect::<Vec<_>>();
+        drops.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        drops
+    }
+
+    /// Every item specifier this table can produce, following nested tables
+    pub fn item_specifiers(&self) -> Vec<String> { self.item_specifiers_nested(0) }
+
+    fn item_specifiers_nested(&self, depth: usize) -> Vec<String> {
+        self.entries
+            .iter()
+            .flat_map(|(_, spec)| spec.item_specifiers_nested(depth))
+            .collect()
+    }
+}
+
+/// Specifier of the loot table creatures with the given body drop from when
+/// they weren't given a drop of their own
+pub fn creature_loot_table(body: &Body) -> &'static str {
+    match body {
+        Body::Humanoid(_) => "common.loot_tables.creature.humanoid",
+        Body::QuadrupedSmall(body) => match body.species {
+            quadruped_small::Species::Dodarock => "common.loot_tables.creature.dodarock",
+            _ => "common.loot_tables.creature.animal",
+        },
+        Body::QuadrupedMedium(body) => match body.species {
+            quadruped_medium::Species::Frostfang | quadruped_medium::Species::Roshwalr => {
+                "common.loot_tables.creature.ice_animal"
+            },
+            _ => "common.loot_tables.creature.animal",
+        },
+        Body::BirdMedium(_) => "common.loot_tables.creature.bird_medium",
+        Body::BipedLarge(body) => match body.species {
+            biped_large::Species::Wendigo => "common.loot_tables.creature.wendigo",
+            _ => "common.loot_tables.creature.biped_large",
+        },
+        Body::Golem(_) => "common.loot_tables.creature.golem",
+        Body::Theropod(_) => "common.loot_tables.creature.theropod",
+        Body::Dragon(_) => "common.loot_tables.creature.dragon",
+        Body::QuadrupedLow(_) => "common.loot_tables.creature.quadruped_low",
+        _ => "common.loot_tables.creature.default",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loot_tables() {
+        let tables = [
+            "creature.humanoid",
+            "creature.animal",
+            "creature.dodarock",
+            "creature.ice_animal",
+            "creature.bird_medium",
+            "creature.wendigo",
+            "creature.biped_large",
+            "creature.golem",
+            "creature.theropod",
+            "creature.dragon",
+            "creature.quadruped_low",
+            "creature.default",
+            "sprite.chest",
+            "sprite.crate",
+            "dungeon.floor_0",
+            "dungeon.floor_1",
+            "dungeon.floor_2",
+            "dungeon.floor_3",
+            "dungeon.floor_4",
+            "dungeon.boss_crypt",
+            "dungeon.boss_mine",
+            "dungeon.boss_cultist",
+        ];
+
+        for table in tables.iter().map(|t| format!("common.loot_tables.{}", t)) {
+            // Loading checks the quantity ranges
+            let loot_table = LootTable::load_expect(&table);
+            assert!(
+                loot_table.nesting(0) < MAX_TABLE_DEPTH,
+                "Loot table '{}' nests too deeply or names itself",
+                table
+            );
+            for item_asset_specifier in loot_table.item_specifiers() {
+                assert!(
+                    Item::new_from_asset(&item_asset_specifier).is_ok(),
+                    "Invalid item '{}' in loot table '{}'",
+                    item_asset_specifier,
+                    table
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn empty_quantity_ranges_are_invalid() {
+        let quantity =
+            |min, max| LootSpec::ItemQuantity("common.items.food.apple".into(), min, max);
+        let table = |spec| LootTable {
+            entries: vec![(1.0, LootSpec::Nothing), (1.0, spec)],
+        };
+
+        assert!(table(quantity(1, 1)).validate().is_ok());
+        assert!(table(quantity(2, 1)).validate().is_err());
+        assert!(
+            table(LootSpec::Conditional(Vec::new(), Box::new(quantity(2, 1))))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn nested_tables_stop_at_the_depth_limit() {
+        let table = LootTable {
+            entries: vec![(
+                1.0,
+                LootSpec::LootTable("common.loot_tables.creature.animal".to_string()),
+            )],
+        };
+        let mut rng = thread_rng();
+
+        assert!(
+            table
+                .roll_nested(&LootContext::default(), &mut rng, MAX_TABLE_DEPTH)
+                .is_none()
+        );
+        assert!(table.item_specifiers_nested(MAX_TABLE_DEPTH).is_empty());
+    }
+
+    #[test]
+    fn conditional_entries_only_roll_when_they_hold() {
+        let table = LootTable {
+            entries: vec![(
+                1.0,
+                LootSpec::Conditional(
+                    vec![
+                        LootCondition::MinLevel(10),
+                        LootCondition::Site(LootSite::Dungeon),
+                    ],
+                    Box::new(LootSpec::Item("common.items.food.apple".to_string())),
+                ),
+            )],
+        };
+        let mut rng = thread_rng();
+
+        let low_level = LootContext {
+            level: Some(5),
+            site: Some(LootSite::Dungeon),
+            ..LootContext::default()
+        };
+        assert!(table.roll(&low_level, &mut rng).is_none());
+        assert!(table.roll(&LootContext::default(), &mut rng).is_none());
+
+        let high_level = LootContext {
+            level: Some(12),
+            ..low_level
+        };
+        assert_eq!(
+            table
+                .roll(&high_level, &mut rng)
+                .map(|item| item.item_definition_id().to_string()),
+            Some("common.items.food.apple".to_string())
+        );
+    }
+}
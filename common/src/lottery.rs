@@ -1,7 +1,7 @@
-use crate::assets::{self, Asset};
+use crate::assets::{self, Asset, AssetFile};
 use rand::prelude::*;
 use serde::{de::DeserializeOwned, Deserialize};
-use std::{fs::File, io::BufReader};
+use std::io::BufReader;
 
 #[derive(Clone, Debug, PartialEq, Deserialize)]
 pub struct Lottery<T> {
@@ -12,8 +12,8 @@ pub struct Lottery<T> {
 impl<T: DeserializeOwned + Send + Sync> Asset for Lottery<T> {
     const ENDINGS: &'static [&'static str] = &["ron"];
 
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, assets::Error> {
-        ron::de::from_reader::<BufReader<File>, Vec<(f32, T)>>(buf_reader)
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
+        ron::de::from_reader::<BufReader<AssetFile>, Vec<(f32, T)>>(buf_reader)
             .map(|items| Lottery::from_rates(items.into_iter()))
             .map_err(assets::Error::parse_error)
     }
@@ -48,7 +48,8 @@ impl<T> Lottery<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{assets::Asset, comp::Item};
+    use crate::{assets::Asset, comp::Item, farming::CropKind, terrain::BlockKind};
+    use enum_iterator::IntoEnumIterator;
 
     #[test]
     fn test_loot_table() {
@@ -62,4 +63,40 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_mining_loot_tables() {
+        for table in BlockKind::into_enum_iter().filter_map(|kind| kind.mine_drop_table()) {
+            let lottery = Lottery::<String>::load_expect(table);
+            for (_, item_asset_specifier) in lottery.iter() {
+                assert!(
+                    Item::new_from_asset(item_asset_specifier).is_ok(),
+                    "Invalid item '{}' in loot table '{}'",
+                    item_asset_specifier,
+                    table
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_farming_loot_tables() {
+        for crop in CropKind::into_enum_iter() {
+            let table = crop.harvest_table();
+            let lottery = Lottery::<String>::load_expect(table);
+            for (_, item_asset_specifier) in lottery.iter() {
+                assert!(
+                    Item::new_from_asset(item_asset_specifier).is_ok(),
+                    "Invalid item '{}' in loot table '{}'",
+                    item_asset_specifier,
+                    table
+                );
+            }
+            assert!(
+                Item::new_from_asset(crop.seed_item()).is_ok(),
+                "Invalid seed item '{}'",
+                crop.seed_item()
+            );
+        }
+    }
 }
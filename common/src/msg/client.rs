@@ -80,6 +80,8 @@ pub enum ClientGeneral {
     ChatMsg(String),
     Disconnect,
     Terminate,
+    /// Request the contents of assets advertised in `ServerInit::GameSync`
+    RequestAssets(Vec<String>),
 }
 
 impl ClientMsg {
@@ -121,7 +123,8 @@ impl ClientMsg {
                         //Always possible
                         ClientGeneral::ChatMsg(_)
                         | ClientGeneral::Disconnect
-                        | ClientGeneral::Terminate => true,
+                        | ClientGeneral::Terminate
+                        | ClientGeneral::RequestAssets(_) => true,
                     }
             },
             ClientMsg::Ping(_) => true,
@@ -54,6 +54,9 @@ pub enum ServerInit {
         client_timeout: Duration,
         world_map: crate::msg::world_msg::WorldMapMsg,
         recipe_book: RecipeBook,
+        /// Paths and content hashes of gameplay assets the server has from
+        /// overlays, which the client can request if its own differ
+        custom_assets: Vec<(String, u64)>,
     },
 }
 
@@ -72,10 +75,12 @@ pub enum ServerGeneral {
     CharacterSuccess,
     //Ingame related
     GroupUpdate(comp::group::ChangeNotification<sync::Uid>),
-    /// Indicate to the client that they are invited to join a group
+    /// Indicate to the client that they are invited to join a group or
+    /// faction
     GroupInvite {
         inviter: sync::Uid,
         timeout: std::time::Duration,
+        kind: comp::group::InviteKind,
     },
     /// Indicate to the client that their sent invite was not invalid and is
     /// currently pending
@@ -93,6 +98,9 @@ pub enum ServerGeneral {
     /// from an ingame state
     ExitInGameSuccess,
     InventoryUpdate(comp::Inventory, comp::InventoryUpdateEvent),
+    /// The contents of a container placed in the world, sent to players
+    /// that are close enough to have it open
+    ContainerUpdate(Vec3<i32>, comp::Inventory),
     TerrainChunkUpdate {
         key: Vec2<i32>,
         chunk: Result<Box<TerrainChunk>, ()>,
@@ -115,6 +123,9 @@ pub enum ServerGeneral {
     Disconnect(DisconnectReason),
     /// Send a popup notification such as "Waypoint Saved"
     Notification(Notification),
+    /// Asset files requested with `ClientGeneral::RequestAssets`, with their
+    /// paths relative to the asset root
+    CustomAssets(Vec<(String, Vec<u8>)>),
 }
 
 /*
@@ -209,6 +220,7 @@ impl ServerMsg {
                         | ServerGeneral::InviteComplete { .. }
                         | ServerGeneral::ExitInGameSuccess
                         | ServerGeneral::InventoryUpdate(_, _)
+                        | ServerGeneral::ContainerUpdate(_, _)
                         | ServerGeneral::TerrainChunkUpdate { .. }
                         | ServerGeneral::TerrainBlockUpdates(_)
                         | ServerGeneral::SetViewDistance(_)
@@ -226,7 +238,8 @@ impl ServerMsg {
                         | ServerGeneral::CreateEntity(_)
                         | ServerGeneral::DeleteEntity(_)
                         | ServerGeneral::Disconnect(_)
-                        | ServerGeneral::Notification(_) => true,
+                        | ServerGeneral::Notification(_)
+                        | ServerGeneral::CustomAssets(_) => true,
                     }
             },
             ServerMsg::Ping(_) => true,
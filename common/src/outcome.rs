@@ -1,4 +1,4 @@
-use crate::comp;
+use crate::{comp, sync::Uid};
 use comp::item::Reagent;
 use serde::{Deserialize, Serialize};
 use vek::*;
@@ -22,6 +22,20 @@ pub enum Outcome {
         body: comp::Body,
         vel: Vec3<f32>,
     },
+    /// Health of an entity was changed by an attack, a heal or the world.
+    /// Negative amounts are damage, positive ones healing.
+    Damage {
+        pos: Vec3<f32>,
+        attacker: Option<Uid>,
+        target: Uid,
+        /// `None` for health changes that weren't mitigated, like damage over
+        /// time
+        source: Option<comp::DamageSource>,
+        element: Option<comp::Element>,
+        pre_mitigation: f32,
+        post_mitigation: f32,
+        crit: bool,
+    },
 }
 
 impl Outcome {
@@ -29,6 +43,7 @@ impl Outcome {
         match self {
             Outcome::Explosion { pos, .. } => Some(*pos),
             Outcome::ProjectileShot { pos, .. } => Some(*pos),
+            Outcome::Damage { pos, .. } => Some(*pos),
         }
     }
 }
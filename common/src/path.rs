@@ -6,12 +6,13 @@ use crate::{
 };
 use hashbrown::hash_map::DefaultHashBuilder;
 use rand::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::iter::FromIterator;
 use vek::*;
 
 // Path
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Path<T> {
     nodes: Vec<T>,
 }
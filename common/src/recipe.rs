@@ -1,15 +1,87 @@
 use crate::{
-    assets::{self, Asset},
-    comp::{item::ItemDef, Inventory, Item},
+    assets::{self, Asset, AssetFile},
+    comp::{
+        item::{ItemDef, ItemKind, Quality},
+        Inventory, Item, MAX_PICKUP_RANGE_SQR,
+    },
+    terrain::{Block, SpriteKind},
+    vol::ReadVol,
 };
 use hashbrown::HashMap;
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::BufReader, sync::Arc};
+use std::{io::BufReader, sync::Arc};
+use vek::*;
+
+/// A block that has to be close by to craft some recipes
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum CraftingStation {
+    Anvil,
+    Cauldron,
+    Loom,
+}
+
+impl CraftingStation {
+    pub const ALL: [CraftingStation; 3] = [
+        CraftingStation::Anvil,
+        CraftingStation::Cauldron,
+        CraftingStation::Loom,
+    ];
+
+    pub fn sprite(&self) -> SpriteKind {
+        match self {
+            CraftingStation::Anvil => SpriteKind::Anvil,
+            CraftingStation::Cauldron => SpriteKind::Cauldron,
+            CraftingStation::Loom => SpriteKind::Loom,
+        }
+    }
+
+    pub fn from_sprite(sprite: SpriteKind) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|station| station.sprite() == sprite)
+    }
+
+    /// Whether a block of this station is within reach of `pos`
+    pub fn is_near<V: ReadVol<Vox = Block>>(&self, vol: &V, pos: Vec3<f32>) -> bool {
+        blocks_in_reach(pos).any(|wpos| {
+            vol.get(wpos)
+                .map_or(false, |block| block.get_sprite() == Some(self.sprite()))
+        })
+    }
+
+    /// Every kind of station with a block within reach of `pos`
+    pub fn all_near<V: ReadVol<Vox = Block>>(vol: &V, pos: Vec3<f32>) -> Vec<Self> {
+        let mut stations = blocks_in_reach(pos)
+            .filter_map(|wpos| vol.get(wpos).ok()?.get_sprite())
+            .filter_map(Self::from_sprite)
+            .collect::<Vec<_>>();
+        stations.sort_by_key(|station| *station as u8);
+        stations.dedup();
+        stations
+    }
+}
+
+/// Positions of the blocks close enough to `pos` to be interacted with
+fn blocks_in_reach(pos: Vec3<f32>) -> impl Iterator<Item = Vec3<i32>> {
+    let range = MAX_PICKUP_RANGE_SQR.sqrt().ceil() as i32;
+    let center = pos.map(|e| e.floor() as i32);
+    (-range..=range)
+        .flat_map(move |x| (-range..=range).map(move |y| (x, y)))
+        .flat_map(move |(x, y)| (-range..=range).map(move |z| center + Vec3::new(x, y, z)))
+        .filter(move |wpos| {
+            wpos.map(|e| e as f32 + 0.5).distance_squared(pos) < MAX_PICKUP_RANGE_SQR
+        })
+}
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Recipe {
     pub output: (Arc<ItemDef>, u32),
     pub inputs: Vec<(Arc<ItemDef>, u32)>,
+    /// The station the player must be near to craft the recipe, if any
+    pub station: Option<CraftingStation>,
+    /// How long crafting takes, in seconds
+    pub craft_time: f32,
 }
 
 #[allow(clippy::type_complexity)]
@@ -44,6 +116,13 @@ impl Recipe {
             .iter()
             .map(|(item_def, amount)| (item_def, *amount))
     }
+
+    /// Whether the recipe can be crafted at `pos` as far as its station is
+    /// concerned
+    pub fn station_in_range<V: ReadVol<Vox = Block>>(&self, vol: &V, pos: Vec3<f32>) -> bool {
+        self.station
+            .map_or(true, |station| station.is_near(vol, pos))
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -68,10 +147,18 @@ impl RecipeBook {
 impl Asset for RecipeBook {
     const ENDINGS: &'static [&'static str] = &["ron"];
 
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
         ron::de::from_reader::<
-            BufReader<File>,
-            HashMap<String, ((String, u32), Vec<(String, u32)>)>,
+            BufReader<AssetFile>,
+            HashMap<
+                String,
+                (
+                    (String, u32),
+                    Vec<(String, u32)>,
+                    Option<CraftingStation>,
+                    f32,
+                ),
+            >,
         >(buf_reader)
         .map_err(assets::Error::parse_error)
         .and_then(|recipes| {
@@ -79,7 +166,7 @@ impl Asset for RecipeBook {
                 recipes: recipes
                     .into_iter()
                     .map::<Result<(String, Recipe), assets::Error>, _>(
-                        |(name, ((output, amount), inputs))| {
+                        |(name, ((output, amount), inputs, station, craft_time))| {
                             Ok((name, Recipe {
                                 output: (ItemDef::load(&output)?, amount),
                                 inputs: inputs
@@ -88,6 +175,8 @@ impl Asset for RecipeBook {
                                         |(name, amount)| Ok((ItemDef::load(&name)?, amount)),
                                     )
                                     .collect::<Result<_, _>>()?,
+                                station,
+                                craft_time,
                             }))
                         },
                     )
@@ -98,3 +187,122 @@ impl Asset for RecipeBook {
 }
 
 pub fn default_recipe_book() -> Arc<RecipeBook> { RecipeBook::load_expect("common.recipe_book") }
+
+/// The reverse of the recipe book: what armor and weapons can be salvaged into
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SalvageBook {
+    /// Salvage results for particular items
+    items: HashMap<String, Vec<(Arc<ItemDef>, u32)>>,
+    /// Salvage results for any other armor or weapon, by its quality
+    qualities: HashMap<Quality, Vec<(Arc<ItemDef>, u32)>>,
+}
+
+impl SalvageBook {
+    /// The ingredients an item salvages into, if it can be salvaged
+    pub fn get(&self, item: &Item) -> Option<&[(Arc<ItemDef>, u32)]> {
+        self.items
+            .get(item.item_definition_id())
+            .or_else(|| match item.kind() {
+                ItemKind::Tool(_) | ItemKind::Armor(_) => self.qualities.get(&item.quality()),
+                _ => None,
+            })
+            .map(|outputs| outputs.as_slice())
+    }
+
+    /// Salvage the item in an inventory slot, returning `None` if it can't be
+    /// salvaged or the ingredients that didn't fit in the inventory otherwise
+    pub fn salvage(&self, inv: &mut Inventory, slot: usize) -> Option<Vec<Item>> {
+        let outputs = self.get(inv.get(slot)?)?;
+        inv.take(slot)?;
+
+        Some(
+            outputs
+                .iter()
+                .flat_map(|(item_def, amount)| (0..*amount).map(move |_| item_def))
+                .filter_map(|item_def| inv.push(Item::new(Arc::clone(item_def))))
+                .collect(),
+        )
+    }
+}
+
+impl Asset for SalvageBook {
+    const ENDINGS: &'static [&'static str] = &["ron"];
+
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
+        #[derive(Deserialize)]
+        struct RawSalvageBook {
+            items: HashMap<String, Vec<(String, u32)>>,
+            qualities: HashMap<Quality, Vec<(String, u32)>>,
+        }
+
+        fn load_outputs(
+            outputs: Vec<(String, u32)>,
+        ) -> Result<Vec<(Arc<ItemDef>, u32)>, assets::Error> {
+            outputs
+                .into_iter()
+                .map(|(name, amount)| Ok((ItemDef::load(&name)?, amount)))
+                .collect()
+        }
+
+        let raw = ron::de::from_reader::<_, RawSalvageBook>(buf_reader)
+            .map_err(assets::Error::parse_error)?;
+        Ok(SalvageBook {
+            items: raw
+                .items
+                .into_iter()
+                .map(|(name, outputs)| Ok((name, load_outputs(outputs)?)))
+                .collect::<Result<_, assets::Error>>()?,
+            qualities: raw
+                .qualities
+                .into_iter()
+                .map(|(quality, outputs)| Ok((quality, load_outputs(outputs)?)))
+                .collect::<Result<_, assets::Error>>()?,
+        })
+    }
+}
+
+pub fn default_salvage_book() -> Arc<SalvageBook> {
+    SalvageBook::load_expect("common.salvage_book")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recipe_book_stations_have_sprites() {
+        let recipe_book = default_recipe_book();
+        for (_, recipe) in recipe_book.iter() {
+            if let Some(station) = recipe.station {
+                assert_eq!(
+                    CraftingStation::from_sprite(station.sprite()),
+                    Some(station)
+                );
+            }
+            assert!(recipe.craft_time >= 0.0);
+        }
+    }
+
+    #[test]
+    fn salvaging_replaces_item_with_ingredients() {
+        let salvage_book = default_salvage_book();
+        let mut inv = Inventory::new_empty();
+        inv.push(Item::new_from_asset_expect(
+            "common.items.weapons.sword.starter_sword",
+        ));
+        let ingredients = salvage_book
+            .get(inv.get(0).unwrap())
+            .expect("Swords should be salvageable")
+            .to_vec();
+
+        assert_eq!(salvage_book.salvage(&mut inv, 0), Some(Vec::new()));
+        for (item_def, amount) in ingredients {
+            assert_eq!(inv.item_count(&item_def), u64::from(amount));
+        }
+        assert!(!inv.contains(&Item::new_from_asset_expect(
+            "common.items.weapons.sword.starter_sword"
+        )));
+        // Nothing left to salvage
+        assert_eq!(salvage_book.salvage(&mut inv, 0), None);
+    }
+}
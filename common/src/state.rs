@@ -10,6 +10,7 @@ use crate::{
     vol::{ReadVol, WriteVol},
 };
 use hashbrown::{HashMap, HashSet};
+use rand::{rngs::StdRng, RngCore, SeedableRng};
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use serde::{Deserialize, Serialize};
 use specs::{
@@ -36,6 +37,51 @@ pub struct Time(pub f64);
 #[derive(Default)]
 pub struct DeltaTime(pub f32);
 
+/// A resource that combat randomness (critical hits, bleeding, ...) is drawn
+/// from. It is reseeded from the world seed and the tick number at the start
+/// of every tick, so a fight can be reproduced given the same seed and inputs.
+pub struct CombatRng {
+    seed: u64,
+    tick: u64,
+    rng: StdRng,
+}
+
+impl CombatRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            tick: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 { self.seed }
+
+    pub fn tick(&self) -> u64 { self.tick }
+
+    /// Move on to the next tick, reseeding the generator for it
+    pub fn next_tick(&mut self) {
+        self.tick += 1;
+        self.rng = StdRng::seed_from_u64(self.seed ^ self.tick.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    }
+}
+
+impl Default for CombatRng {
+    fn default() -> Self { Self::new(0) }
+}
+
+impl RngCore for CombatRng {
+    fn next_u32(&mut self) -> u32 { self.rng.next_u32() }
+
+    fn next_u64(&mut self) -> u64 { self.rng.next_u64() }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) { self.rng.fill_bytes(dest) }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
 /// At what point should we stop speeding up physics to compensate for lag? If
 /// we speed physics up too fast, we'd skip important physics events like
 /// collisions. This constant determines the upper limit. If delta time exceeds
@@ -167,6 +213,7 @@ impl State {
         ecs.register::<comp::group::Invite>();
         ecs.register::<comp::group::PendingInvites>();
         ecs.register::<comp::Beam>();
+        ecs.register::<comp::PvpFlag>();
 
         // Register synced resources used by the ECS.
         ecs.insert(TimeOfDay(0.0));
@@ -174,6 +221,7 @@ impl State {
         // Register unsynced resources used by the ECS.
         ecs.insert(Time(0.0));
         ecs.insert(DeltaTime(0.0));
+        ecs.insert(CombatRng::default());
         ecs.insert(TerrainGrid::new().unwrap());
         ecs.insert(BlockChange::default());
         ecs.insert(TerrainChanges::default());
@@ -181,6 +229,7 @@ impl State {
         // TODO: only register on the server
         ecs.insert(EventBus::<ServerEvent>::default());
         ecs.insert(comp::group::GroupManager::default());
+        ecs.insert(comp::PvpRules::default());
         ecs.insert(RegionMap::new());
         ecs.insert(SysMetrics::default());
 
@@ -356,6 +405,7 @@ impl State {
         // Beyond a delta time of MAX_DELTA_TIME, start lagging to avoid skipping
         // important physics events.
         self.ecs.write_resource::<DeltaTime>().0 = dt.as_secs_f32().min(MAX_DELTA_TIME);
+        self.ecs.write_resource::<CombatRng>().next_tick();
 
         if update_terrain_and_regions {
             self.update_region_map();
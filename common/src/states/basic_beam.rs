@@ -128,6 +128,7 @@ impl CharacterBehavior for Data {
                         energy_cost,
                         duration: self.static_data.beam_duration,
                         owner: Some(*data.uid),
+                        element: data.loadout.active_element(),
                     };
                     let pos = Pos(data.pos.0 + Vec3::new(0.0, 0.0, self.offset));
                     // Create beam segment
@@ -146,6 +146,7 @@ impl CharacterBehavior for Data {
                 time_left: Duration::from_secs(15),
                 owner: None,
                 ignore_group: true,
+                element: data.loadout.active_element(),
             };
             projectile.owner = Some(*data.uid);
             update.server_events.push_front(ServerEvent::Shoot {
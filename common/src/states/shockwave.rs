@@ -74,6 +74,7 @@ impl CharacterBehavior for Data {
                         knockback: self.static_data.knockback,
                         requires_ground: self.static_data.requires_ground,
                         owner: Some(*data.uid),
+                        element: data.loadout.active_element(),
                     };
                     update.server_events.push_front(ServerEvent::Shockwave {
                         properties,
@@ -1,7 +1,7 @@
 use crate::{
     comp::{
         item::{Hands, ItemKind, Tool},
-        Body, CharacterState, StateUpdate,
+        Body, CharacterAbility, CharacterState, ItemConfig, SkillSet, StateUpdate,
     },
     event::LocalEvent,
     states::*,
@@ -82,7 +82,7 @@ fn basic_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32) {
         data.body.base_accel()
     } else {
         BASE_HUMANOID_AIR_ACCEL
-    };
+    } * movement_modifier(data);
 
     update.vel.0 =
         update.vel.0 + Vec2::broadcast(data.dt.0) * data.inputs.move_dir * accel * efficiency;
@@ -96,7 +96,7 @@ pub fn forward_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32,
         data.body.base_accel()
     } else {
         BASE_HUMANOID_AIR_ACCEL
-    };
+    } * movement_modifier(data);
 
     update.vel.0 += Vec2::broadcast(data.dt.0)
         * accel
@@ -105,6 +105,12 @@ pub fn forward_move(data: &JoinData, update: &mut StateUpdate, efficiency: f32,
     handle_orientation(data, update, data.body.base_ori_rate() * efficiency);
 }
 
+/// How much buffs speed up or slow down the character
+fn movement_modifier(data: &JoinData) -> f32 {
+    data.buffs
+        .map_or(1.0, |buffs| buffs.movement_speed_modifier())
+}
+
 pub fn handle_orientation(data: &JoinData, update: &mut StateUpdate, rate: f32) {
     // Set direction based on move direction
     let ori_dir = if update.character.is_block() || update.character.is_attack() {
@@ -243,17 +249,32 @@ pub fn handle_jump(data: &JoinData, update: &mut StateUpdate) {
     }
 }
 
+/// An ability of the given item, adjusted by the character's skills with the
+/// item
+fn skilled_ability(
+    skill_set: &SkillSet,
+    item_config: Option<&ItemConfig>,
+    ability: impl FnOnce(&ItemConfig) -> Option<&CharacterAbility>,
+) -> Option<CharacterAbility> {
+    let item_config = item_config?;
+    let ability = ability(item_config)?.clone();
+    Some(match item_config.item.kind() {
+        ItemKind::Tool(tool) => ability.adjusted_by_skills(&tool.kind, skill_set),
+        _ => ability,
+    })
+}
+
 /// Will attempt to go into `loadout.active_item.ability1`
 pub fn handle_ability1_input(data: &JoinData, update: &mut StateUpdate) {
     if data.inputs.primary.is_pressed() {
-        if let Some(ability) = data
-            .loadout
-            .active_item
-            .as_ref()
-            .and_then(|i| i.ability1.as_ref())
-            .filter(|ability| ability.requirements_paid(data, update))
+        if let Some(ability) = skilled_ability(
+            &data.stats.skill_set,
+            data.loadout.active_item.as_ref(),
+            |i| i.ability1.as_ref(),
+        )
+        .filter(|ability| ability.requirements_paid(data, update))
         {
-            update.character = (ability, AbilityKey::Mouse1).into();
+            update.character = (&ability, AbilityKey::Mouse1).into();
         }
     }
 }
@@ -276,25 +297,25 @@ pub fn handle_ability2_input(data: &JoinData, update: &mut StateUpdate) {
             second_tool_kind.map(|tk| tk.hands()),
         ) {
             (Some(Hands::TwoHand), _) => {
-                if let Some(ability) = data
-                    .loadout
-                    .active_item
-                    .as_ref()
-                    .and_then(|i| i.ability2.as_ref())
-                    .filter(|ability| ability.requirements_paid(data, update))
+                if let Some(ability) = skilled_ability(
+                    &data.stats.skill_set,
+                    data.loadout.active_item.as_ref(),
+                    |i| i.ability2.as_ref(),
+                )
+                .filter(|ability| ability.requirements_paid(data, update))
                 {
-                    update.character = (ability, AbilityKey::Mouse2).into();
+                    update.character = (&ability, AbilityKey::Mouse2).into();
                 }
             },
             (_, Some(Hands::OneHand)) => {
-                if let Some(ability) = data
-                    .loadout
-                    .second_item
-                    .as_ref()
-                    .and_then(|i| i.ability2.as_ref())
-                    .filter(|ability| ability.requirements_paid(data, update))
+                if let Some(ability) = skilled_ability(
+                    &data.stats.skill_set,
+                    data.loadout.second_item.as_ref(),
+                    |i| i.ability2.as_ref(),
+                )
+                .filter(|ability| ability.requirements_paid(data, update))
                 {
-                    update.character = (ability, AbilityKey::Mouse2).into();
+                    update.character = (&ability, AbilityKey::Mouse2).into();
                 }
             },
             (_, _) => {},
@@ -305,14 +326,14 @@ pub fn handle_ability2_input(data: &JoinData, update: &mut StateUpdate) {
 /// Will attempt to go into `loadout.active_item.ability3`
 pub fn handle_ability3_input(data: &JoinData, update: &mut StateUpdate) {
     if data.inputs.ability3.is_pressed() {
-        if let Some(ability) = data
-            .loadout
-            .active_item
-            .as_ref()
-            .and_then(|i| i.ability3.as_ref())
-            .filter(|ability| ability.requirements_paid(data, update))
+        if let Some(ability) = skilled_ability(
+            &data.stats.skill_set,
+            data.loadout.active_item.as_ref(),
+            |i| i.ability3.as_ref(),
+        )
+        .filter(|ability| ability.requirements_paid(data, update))
         {
-            update.character = (ability, AbilityKey::Skill1).into();
+            update.character = (&ability, AbilityKey::Skill1).into();
         }
     }
 }
@@ -385,3 +406,33 @@ pub enum AbilityKey {
     Skill1,
     Dodge,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        comp::{Skill, SkillGroupType},
+        LoadoutBuilder,
+    };
+
+    #[test]
+    fn test_ability_input_uses_skills() {
+        let axe =
+            LoadoutBuilder::default_item_config_from_str("common.items.weapons.axe.starter_axe");
+
+        let mut skill_set = SkillSet::new();
+        skill_set.unlock_skill_group(SkillGroupType::Axes);
+        skill_set.add_skill_points(SkillGroupType::Axes, 2);
+        skill_set.unlock_skill(Skill::AxeDamage);
+        skill_set.unlock_skill(Skill::AxeExtraSpin);
+
+        // The spin of the axe is its second ability, as used by `handle_ability2_input`
+        let spins = |skill_set: &SkillSet| match skilled_ability(skill_set, Some(&axe), |i| {
+            i.ability2.as_ref()
+        }) {
+            Some(CharacterAbility::SpinMelee { num_spins, .. }) => num_spins,
+            ability => panic!("Expected a spin, got {:?}", ability),
+        };
+        assert_eq!(spins(&skill_set), spins(&SkillSet::new()) + 1);
+    }
+}
@@ -1,3 +1,4 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::{
     cmp::{Eq, PartialEq},
     fmt, hash,
@@ -29,7 +30,20 @@ impl<T> fmt::Debug for Id<T> {
 impl<T> hash::Hash for Id<T> {
     fn hash<H: hash::Hasher>(&self, h: &mut H) { self.0.hash(h); }
 }
+// NOTE: Implemented manually so that `T` isn't required to be serializable.
+impl<T> Serialize for Id<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+impl<'de, T> Deserialize<'de> for Id<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u64::deserialize(deserializer).map(|id| Self(id, PhantomData))
+    }
+}
 
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Store<T> {
     items: Vec<T>,
 }
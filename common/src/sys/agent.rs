@@ -27,6 +27,39 @@ use specs::{
 };
 use vek::*;
 
+/// How an NPC fights with the tool it is holding
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Tactic {
+    Melee,
+    Axe,
+    Hammer,
+    Sword,
+    RangedPowerup,
+    Staff,
+    StoneGolemBoss,
+    /// Tools like pickaxes, whose primary ability works on blocks rather than
+    /// on enemies, so they attack with their secondary ability
+    Tool,
+}
+
+impl Tactic {
+    fn for_tool(tool: Option<&ToolKind>) -> Self {
+        match tool {
+            Some(ToolKind::Bow(_)) => Tactic::RangedPowerup,
+            Some(ToolKind::Staff(_)) => Tactic::Staff,
+            Some(ToolKind::Hammer(_)) => Tactic::Hammer,
+            Some(ToolKind::Sword(_)) => Tactic::Sword,
+            Some(ToolKind::Axe(_)) => Tactic::Axe,
+            Some(ToolKind::Pick(_)) | Some(ToolKind::Hatchet(_)) => Tactic::Tool,
+            Some(ToolKind::NpcWeapon(kind)) => match kind.as_str() {
+                "StoneGolemsFist" => Tactic::StoneGolemBoss,
+                _ => Tactic::Melee,
+            },
+            _ => Tactic::Melee,
+        }
+    }
+}
+
 /// This system will allow NPCs to modify their controller
 pub struct Sys;
 impl<'a> System<'a> for Sys {
@@ -93,6 +126,39 @@ impl<'a> System<'a> for Sys {
     ) {
         let start_time = std::time::Instant::now();
         span!(_guard, "run", "agent::Sys::run");
+
+        // Villagers that were recently attacked raise an alarm, which guards nearby
+        // will respond to
+        let alarms = (&positions, &stats, &alignments)
+            .join()
+            .filter(|(_, victim_stats, alignment)| {
+                **alignment == Alignment::Npc
+                    && !victim_stats.is_dead
+                    && victim_stats.health.last_change.0 < 3.0
+                    && victim_stats.health.last_change.1.amount < 0
+            })
+            .filter_map(
+                |(pos, victim_stats, _)| match victim_stats.health.last_change.1.cause {
+                    comp::HealthSource::Attack { by }
+                    | comp::HealthSource::Projectile { owner: Some(by) }
+                    | comp::HealthSource::Energy { owner: Some(by) }
+                    | comp::HealthSource::Buff { owner: Some(by) }
+                    | comp::HealthSource::Explosion { owner: Some(by) } => {
+                        let attacker = uid_allocator.retrieve_entity_internal(by.id())?;
+                        // Squabbles between villagers don't raise the alarm
+                        if alignments.get(attacker) == Some(&Alignment::Npc)
+                            || stats.get(attacker).map_or(true, |a| a.is_dead)
+                        {
+                            None
+                        } else {
+                            Some((pos.0, attacker))
+                        }
+                    },
+                    _ => None,
+                },
+            )
+            .collect::<Vec<_>>();
+
         for (
             entity,
             energy,
@@ -191,6 +257,8 @@ impl<'a> System<'a> for Sys {
             const SIGHT_DIST: f32 = 80.0;
             const MIN_ATTACK_DIST: f32 = 2.0;
             const MAX_FLEE_DIST: f32 = 20.0;
+            const PATROL_WAYPOINT_DIST: f32 = 4.0;
+            const ALARM_DIST: f32 = 64.0;
 
             let scale = scales.get(entity).map(|s| s.0).unwrap_or(1.0);
 
@@ -207,49 +275,84 @@ impl<'a> System<'a> for Sys {
             'activity: {
                 match &mut agent.activity {
                     Activity::Idle(bearing) => {
-                        *bearing += Vec2::new(
-                            thread_rng().gen::<f32>() - 0.5,
-                            thread_rng().gen::<f32>() - 0.5,
-                        ) * 0.1
-                            - *bearing * 0.003
-                            - agent.patrol_origin.map_or(Vec2::zero(), |patrol_origin| {
-                                (pos.0 - patrol_origin).xy() * 0.0002
-                            });
-
-                        // Stop if we're too close to a wall
-                        *bearing *= 0.1
-                            + if terrain
-                                .ray(
-                                    pos.0 + Vec3::unit_z(),
-                                    pos.0
-                                        + Vec3::from(*bearing)
-                                            .try_normalized()
-                                            .unwrap_or(Vec3::unit_y())
-                                            * 5.0
-                                        + Vec3::unit_z(),
-                                )
-                                .until(Block::is_solid)
-                                .cast()
-                                .1
-                                .map_or(true, |b| b.is_none())
+                        if let Some(patrol) = agent
+                            .patrol
+                            .as_mut()
+                            .filter(|patrol| !patrol.route.is_empty())
+                        {
+                            // Walk the patrol route, one waypoint at a time
+                            let waypoint = patrol.waypoint().unwrap_or(pos.0);
+                            if pos.0.xy().distance_squared(waypoint.xy())
+                                < PATROL_WAYPOINT_DIST.powf(2.0)
                             {
-                                0.9
+                                patrol.advance();
+                            } else if let Some((bearing, speed)) = patrol.chaser.chase(
+                                &*terrain,
+                                pos.0,
+                                vel.0,
+                                waypoint,
+                                TraversalConfig {
+                                    node_tolerance,
+                                    slow_factor,
+                                    on_ground: physics_state.on_ground,
+                                    min_tgt_dist: 1.0,
+                                },
+                            ) {
+                                inputs.move_dir =
+                                    bearing.xy().try_normalized().unwrap_or(Vec2::zero())
+                                        * speed.min(0.5);
+                                inputs.jump.set_state(bearing.z > 1.5);
+                                inputs.swimup.set_state(bearing.z > 0.5);
+                                inputs.swimdown.set_state(bearing.z < 0.5);
                             } else {
-                                0.0
-                            };
+                                // The waypoint can't be reached, so skip it
+                                patrol.advance();
+                            }
+                        } else {
+                            *bearing += Vec2::new(
+                                thread_rng().gen::<f32>() - 0.5,
+                                thread_rng().gen::<f32>() - 0.5,
+                            ) * 0.1
+                                - *bearing * 0.003
+                                - agent.patrol_origin.map_or(Vec2::zero(), |patrol_origin| {
+                                    (pos.0 - patrol_origin).xy() * 0.0002
+                                });
+
+                            // Stop if we're too close to a wall
+                            *bearing *= 0.1
+                                + if terrain
+                                    .ray(
+                                        pos.0 + Vec3::unit_z(),
+                                        pos.0
+                                            + Vec3::from(*bearing)
+                                                .try_normalized()
+                                                .unwrap_or(Vec3::unit_y())
+                                                * 5.0
+                                            + Vec3::unit_z(),
+                                    )
+                                    .until(Block::is_solid)
+                                    .cast()
+                                    .1
+                                    .map_or(true, |b| b.is_none())
+                                {
+                                    0.9
+                                } else {
+                                    0.0
+                                };
 
-                        if bearing.magnitude_squared() > 0.5f32.powf(2.0) {
-                            inputs.move_dir = *bearing * 0.65;
-                        }
+                            if bearing.magnitude_squared() > 0.5f32.powf(2.0) {
+                                inputs.move_dir = *bearing * 0.65;
+                            }
 
-                        // Put away weapon
-                        if thread_rng().gen::<f32>() < 0.005 {
-                            controller.actions.push(ControlAction::Unwield);
-                        }
+                            // Put away weapon
+                            if thread_rng().gen::<f32>() < 0.005 {
+                                controller.actions.push(ControlAction::Unwield);
+                            }
 
-                        // Sit
-                        if thread_rng().gen::<f32>() < 0.0035 {
-                            controller.actions.push(ControlAction::Sit);
+                            // Sit
+                            if thread_rng().gen::<f32>() < 0.0035 {
+                                controller.actions.push(ControlAction::Sit);
+                            }
                         }
 
                         // Sometimes try searching for new targets
@@ -297,35 +400,14 @@ impl<'a> System<'a> for Sys {
                         powerup,
                         ..
                     } => {
-                        #[derive(Eq, PartialEq)]
-                        enum Tactic {
-                            Melee,
-                            Axe,
-                            Hammer,
-                            Sword,
-                            RangedPowerup,
-                            Staff,
-                            StoneGolemBoss,
-                        }
-
-                        let tactic = match loadout.active_item.as_ref().and_then(|ic| {
-                            if let ItemKind::Tool(tool) = &ic.item.kind() {
-                                Some(&tool.kind)
-                            } else {
-                                None
-                            }
-                        }) {
-                            Some(ToolKind::Bow(_)) => Tactic::RangedPowerup,
-                            Some(ToolKind::Staff(_)) => Tactic::Staff,
-                            Some(ToolKind::Hammer(_)) => Tactic::Hammer,
-                            Some(ToolKind::Sword(_)) => Tactic::Sword,
-                            Some(ToolKind::Axe(_)) => Tactic::Axe,
-                            Some(ToolKind::NpcWeapon(kind)) => match kind.as_str() {
-                                "StoneGolemsFist" => Tactic::StoneGolemBoss,
-                                _ => Tactic::Melee,
-                            },
-                            _ => Tactic::Melee,
-                        };
+                        let tactic =
+                            Tactic::for_tool(loadout.active_item.as_ref().and_then(|ic| {
+                                if let ItemKind::Tool(tool) = &ic.item.kind() {
+                                    Some(&tool.kind)
+                                } else {
+                                    None
+                                }
+                            }));
 
                         if let (Some(tgt_pos), Some(tgt_stats), tgt_alignment) = (
                             positions.get(*target),
@@ -407,6 +489,7 @@ impl<'a> System<'a> for Sys {
                                     | Tactic::Melee
                                     | Tactic::Hammer
                                     | Tactic::StoneGolemBoss => inputs.primary.set_state(true),
+                                    Tactic::Tool => inputs.secondary.set_state(true),
                                     Tactic::Staff => {
                                         // Kind of arbitrary values, but feel right in game
                                         if energy.current() > 800 && thread_rng().gen::<f32>() > 0.8
@@ -434,7 +517,8 @@ impl<'a> System<'a> for Sys {
                                 }
                             } else if dist_sqrd < MAX_CHASE_DIST.powf(2.0)
                                 || (dist_sqrd < SIGHT_DIST.powf(2.0)
-                                    && (!*been_close || !matches!(tactic, Tactic::Melee)))
+                                    && (!*been_close
+                                        || !matches!(tactic, Tactic::Melee | Tactic::Tool)))
                             {
                                 let can_see_tgt = terrain
                                     .ray(pos.0 + Vec3::unit_z(), tgt_pos.0 + Vec3::unit_z())
@@ -533,7 +617,7 @@ impl<'a> System<'a> for Sys {
                                 }
 
                                 if dist_sqrd < 16.0f32.powf(2.0)
-                                    && matches!(tactic, Tactic::Melee)
+                                    && matches!(tactic, Tactic::Melee | Tactic::Tool)
                                     && thread_rng().gen::<f32>() < 0.02
                                 {
                                     inputs.roll.set_state(true);
@@ -636,6 +720,34 @@ impl<'a> System<'a> for Sys {
                 }
             }
 
+            // Guards respond to alarms raised nearby
+            if agent.patrol.is_some()
+                && alignment == Some(Alignment::Npc)
+                && !agent.activity.is_attack()
+            {
+                if let Some((_, attacker)) = alarms
+                    .iter()
+                    .filter(|(alarm_pos, attacker)| {
+                        *attacker != entity
+                            && alarm_pos.distance_squared(pos.0) < ALARM_DIST.powf(2.0)
+                    })
+                    .min_by_key(|(alarm_pos, _)| (alarm_pos.distance_squared(pos.0) * 100.0) as i32)
+                {
+                    if agent.can_speak {
+                        let msg = "npc.speech.guard_alarm".to_string();
+                        event_emitter.emit(ServerEvent::Chat(UnresolvedChatMsg::npc(*uid, msg)));
+                    }
+
+                    agent.activity = Activity::Attack {
+                        target: *attacker,
+                        chaser: Chaser::default(),
+                        time: time.0,
+                        been_close: false,
+                        powerup: 0.0,
+                    };
+                }
+            }
+
             // Follow owner if we're too far, or if they're under attack
             if let Some(Alignment::Owned(owner)) = alignment {
                 (|| {
@@ -703,3 +815,33 @@ impl<'a> System<'a> for Sys {
         );
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::comp::{CharacterAbility, Item, ItemConfig};
+
+    #[test]
+    fn test_pickaxes_and_hatchets_attack() {
+        for specifier in &[
+            "common.items.npc_weapons.tool.pickaxe",
+            "common.items.weapons.tool.pickaxe",
+            "common.items.weapons.tool.hatchet",
+        ] {
+            let item = Item::new_from_asset_expect(specifier);
+            let tool = item
+                .tool()
+                .expect("Pickaxes and hatchets are tools")
+                .kind
+                .clone();
+            let item_config = ItemConfig::from(item);
+
+            // The tactic presses the secondary input, which has to be an attack
+            assert_eq!(Tactic::for_tool(Some(&tool)), Tactic::Tool);
+            assert!(matches!(
+                item_config.ability2,
+                Some(CharacterAbility::BasicMelee { .. })
+            ));
+        }
+    }
+}
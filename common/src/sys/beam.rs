@@ -1,13 +1,16 @@
 use crate::{
     comp::{
-        group, Beam, BeamSegment, Body, CharacterState, Damage, DamageSource, Energy, EnergySource,
-        HealthChange, HealthSource, Last, Loadout, Ori, Pos, Scale, Stats,
+        group, Beam, BeamSegment, Body, BuffChange, BuffSource, CharacterState, Damage,
+        DamageSource, Energy, EnergySource, HealthChange, HealthSource, Last, Loadout, Ori, Player,
+        Pos, PvpFlag, PvpRules, Scale, Stats,
     },
     event::{EventBus, ServerEvent},
-    state::{DeltaTime, Time},
+    state::{CombatRng, DeltaTime, Time},
     sync::{Uid, UidAllocator},
 };
-use specs::{saveload::MarkerAllocator, Entities, Join, Read, ReadStorage, System, WriteStorage};
+use specs::{
+    saveload::MarkerAllocator, Entities, Join, Read, ReadStorage, System, Write, WriteStorage,
+};
 use std::time::Duration;
 use vek::*;
 
@@ -23,6 +26,8 @@ impl<'a> System<'a> for Sys {
         Read<'a, Time>,
         Read<'a, DeltaTime>,
         Read<'a, UidAllocator>,
+        Write<'a, CombatRng>,
+        Read<'a, PvpRules>,
         ReadStorage<'a, Uid>,
         ReadStorage<'a, Pos>,
         ReadStorage<'a, Last<Pos>>,
@@ -33,6 +38,8 @@ impl<'a> System<'a> for Sys {
         ReadStorage<'a, Loadout>,
         ReadStorage<'a, group::Group>,
         ReadStorage<'a, CharacterState>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, PvpFlag>,
         WriteStorage<'a, Energy>,
         WriteStorage<'a, BeamSegment>,
         WriteStorage<'a, Beam>,
@@ -46,6 +53,8 @@ impl<'a> System<'a> for Sys {
             time,
             dt,
             uid_allocator,
+            mut rng,
+            pvp_rules,
             uids,
             positions,
             last_positions,
@@ -56,6 +65,8 @@ impl<'a> System<'a> for Sys {
             loadouts,
             groups,
             character_states,
+            players,
+            pvp_flags,
             mut energies,
             mut beam_segments,
             mut beams,
@@ -168,7 +179,11 @@ impl<'a> System<'a> for Sys {
                     }
                     // Don't heal if outside group
                     // Don't damage in the same group
-                    let is_damage = !same_group && (beam_segment.damage > 0);
+                    let is_damage = !same_group
+                        && (beam_segment.damage > 0)
+                        && beam_owner.map_or(true, |owner| {
+                            pvp_rules.allows(&players, &pvp_flags, &positions, owner, b)
+                        });
                     let is_heal = same_group && (beam_segment.heal > 0);
                     if !is_heal && !is_damage {
                         continue;
@@ -189,15 +204,16 @@ impl<'a> System<'a> for Sys {
                     let mut damage = Damage {
                         healthchange,
                         source,
+                        element: beam_segment.element,
                     };
 
                     let block = character_b.map(|c_b| c_b.is_block()).unwrap_or(false)
                         // TODO: investigate whether this calculation is proper for beams
                         && ori_b.0.angle_between(pos.0 - pos_b.0) < BLOCK_ANGLE.to_radians() / 2.0;
 
-                    if let Some(loadout) = loadouts.get(b) {
-                        damage.modify_damage(block, loadout);
-                    }
+                    let report = loadouts
+                        .get(b)
+                        .map(|loadout| damage.modify_damage(block, loadout, &mut *rng));
 
                     if is_damage {
                         server_emitter.emit(ServerEvent::Damage {
@@ -208,7 +224,16 @@ impl<'a> System<'a> for Sys {
                                     owner: beam_segment.owner,
                                 },
                             },
+                            report,
                         });
+                        if let Some(buff) = damage.on_hit_buff(block, BuffSource::Character {
+                            by: beam_segment.owner.unwrap_or(*uid),
+                        }) {
+                            server_emitter.emit(ServerEvent::Buff {
+                                entity: b,
+                                buff_change: BuffChange::Add(buff),
+                            });
+                        }
                         if beam_segment.lifesteal_eff > 0.0 {
                             server_emitter.emit(ServerEvent::Damage {
                                 uid: beam_segment.owner.unwrap_or(*uid),
@@ -219,6 +244,7 @@ impl<'a> System<'a> for Sys {
                                         by: beam_segment.owner,
                                     },
                                 },
+                                report: None,
                             });
                         }
                         if let Some(energy_mut) = beam_owner.and_then(|o| energies.get_mut(o)) {
@@ -245,6 +271,7 @@ impl<'a> System<'a> for Sys {
                                             by: beam_segment.owner,
                                         },
                                     },
+                                    report,
                                 });
                             }
                         }
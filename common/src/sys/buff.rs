@@ -1,7 +1,7 @@
 use crate::{
     comp::{
-        BuffCategory, BuffChange, BuffEffect, BuffId, BuffSource, Buffs, HealthChange,
-        HealthSource, Loadout, ModifierKind, Stats,
+        buff::active_ids, BuffCategory, BuffChange, BuffEffect, BuffId, BuffSource, Buffs,
+        HealthChange, HealthSource, Loadout, ModifierKind, Stats,
     },
     event::{EventBus, ServerEvent},
     state::DeltaTime,
@@ -66,9 +66,14 @@ impl<'a> System<'a> for Sys {
             stat.health.reset_max();
 
             // Iterator over the lists of buffs by kind
-            for buff_ids in buff_comp.kinds.values() {
-                // Get the strongest of this buff kind
-                if let Some(buff) = buff_comp.buffs.get_mut(&buff_ids[0]) {
+            let buff_map = &mut buff_comp.buffs;
+            for (kind, buff_ids) in buff_comp.kinds.iter() {
+                // Get the strongest of this buff kind, or all of them if the kind stacks
+                for id in active_ids(*kind, buff_ids) {
+                    let buff = match buff_map.get_mut(id) {
+                        Some(buff) => buff,
+                        None => continue,
+                    };
                     // Get buff owner?
                     let buff_owner = if let BuffSource::Character { by: owner } = buff.source {
                         Some(owner)
@@ -97,6 +102,7 @@ impl<'a> System<'a> for Sys {
                                             amount: *accumulated as i32,
                                             cause,
                                         },
+                                        report: None,
                                     });
                                     *accumulated = 0.0;
                                 };
@@ -113,6 +119,12 @@ impl<'a> System<'a> for Sys {
                                     );
                                 },
                             },
+                            // Read by the systems they affect through `Buffs`
+                            BuffEffect::MovementSpeed(_)
+                            | BuffEffect::DamageDealt(_)
+                            | BuffEffect::DamageTaken(_)
+                            | BuffEffect::EnergyRegen(_)
+                            | BuffEffect::CrowdControl(_) => {},
                         };
                     }
                 }
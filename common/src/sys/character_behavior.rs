@@ -1,7 +1,7 @@
 use crate::{
     comp::{
-        Attacking, Beam, Body, CharacterState, ControlAction, Controller, ControllerInputs, Energy,
-        Loadout, Mounting, Ori, PhysicsState, Pos, StateUpdate, Stats, Vel,
+        Attacking, Beam, Body, Buffs, CharacterState, ControlAction, Controller, ControllerInputs,
+        CrowdControl, Energy, Loadout, Mounting, Ori, PhysicsState, Pos, StateUpdate, Stats, Vel,
     },
     event::{EventBus, LocalEvent, ServerEvent},
     metrics::SysMetrics,
@@ -64,6 +64,7 @@ pub struct JoinData<'a> {
     pub body: &'a Body,
     pub physics: &'a PhysicsState,
     pub attacking: Option<&'a Attacking>,
+    pub buffs: Option<&'a Buffs>,
     pub updater: &'a LazyUpdate,
 }
 
@@ -90,6 +91,7 @@ pub type JoinTuple<'a> = (
     &'a PhysicsState,
     Option<&'a Attacking>,
     Option<&'a Beam>,
+    Option<&'a Buffs>,
 );
 
 fn incorporate_update(tuple: &mut JoinTuple, state_update: StateUpdate) {
@@ -127,6 +129,7 @@ impl<'a> JoinData<'a> {
             body: j.10,
             physics: j.11,
             attacking: j.12,
+            buffs: j.14,
             updater,
             dt,
         }
@@ -160,6 +163,7 @@ impl<'a> System<'a> for Sys {
         ReadStorage<'a, PhysicsState>,
         ReadStorage<'a, Attacking>,
         ReadStorage<'a, Beam>,
+        ReadStorage<'a, Buffs>,
         ReadStorage<'a, Uid>,
         ReadStorage<'a, Mounting>,
     );
@@ -187,6 +191,7 @@ impl<'a> System<'a> for Sys {
             physics_states,
             attacking_storage,
             beam_storage,
+            buff_storage,
             uids,
             mountings,
         ): Self::SystemData,
@@ -211,6 +216,7 @@ impl<'a> System<'a> for Sys {
             &physics_states,
             attacking_storage.maybe(),
             beam_storage.maybe(),
+            buff_storage.maybe(),
         )
             .join()
         {
@@ -229,6 +235,30 @@ impl<'a> System<'a> for Sys {
                 continue;
             }
 
+            // Crowd control keeps inputs from reaching the character state
+            if let Some(buffs) = tuple.14 {
+                let inputs = &mut tuple.8.inputs;
+                if !buffs.can_move() {
+                    inputs.release_movement();
+                }
+                if !buffs.can_use_abilities() {
+                    inputs.release_abilities();
+                }
+                let forced_state = if buffs.has_crowd_control(CrowdControl::Stun) {
+                    tuple.8.actions.clear();
+                    Some(CharacterState::Idle)
+                } else if !buffs.can_use_abilities() && tuple.2.get_unchecked().is_attack() {
+                    Some(CharacterState::Wielding)
+                } else {
+                    None
+                };
+                if let Some(state) = forced_state {
+                    if tuple.2.get_unchecked() != &state {
+                        *tuple.2.get_mut_unchecked() = state;
+                    }
+                }
+            }
+
             let actions = std::mem::replace(&mut tuple.8.actions, Vec::new());
             for action in actions {
                 let j = JoinData::new(&tuple, &updater, &dt);
@@ -266,6 +296,7 @@ impl<'a> System<'a> for Sys {
                     CharacterState::RepeaterRanged(data) => data.handle_event(&j, action),
                     CharacterState::Shockwave(data) => data.handle_event(&j, action),
                     CharacterState::BasicBeam(data) => data.handle_event(&j, action),
+                    CharacterState::Mining(data) => data.handle_event(&j, action),
                 };
                 local_emitter.append(&mut state_update.local_events);
                 server_emitter.append(&mut state_update.server_events);
@@ -298,6 +329,7 @@ impl<'a> System<'a> for Sys {
                 CharacterState::RepeaterRanged(data) => data.behavior(&j),
                 CharacterState::Shockwave(data) => data.behavior(&j),
                 CharacterState::BasicBeam(data) => data.behavior(&j),
+                CharacterState::Mining(data) => data.behavior(&j),
             };
 
             local_emitter.append(&mut state_update.local_events);
@@ -1,16 +1,17 @@
 use crate::{
     comp::{
         buff, group, Attacking, Body, CharacterState, Damage, DamageSource, HealthChange,
-        HealthSource, Loadout, Ori, Pos, Scale, Stats,
+        HealthSource, Loadout, Ori, Player, Pos, PvpFlag, PvpRules, Scale, Stats,
     },
     event::{EventBus, LocalEvent, ServerEvent},
     metrics::SysMetrics,
     span,
+    state::CombatRng,
     sync::Uid,
     util::Dir,
 };
-use rand::{thread_rng, Rng};
-use specs::{Entities, Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
+use rand::Rng;
+use specs::{Entities, Join, Read, ReadExpect, ReadStorage, System, Write, WriteStorage};
 use std::time::Duration;
 use vek::*;
 
@@ -27,6 +28,8 @@ impl<'a> System<'a> for Sys {
         Read<'a, EventBus<ServerEvent>>,
         Read<'a, EventBus<LocalEvent>>,
         ReadExpect<'a, SysMetrics>,
+        Write<'a, CombatRng>,
+        Read<'a, PvpRules>,
         ReadStorage<'a, Uid>,
         ReadStorage<'a, Pos>,
         ReadStorage<'a, Ori>,
@@ -36,6 +39,8 @@ impl<'a> System<'a> for Sys {
         ReadStorage<'a, Loadout>,
         ReadStorage<'a, group::Group>,
         ReadStorage<'a, CharacterState>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, PvpFlag>,
         WriteStorage<'a, Attacking>,
     );
 
@@ -46,6 +51,8 @@ impl<'a> System<'a> for Sys {
             server_bus,
             local_bus,
             sys_metrics,
+            mut rng,
+            pvp_rules,
             uids,
             positions,
             orientations,
@@ -55,6 +62,8 @@ impl<'a> System<'a> for Sys {
             loadouts,
             groups,
             character_states,
+            players,
+            pvp_flags,
             mut attacking_storage,
         ): Self::SystemData,
     ) {
@@ -77,6 +86,8 @@ impl<'a> System<'a> for Sys {
                 continue;
             }
             attack.applied = true;
+            // Melee attacks deal the element of the weapon they are made with
+            let element = loadouts.get(entity).and_then(Loadout::active_element);
 
             // Go through all other entities
             for (b, uid_b, pos_b, ori_b, scale_b_maybe, character_b, stats_b, body_b) in (
@@ -115,7 +126,9 @@ impl<'a> System<'a> for Sys {
                         .unwrap_or(false);
                     // Don't heal if outside group
                     // Don't damage in the same group
-                    let is_damage = !same_group && (attack.base_damage > 0);
+                    let is_damage = !same_group
+                        && (attack.base_damage > 0)
+                        && pvp_rules.allows(&players, &pvp_flags, &positions, entity, b);
                     let is_heal = same_group && (attack.base_heal > 0);
                     if !is_heal && !is_damage {
                         continue;
@@ -130,14 +143,15 @@ impl<'a> System<'a> for Sys {
                     let mut damage = Damage {
                         healthchange,
                         source,
+                        element,
                     };
 
                     let block = character_b.map(|c_b| c_b.is_block()).unwrap_or(false)
                         && ori_b.0.angle_between(pos.0 - pos_b.0) < BLOCK_ANGLE.to_radians() / 2.0;
 
-                    if let Some(loadout) = loadouts.get(b) {
-                        damage.modify_damage(block, loadout);
-                    }
+                    let report = loadouts
+                        .get(b)
+                        .map(|loadout| damage.modify_damage(block, loadout, &mut *rng));
 
                     if damage.healthchange != 0.0 {
                         let cause = if is_heal {
@@ -151,11 +165,21 @@ impl<'a> System<'a> for Sys {
                                 amount: damage.healthchange as i32,
                                 cause,
                             },
+                            report,
                         });
 
+                        if let Some(buff) =
+                            damage.on_hit_buff(block, buff::BuffSource::Character { by: *uid })
+                        {
+                            server_emitter.emit(ServerEvent::Buff {
+                                entity: b,
+                                buff_change: buff::BuffChange::Add(buff),
+                            });
+                        }
+
                         // Apply bleeding buff on melee hits with 10% chance
                         // TODO: Don't have buff uniformly applied on all melee attacks
-                        if thread_rng().gen::<f32>() < 0.1 {
+                        if rng.gen::<f32>() < 0.1 {
                             use buff::*;
                             server_emitter.emit(ServerEvent::Buff {
                                 entity: b,
@@ -115,6 +115,9 @@ impl<'a> System<'a> for Sys {
                         server_emitter.emit(ServerEvent::GroupManip(entity, manip))
                     },
                     ControlEvent::Respawn => server_emitter.emit(ServerEvent::Respawn(entity)),
+                    ControlEvent::ToggleSprite(pos) => {
+                        server_emitter.emit(ServerEvent::ToggleSprite { entity, pos })
+                    },
                 }
             }
         }
@@ -1,17 +1,19 @@
 use crate::{
     comp::{
-        projectile, Damage, DamageSource, Energy, EnergySource, Group, HealthChange, HealthSource,
-        Loadout, Ori, PhysicsState, Pos, Projectile, Vel,
+        projectile, BuffChange, BuffSource, Damage, DamageSource, Energy, EnergySource, Group,
+        HealthChange, HealthSource, Loadout, Ori, PhysicsState, Player, Pos, Projectile, PvpFlag,
+        PvpRules, Vel,
     },
     event::{EventBus, LocalEvent, ServerEvent},
     metrics::SysMetrics,
     span,
-    state::DeltaTime,
+    state::{CombatRng, DeltaTime},
     sync::UidAllocator,
     util::Dir,
 };
 use specs::{
-    saveload::MarkerAllocator, Entities, Join, Read, ReadExpect, ReadStorage, System, WriteStorage,
+    saveload::MarkerAllocator, Entities, Join, Read, ReadExpect, ReadStorage, System, Write,
+    WriteStorage,
 };
 use std::time::Duration;
 use vek::*;
@@ -27,6 +29,8 @@ impl<'a> System<'a> for Sys {
         Read<'a, EventBus<LocalEvent>>,
         Read<'a, EventBus<ServerEvent>>,
         ReadExpect<'a, SysMetrics>,
+        Write<'a, CombatRng>,
+        Read<'a, PvpRules>,
         ReadStorage<'a, Pos>,
         ReadStorage<'a, PhysicsState>,
         ReadStorage<'a, Vel>,
@@ -35,6 +39,8 @@ impl<'a> System<'a> for Sys {
         WriteStorage<'a, Energy>,
         ReadStorage<'a, Loadout>,
         ReadStorage<'a, Group>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, PvpFlag>,
     );
 
     fn run(
@@ -46,6 +52,8 @@ impl<'a> System<'a> for Sys {
             local_bus,
             server_bus,
             sys_metrics,
+            mut rng,
+            pvp_rules,
             positions,
             physics_states,
             velocities,
@@ -54,6 +62,8 @@ impl<'a> System<'a> for Sys {
             mut energies,
             loadouts,
             groups,
+            players,
+            pvp_flags,
         ): Self::SystemData,
     ) {
         let start_time = std::time::Instant::now();
@@ -95,6 +105,24 @@ impl<'a> System<'a> for Sys {
                     continue;
                 }
 
+                // Harmful projectiles pass through players the owner may not harm
+                let harmful = projectile.hit_entity.iter().any(|effect| match effect {
+                    projectile::Effect::Damage(change) => *change < 0,
+                    projectile::Effect::Knockback(_) | projectile::Effect::Explode(_) => true,
+                    _ => false,
+                });
+                let owner = projectile
+                    .owner
+                    .and_then(|uid| uid_allocator.retrieve_entity_internal(uid.into()));
+                let target = uid_allocator.retrieve_entity_internal(other.into());
+                if harmful
+                    && owner.zip(target).map_or(false, |(owner, target)| {
+                        !pvp_rules.allows(&players, &pvp_flags, &positions, owner, target)
+                    })
+                {
+                    continue;
+                }
+
                 for effect in projectile.hit_entity.drain(..) {
                     match effect {
                         projectile::Effect::Damage(healthchange) => {
@@ -102,14 +130,25 @@ impl<'a> System<'a> for Sys {
                             let mut damage = Damage {
                                 healthchange: healthchange as f32,
                                 source: DamageSource::Projectile,
+                                element: projectile.element,
                             };
 
                             let other_entity = uid_allocator.retrieve_entity_internal(other.into());
-                            if let Some(loadout) = other_entity.and_then(|e| loadouts.get(e)) {
-                                damage.modify_damage(false, loadout);
-                            }
+                            let report = other_entity
+                                .and_then(|e| loadouts.get(e))
+                                .map(|loadout| damage.modify_damage(false, loadout, &mut *rng));
 
                             if other != owner_uid {
+                                if let Some((entity, buff)) = other_entity.zip(
+                                    damage.on_hit_buff(false, BuffSource::Character {
+                                        by: owner_uid,
+                                    }),
+                                ) {
+                                    server_emitter.emit(ServerEvent::Buff {
+                                        entity,
+                                        buff_change: BuffChange::Add(buff),
+                                    });
+                                }
                                 if damage.healthchange < 0.0 {
                                     server_emitter.emit(ServerEvent::Damage {
                                         uid: other,
@@ -119,6 +158,7 @@ impl<'a> System<'a> for Sys {
                                                 owner: Some(owner_uid),
                                             },
                                         },
+                                        report,
                                     });
                                 } else if damage.healthchange > 0.0 {
                                     server_emitter.emit(ServerEvent::Damage {
@@ -129,6 +169,7 @@ impl<'a> System<'a> for Sys {
                                                 by: Some(owner_uid),
                                             },
                                         },
+                                        report,
                                     });
                                 }
                             }
@@ -1,17 +1,23 @@
 use crate::{
     comp::{
+        buff::{Buff, BuffCategory, BuffChange, BuffData, BuffKind, BuffSource},
         group, Body, CharacterState, Damage, DamageSource, HealthChange, HealthSource, Last,
-        Loadout, Ori, PhysicsState, Pos, Scale, Shockwave, ShockwaveHitEntities, Stats,
+        Loadout, Ori, PhysicsState, Player, Pos, PvpFlag, PvpRules, Scale, Shockwave,
+        ShockwaveHitEntities, Stats,
     },
     event::{EventBus, LocalEvent, ServerEvent},
-    state::{DeltaTime, Time},
+    state::{CombatRng, DeltaTime, Time},
     sync::{Uid, UidAllocator},
     util::Dir,
 };
-use specs::{saveload::MarkerAllocator, Entities, Join, Read, ReadStorage, System, WriteStorage};
+use specs::{
+    saveload::MarkerAllocator, Entities, Join, Read, ReadStorage, System, Write, WriteStorage,
+};
+use std::time::Duration;
 use vek::*;
 
 pub const BLOCK_ANGLE: f32 = 180.0;
+const SHOCKWAVE_STUN_DURATION: Duration = Duration::from_millis(750);
 
 /// This system is responsible for handling accepted inputs like moving or
 /// attacking
@@ -25,6 +31,8 @@ impl<'a> System<'a> for Sys {
         Read<'a, Time>,
         Read<'a, DeltaTime>,
         Read<'a, UidAllocator>,
+        Write<'a, CombatRng>,
+        Read<'a, PvpRules>,
         ReadStorage<'a, Uid>,
         ReadStorage<'a, Pos>,
         ReadStorage<'a, Last<Pos>>,
@@ -35,6 +43,8 @@ impl<'a> System<'a> for Sys {
         ReadStorage<'a, Loadout>,
         ReadStorage<'a, group::Group>,
         ReadStorage<'a, CharacterState>,
+        ReadStorage<'a, Player>,
+        ReadStorage<'a, PvpFlag>,
         ReadStorage<'a, PhysicsState>,
         WriteStorage<'a, Shockwave>,
         WriteStorage<'a, ShockwaveHitEntities>,
@@ -49,6 +59,8 @@ impl<'a> System<'a> for Sys {
             time,
             dt,
             uid_allocator,
+            mut rng,
+            pvp_rules,
             uids,
             positions,
             last_positions,
@@ -59,6 +71,8 @@ impl<'a> System<'a> for Sys {
             loadouts,
             groups,
             character_states,
+            players,
+            pvp_flags,
             physics_states,
             mut shockwaves,
             mut shockwave_hit_lists,
@@ -119,10 +133,10 @@ impl<'a> System<'a> for Sys {
 
             // Group to ignore collisions with
             // Might make this more nuanced if shockwaves are used for non damage effects
-            let group = shockwave
+            let owner = shockwave
                 .owner
-                .and_then(|uid| uid_allocator.retrieve_entity_internal(uid.into()))
-                .and_then(|e| groups.get(e));
+                .and_then(|uid| uid_allocator.retrieve_entity_internal(uid.into()));
+            let group = owner.and_then(|e| groups.get(e));
 
             // Go through all other effectable entities
             for (
@@ -177,6 +191,11 @@ impl<'a> System<'a> for Sys {
                     .map(|group_a| Some(group_a) == groups.get(b))
                     .unwrap_or(Some(*uid_b) == shockwave.owner);
 
+                // Don't hurt players that PvP rules protect from the owner
+                let pvp_allowed = owner.map_or(true, |owner| {
+                    pvp_rules.allows(&players, &pvp_flags, &positions, owner, b)
+                });
+
                 // Check if it is a hit
                 let hit = entity != b
                     && !stats_b.is_dead
@@ -190,20 +209,22 @@ impl<'a> System<'a> for Sys {
                     }
                     && (pos_b_ground - pos.0).angle_between(pos_b.0 - pos.0) < max_angle
                     && (!shockwave.requires_ground || physics_state_b.on_ground)
-                    && !same_group;
+                    && !same_group
+                    && pvp_allowed;
 
                 if hit {
                     let mut damage = Damage {
                         healthchange: -(shockwave.damage as f32),
                         source: DamageSource::Shockwave,
+                        element: shockwave.element,
                     };
 
                     let block = character_b.map(|c_b| c_b.is_block()).unwrap_or(false)
                         && ori_b.0.angle_between(pos.0 - pos_b.0) < BLOCK_ANGLE.to_radians() / 2.0;
 
-                    if let Some(loadout) = loadouts.get(b) {
-                        damage.modify_damage(block, loadout);
-                    }
+                    let report = loadouts
+                        .get(b)
+                        .map(|loadout| damage.modify_damage(block, loadout, &mut *rng));
 
                     if damage.healthchange != 0.0 {
                         let cause = if damage.healthchange < 0.0 {
@@ -221,7 +242,33 @@ impl<'a> System<'a> for Sys {
                                 amount: damage.healthchange as i32,
                                 cause,
                             },
+                            report,
                         });
+                        if let Some(buff) = damage.on_hit_buff(block, BuffSource::Character {
+                            by: shockwave.owner.unwrap_or(*uid),
+                        }) {
+                            server_emitter.emit(ServerEvent::Buff {
+                                entity: b,
+                                buff_change: BuffChange::Add(buff),
+                            });
+                        }
+                        // Shockwaves knock their targets off balance for a moment
+                        if damage.healthchange < 0.0 && !block {
+                            server_emitter.emit(ServerEvent::Buff {
+                                entity: b,
+                                buff_change: BuffChange::Add(Buff::new(
+                                    BuffKind::Stunned,
+                                    BuffData {
+                                        strength: 1.0,
+                                        duration: Some(SHOCKWAVE_STUN_DURATION),
+                                    },
+                                    vec![BuffCategory::Physical],
+                                    BuffSource::Character {
+                                        by: shockwave.owner.unwrap_or(*uid),
+                                    },
+                                )),
+                            });
+                        }
                         shockwave_hit_list.hit_entities.push(*uid_b);
                     }
                     if shockwave.knockback != 0.0 && damage.healthchange != 0.0 {
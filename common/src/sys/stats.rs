@@ -1,5 +1,5 @@
 use crate::{
-    comp::{CharacterState, Energy, EnergySource, HealthSource, Stats},
+    comp::{Buffs, CharacterState, Energy, EnergySource, HealthSource, Stats},
     event::{EventBus, ServerEvent},
     metrics::SysMetrics,
     span,
@@ -19,13 +19,23 @@ impl<'a> System<'a> for Sys {
         Read<'a, EventBus<ServerEvent>>,
         ReadExpect<'a, SysMetrics>,
         ReadStorage<'a, CharacterState>,
+        ReadStorage<'a, Buffs>,
         WriteStorage<'a, Stats>,
         WriteStorage<'a, Energy>,
     );
 
     fn run(
         &mut self,
-        (entities, dt, server_event_bus, sys_metrics, character_states, mut stats, mut energies): Self::SystemData,
+        (
+            entities,
+            dt,
+            server_event_bus,
+            sys_metrics,
+            character_states,
+            buffs,
+            mut stats,
+            mut energies,
+        ): Self::SystemData,
     ) {
         let start_time = std::time::Instant::now();
         span!(_guard, "run", "stats::Sys::run");
@@ -74,8 +84,12 @@ impl<'a> System<'a> for Sys {
         }
 
         // Update energies
-        for (character_state, mut energy) in
-            (&character_states, &mut energies.restrict_mut()).join()
+        for (character_state, buffs, mut energy) in (
+            &character_states,
+            buffs.maybe(),
+            &mut energies.restrict_mut(),
+        )
+            .join()
         {
             match character_state {
                 // Accelerate recharging energy.
@@ -95,10 +109,11 @@ impl<'a> System<'a> for Sys {
 
                     if res {
                         let mut energy = energy.get_mut_unchecked();
+                        let modifier = buffs.map_or(1.0, |buffs| buffs.energy_regen_modifier());
                         // Have to account for Calc I differential equations due to acceleration
                         energy.change_by(
-                            (energy.regen_rate * dt.0 + ENERGY_REGEN_ACCEL * dt.0.powf(2.0) / 2.0)
-                                as i32,
+                            ((energy.regen_rate * dt.0 + ENERGY_REGEN_ACCEL * dt.0.powf(2.0) / 2.0)
+                                * modifier) as i32,
                             EnergySource::Regen,
                         );
                         energy.regen_rate =
@@ -116,7 +131,8 @@ impl<'a> System<'a> for Sys {
                 | CharacterState::ChargedRanged { .. }
                 | CharacterState::RepeaterRanged { .. }
                 | CharacterState::Shockwave { .. }
-                | CharacterState::BasicBeam { .. } => {
+                | CharacterState::BasicBeam { .. }
+                | CharacterState::Mining { .. } => {
                     if energy.get_unchecked().regen_rate != 0.0 {
                         energy.get_mut_unchecked().regen_rate = 0.0
                     }
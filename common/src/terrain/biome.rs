@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum BiomeKind {
     Void,
     Grassland,
@@ -10,4 +10,7 @@ pub enum BiomeKind {
     Desert,
     Swamp,
     Forest,
+    Jungle,
+    Savannah,
+    Taiga,
 }
@@ -1,5 +1,5 @@
 use super::SpriteKind;
-use crate::make_case_elim;
+use crate::{comp::item::ToolCategory, make_case_elim};
 use enum_iterator::IntoEnumIterator;
 use lazy_static::lazy_static;
 use num_derive::FromPrimitive;
@@ -32,7 +32,10 @@ make_case_elim!(
         // being *very* fast).
         Rock = 0x10,
         WeakRock = 0x11, // Explodable
-        // 0x12 <= x < 0x20 is reserved for future rocks
+        CopperOre = 0x12,
+        IronOre = 0x13,
+        GoldOre = 0x14,
+        // 0x15 <= x < 0x20 is reserved for future rocks
         Grass = 0x20, // Note: *not* the same as grass sprites
         // 0x21 <= x < 0x30 is reserved for future grasses
         Earth = 0x30,
@@ -71,6 +74,42 @@ impl BlockKind {
     /// fields.
     #[inline]
     pub const fn has_color(&self) -> bool { self.is_filled() }
+
+    /// How hard blocks of this kind are to mine, or `None` if they can't be
+    /// mined at all. Tools with at least this much power always break the
+    /// block, weaker ones only sometimes, and tools with less than half of it
+    /// can't break it at all.
+    pub fn mine_hardness(&self) -> Option<f32> {
+        Some(match self {
+            BlockKind::Grass | BlockKind::Earth | BlockKind::Sand | BlockKind::Leaves => 0.5,
+            BlockKind::WeakRock | BlockKind::Wood => 1.0,
+            BlockKind::Rock | BlockKind::CopperOre => 1.5,
+            BlockKind::IronOre => 2.0,
+            BlockKind::GoldOre => 3.0,
+            _ => return None,
+        })
+    }
+
+    /// The kind of tool that blocks of this kind must be mined with.
+    pub fn mine_tool(&self) -> Option<ToolCategory> {
+        match self {
+            BlockKind::Wood | BlockKind::Leaves => Some(ToolCategory::Hatchet),
+            _ => self.mine_hardness().map(|_| ToolCategory::Pick),
+        }
+    }
+
+    /// The loot table that items are drawn from when a block of this kind is
+    /// mined, if it drops anything.
+    pub fn mine_drop_table(&self) -> Option<&'static str> {
+        match self {
+            BlockKind::Rock | BlockKind::WeakRock => Some("common.loot_tables.mining.rock"),
+            BlockKind::Wood => Some("common.loot_tables.mining.wood"),
+            BlockKind::CopperOre => Some("common.loot_tables.mining.copper"),
+            BlockKind::IronOre => Some("common.loot_tables.mining.iron"),
+            BlockKind::GoldOre => Some("common.loot_tables.mining.gold"),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for BlockKind {
@@ -187,7 +226,7 @@ impl Block {
             // Explodable means that the terrain sprite will get removed anyway, so all is good for
             // empty fluids.
             // TODO: Handle the case of terrain sprites we don't want to have explode
-            _ => self.get_sprite().is_some(),
+            _ => self.get_sprite().map_or(false, |s| !s.is_container()),
         }
     }
 
@@ -198,6 +237,18 @@ impl Block {
             .unwrap_or(false)
     }
 
+    #[inline]
+    pub fn is_container(&self) -> bool {
+        self.get_sprite().map(|s| s.is_container()).unwrap_or(false)
+    }
+
+    #[inline]
+    pub fn is_toggleable(&self) -> bool {
+        self.get_sprite()
+            .map(|s| s.toggled().is_some())
+            .unwrap_or(false)
+    }
+
     #[inline]
     pub fn is_opaque(&self) -> bool { self.kind().is_filled() }
 
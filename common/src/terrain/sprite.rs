@@ -102,6 +102,14 @@ make_case_elim!(
         Reed = 0x4C,
         Beehive = 0x4D,
         LargeCactus = 0x4E,
+        DropGateOpen = 0x4F,
+        GlowMushroom = 0x50,
+        Seedling = 0x51,
+        Sprout = 0x52,
+        StorageChest = 0x53,
+        Anvil = 0x54,
+        Cauldron = 0x55,
+        Loom = 0x56,
     }
 );
 
@@ -117,6 +125,10 @@ impl SpriteKind {
             SpriteKind::Pumpkin => 0.81,
             SpriteKind::Cabbage => 0.45,
             SpriteKind::Chest => 1.09,
+            SpriteKind::StorageChest => 1.09,
+            SpriteKind::Anvil => 1.0,
+            SpriteKind::Cauldron => 1.0,
+            SpriteKind::Loom => 2.0,
             SpriteKind::StreetLamp => 3.0,
             SpriteKind::Carrot => 0.18,
             SpriteKind::Radish => 0.18,
@@ -175,6 +187,7 @@ impl SpriteKind {
             SpriteKind::ShortGrass => false,
             SpriteKind::Apple => true,
             SpriteKind::Mushroom => true,
+            SpriteKind::GlowMushroom => true,
             SpriteKind::Velorite => true,
             SpriteKind::VeloriteFrag => true,
             SpriteKind::Chest => true,
@@ -183,11 +196,34 @@ impl SpriteKind {
             SpriteKind::Twigs => true,
             SpriteKind::ShinyGem => true,
             SpriteKind::Crate => true,
+            SpriteKind::StorageChest => true,
             SpriteKind::Beehive => true,
+            SpriteKind::WheatYellow => true,
+            SpriteKind::Flax => true,
+            SpriteKind::Corn => true,
+            SpriteKind::Cabbage => true,
+            SpriteKind::Carrot => true,
+            SpriteKind::Tomato => true,
+            SpriteKind::Radish => true,
+            SpriteKind::Turnip => true,
+            SpriteKind::Pumpkin => true,
             _ => false,
         }
     }
 
+    /// The sprite this one turns into when interacted with, for things like
+    /// gates that can be opened and closed.
+    pub fn toggled(&self) -> Option<SpriteKind> {
+        match self {
+            SpriteKind::DropGate => Some(SpriteKind::DropGateOpen),
+            SpriteKind::DropGateOpen => Some(SpriteKind::DropGate),
+            _ => None,
+        }
+    }
+
+    /// Whether the sprite is a placed container with its own inventory
+    pub fn is_container(&self) -> bool { matches!(self, SpriteKind::StorageChest) }
+
     pub fn has_ori(&self) -> bool {
         matches!(
             self,
@@ -219,8 +255,12 @@ impl SpriteKind {
                 | SpriteKind::Chest
                 | SpriteKind::DropGate
                 | SpriteKind::DropGateBottom
+                | SpriteKind::DropGateOpen
                 | SpriteKind::Door
                 | SpriteKind::Beehive
+                | SpriteKind::Anvil
+                | SpriteKind::Cauldron
+                | SpriteKind::Loom
         )
     }
 }
@@ -1,13 +1,18 @@
-use super::BlockKind;
+use super::{Block, BlockKind};
 use crate::{
-    assets::{self, Asset, Ron},
+    assets::{self, Asset, AssetFile, Ron},
     make_case_elim,
     vol::{BaseVol, ReadVol, SizedVol, WriteVol},
     volumes::dyna::{Dyna, DynaError},
 };
 use dot_vox::DotVoxData;
 use serde::Deserialize;
-use std::{fs::File, io::BufReader, sync::Arc};
+use std::{
+    fs,
+    io::{self, BufReader, Write},
+    path::Path,
+    sync::Arc,
+};
 use vek::*;
 
 make_case_elim!(
@@ -34,6 +39,10 @@ make_case_elim!(
     }
 );
 
+/// Palette slots below this index are given special meaning when structures
+/// are loaded (leaves, water, etc.), so saved colours never use them.
+const VOX_RESERVED_PALETTE: usize = 16;
+
 #[derive(Debug)]
 pub enum StructureError {}
 
@@ -56,6 +65,15 @@ impl Structure {
             .collect()
     }
 
+    /// Load a structure from a `.vox` file outside of the asset directory,
+    /// such as one saved with the `/structure` command.
+    pub fn load_file(path: &Path) -> Result<Self, assets::Error> {
+        let bytes = fs::read(path)?;
+        dot_vox::load_bytes(&bytes)
+            .map(|dot_vox_data| Self::from(&dot_vox_data))
+            .map_err(assets::Error::parse_error)
+    }
+
     pub fn with_center(mut self, center: Vec3<i32>) -> Self {
         self.center = center;
         self
@@ -94,9 +112,14 @@ impl ReadVol for Structure {
 impl Asset for Structure {
     const ENDINGS: &'static [&'static str] = &["vox"];
 
-    fn parse(buf_reader: BufReader<File>, specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, specifier: &str) -> Result<Self, assets::Error> {
         let dot_vox_data = DotVoxData::parse(buf_reader, specifier)?;
+        Ok(Self::from(&dot_vox_data))
+    }
+}
 
+impl From<&DotVoxData> for Structure {
+    fn from(dot_vox_data: &DotVoxData) -> Self {
         if let Some(model) = dot_vox_data.models.get(0) {
             let palette = dot_vox_data
                 .palette
@@ -138,23 +161,103 @@ impl Asset for Structure {
                 let _ = vol.set(Vec3::new(voxel.x, voxel.y, voxel.z).map(i32::from), block);
             }
 
-            Ok(Structure {
+            Structure {
                 center: Vec3::zero(),
                 vol,
                 empty: StructureBlock::None,
                 default_kind: BlockKind::Misc,
-            })
+            }
         } else {
-            Ok(Self {
+            Self {
                 center: Vec3::zero(),
                 vol: Dyna::filled(Vec3::zero(), StructureBlock::None, ()),
                 empty: StructureBlock::None,
                 default_kind: BlockKind::Misc,
-            })
+            }
         }
     }
 }
 
+/// Write coloured voxels out as a MagicaVoxel `.vox` model that can be read
+/// back with [`Structure::load_file`]. No axis of `size` may exceed 256.
+pub fn write_vox<W: Write>(
+    writer: &mut W,
+    size: Vec3<u32>,
+    voxels: &[(Vec3<u8>, Rgb<u8>)],
+) -> io::Result<()> {
+    let mut palette = Vec::<Rgb<u8>>::new();
+    let mut xyzi = Vec::with_capacity(4 + voxels.len() * 4);
+    xyzi.extend_from_slice(&(voxels.len() as u32).to_le_bytes());
+    for (pos, color) in voxels {
+        let index = match palette.iter().position(|c| c == color) {
+            Some(index) => index,
+            None if palette.len() < 255 - VOX_RESERVED_PALETTE => {
+                palette.push(*color);
+                palette.len() - 1
+            },
+            // The palette is full, so settle for the closest colour we have
+            None => palette
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, c)| {
+                    Vec3::from(c.map(i32::from)).distance_squared(Vec3::from(color.map(i32::from)))
+                })
+                .map_or(0, |(index, _)| index),
+        };
+        // Voxel colour indices in the file are 1-based
+        xyzi.extend_from_slice(&[
+            pos.x,
+            pos.y,
+            pos.z,
+            (VOX_RESERVED_PALETTE + index + 1) as u8,
+        ]);
+    }
+
+    let mut rgba = Vec::with_capacity(256 * 4);
+    for i in 0..256 {
+        let color = i
+            .checked_sub(VOX_RESERVED_PALETTE)
+            .and_then(|i| palette.get(i))
+            .copied()
+            .unwrap_or_else(|| Rgb::broadcast(0));
+        rgba.extend_from_slice(&[color.r, color.g, color.b, 255]);
+    }
+
+    let size = [size.x, size.y, size.z]
+        .iter()
+        .flat_map(|e| e.to_le_bytes().to_vec())
+        .collect::<Vec<_>>();
+
+    let mut children = Vec::new();
+    for (id, content) in &[(b"SIZE", size), (b"XYZI", xyzi), (b"RGBA", rgba)] {
+        children.extend_from_slice(*id);
+        children.extend_from_slice(&(content.len() as u32).to_le_bytes());
+        children.extend_from_slice(&0u32.to_le_bytes());
+        children.extend_from_slice(content);
+    }
+
+    writer.write_all(b"VOX ")?;
+    writer.write_all(&150u32.to_le_bytes())?;
+    writer.write_all(b"MAIN")?;
+    writer.write_all(&0u32.to_le_bytes())?;
+    writer.write_all(&(children.len() as u32).to_le_bytes())?;
+    writer.write_all(&children)
+}
+
+/// Write the exact blocks of a structure, including the block kinds, sprites
+/// and fluids that a `.vox` file has no room for. They can be read back with
+/// [`load_blocks`].
+pub fn write_blocks<W: Write>(writer: &mut W, blocks: &[(Vec3<u8>, Block)]) -> io::Result<()> {
+    bincode::serialize_into(writer, blocks)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, format!("{:?}", e)))
+}
+
+/// Load blocks written with [`write_blocks`].
+pub fn load_blocks(path: &Path) -> Result<Vec<(Vec3<u8>, Block)>, assets::Error> {
+    let bytes = fs::read(path)?;
+    bincode::deserialize(&bytes).map_err(assets::Error::parse_error)
+}
+
 #[derive(Deserialize)]
 struct StructureSpec {
     specifier: String,
@@ -162,3 +265,50 @@ struct StructureSpec {
 }
 
 type StructuresSpec = Ron<Vec<StructureSpec>>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_vox_round_trip() {
+        let voxels = [
+            (Vec3::new(0, 0, 0), Rgb::new(10, 20, 30)),
+            (Vec3::new(3, 1, 2), Rgb::new(200, 100, 50)),
+        ];
+        let mut buf = Vec::new();
+        write_vox(&mut buf, Vec3::new(4, 2, 3), &voxels).unwrap();
+
+        let structure = Structure::from(&dot_vox::load_bytes(&buf).unwrap());
+        assert_eq!(structure.get_bounds().size(), Extent3::new(4, 2, 3));
+        for (pos, color) in &voxels {
+            assert!(matches!(
+                structure.get(pos.map(i32::from)),
+                Ok(StructureBlock::Normal(c)) if c == color
+            ));
+        }
+        assert!(matches!(
+            structure.get(Vec3::new(1, 1, 1)),
+            Ok(StructureBlock::None)
+        ));
+    }
+
+    #[test]
+    fn test_write_blocks_round_trip() {
+        use crate::terrain::SpriteKind;
+
+        let blocks = vec![
+            (
+                Vec3::new(0, 0, 0),
+                Block::new(BlockKind::Rock, Rgb::new(10, 20, 30)),
+            ),
+            (Vec3::new(1, 0, 0), Block::air(SpriteKind::Crate)),
+            (Vec3::new(2, 0, 0), Block::water(SpriteKind::Empty)),
+        ];
+        let mut buf = Vec::new();
+        write_blocks(&mut buf, &blocks).unwrap();
+
+        let loaded: Vec<(Vec3<u8>, Block)> = bincode::deserialize(&buf).unwrap();
+        assert_eq!(loaded, blocks);
+    }
+}
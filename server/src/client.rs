@@ -74,6 +74,7 @@ impl Client {
                     | ServerGeneral::InviteComplete { .. }
                     | ServerGeneral::ExitInGameSuccess
                     | ServerGeneral::InventoryUpdate(_, _)
+                    | ServerGeneral::ContainerUpdate(_, _)
                     | ServerGeneral::TerrainChunkUpdate { .. }
                     | ServerGeneral::TerrainBlockUpdates(_)
                     | ServerGeneral::SetViewDistance(_)
@@ -89,7 +90,8 @@ impl Client {
                     | ServerGeneral::CreateEntity(_)
                     | ServerGeneral::DeleteEntity(_)
                     | ServerGeneral::Disconnect(_)
-                    | ServerGeneral::Notification(_) => &mut self.general_stream,
+                    | ServerGeneral::Notification(_)
+                    | ServerGeneral::CustomAssets(_) => &mut self.general_stream,
                 };
                 Self::internal_send(&mut self.network_error, stream, &msg)
             },
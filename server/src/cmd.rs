@@ -4,26 +4,42 @@
 
 use crate::{
     client::Client,
+    events::group_manip::{INVITE_TIMEOUT_DUR, PRESENTED_INVITE_TIMEOUT_DUR},
+    faction::{self, FactionError, FactionManager, FactionRank},
     settings::{BanRecord, EditableSetting},
     Server, StateExt,
 };
 use chrono::{NaiveTime, Timelike};
 use common::{
+    character::CharacterId,
     cmd::{ChatCommand, CHAT_COMMANDS, CHAT_SHORTCUTS},
-    comp::{self, ChatType, Item, LightEmitter, WaypointArea},
+    comp::{
+        self,
+        group::{Invite, InviteKind, PendingInvites},
+        ChatType, Item, LightEmitter, WaypointArea,
+    },
     event::{EventBus, ServerEvent},
     msg::{DisconnectReason, Notification, PlayerListUpdate, ServerGeneral},
     npc::{self, get_npc_name},
     state::TimeOfDay,
     sync::{Uid, WorldSyncExt},
-    terrain::{Block, BlockKind, SpriteKind, TerrainChunkSize},
+    terrain::{
+        structure::{self, Structure, StructureBlock},
+        Block, BlockKind, SpriteKind, TerrainChunkSize,
+    },
     util::Dir,
-    vol::RectVolSize,
+    vol::{ReadVol, RectVolSize},
     Explosion, LoadoutBuilder,
 };
 use rand::Rng;
 use specs::{Builder, Entity as EcsEntity, Join, WorldExt};
-use std::convert::TryFrom;
+use std::{
+    convert::TryFrom,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 use vek::*;
 use world::util::Sampler;
 
@@ -72,35 +88,42 @@ fn get_handler(cmd: &ChatCommand) -> CommandHandler {
         ChatCommand::Ban => handle_ban,
         ChatCommand::Build => handle_build,
         ChatCommand::Campfire => handle_spawn_campfire,
+        ChatCommand::CreateFaction => handle_create_faction,
         ChatCommand::Debug => handle_debug,
         ChatCommand::DebugColumn => handle_debug_column,
         ChatCommand::Dummy => handle_spawn_training_dummy,
         ChatCommand::Explosion => handle_explosion,
         ChatCommand::Faction => handle_faction,
+        ChatCommand::FactionInvite => handle_faction_invite,
+        ChatCommand::FactionKick => handle_faction_kick,
+        ChatCommand::FactionRank => handle_faction_rank,
         ChatCommand::GiveExp => handle_give_exp,
         ChatCommand::GiveItem => handle_give_item,
         ChatCommand::Goto => handle_goto,
         ChatCommand::Group => handle_group,
         ChatCommand::Health => handle_health,
         ChatCommand::Help => handle_help,
-        ChatCommand::JoinFaction => handle_join_faction,
         ChatCommand::Jump => handle_jump,
         ChatCommand::Kick => handle_kick,
         ChatCommand::Kill => handle_kill,
         ChatCommand::KillNpcs => handle_kill_npcs,
         ChatCommand::Lantern => handle_lantern,
+        ChatCommand::LeaveFaction => handle_leave_faction,
         ChatCommand::Light => handle_light,
         ChatCommand::MakeBlock => handle_make_block,
         ChatCommand::MakeSprite => handle_make_sprite,
         ChatCommand::Motd => handle_motd,
+        ChatCommand::NameItem => handle_name_item,
         ChatCommand::Object => handle_object,
         ChatCommand::Players => handle_players,
+        ChatCommand::Pvp => handle_pvp,
         ChatCommand::Region => handle_region,
         ChatCommand::RemoveLights => handle_remove_lights,
         ChatCommand::Say => handle_say,
         ChatCommand::SetLevel => handle_set_level,
         ChatCommand::SetMotd => handle_set_motd,
         ChatCommand::Spawn => handle_spawn,
+        ChatCommand::Structure => handle_structure,
         ChatCommand::Sudo => handle_sudo,
         ChatCommand::Tell => handle_tell,
         ChatCommand::Time => handle_time,
@@ -261,6 +284,224 @@ fn handle_make_sprite(
     }
 }
 
+/// Structures are limited by the `.vox` format to 256 voxels along each axis.
+const MAX_STRUCTURE_SIZE: u32 = 256;
+
+fn structure_path(server: &Server, name: &str) -> Option<PathBuf> {
+    if !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        Some(
+            server
+                .data_dir()
+                .path
+                .join("structures")
+                .join(format!("{}.vox", name)),
+        )
+    } else {
+        None
+    }
+}
+
+/// The exact blocks of a structure are kept next to its `.vox` file, which only
+/// has room for colours.
+fn structure_blocks_path(path: &Path) -> PathBuf { path.with_extension("blocks") }
+
+fn handle_structure(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) {
+    let mut args = args.split_whitespace();
+    let (structure_action, name) = match (args.next(), args.next()) {
+        (Some(structure_action), Some(name)) => (structure_action.to_owned(), name.to_owned()),
+        _ => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
+            );
+            return;
+        },
+    };
+    let coords = match args.map(str::parse::<i32>).collect::<Result<Vec<_>, _>>() {
+        Ok(coords) => coords,
+        Err(_) => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
+            );
+            return;
+        },
+    };
+    let path = match structure_path(server, &name) {
+        Some(path) => path,
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(String::from(
+                    "Structure names may only contain letters, digits, '_' and '-'.",
+                )),
+            );
+            return;
+        },
+    };
+
+    if structure_action.eq_ignore_ascii_case("save") {
+        if coords.len() != 6 {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
+            );
+            return;
+        }
+        let a = Vec3::new(coords[0], coords[1], coords[2]);
+        let b = Vec3::new(coords[3], coords[4], coords[5]);
+        let min = a.map2(b, i32::min);
+        // Far apart corners would overflow an `i32`
+        let size = a.map2(b, |a, b| (i64::from(a) - i64::from(b)).abs() + 1);
+        if size.reduce_max() > i64::from(MAX_STRUCTURE_SIZE) {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(format!(
+                    "Structures may be at most {} blocks along each axis.",
+                    MAX_STRUCTURE_SIZE
+                )),
+            );
+            return;
+        }
+        let size = size.map(|e| e as u32);
+
+        let blocks = {
+            let terrain = server.state.terrain();
+            let mut blocks = Vec::new();
+            for x in 0..size.x as i32 {
+                for y in 0..size.y as i32 {
+                    for z in 0..size.z as i32 {
+                        let offs = Vec3::new(x, y, z);
+                        match terrain.get(min + offs) {
+                            Ok(block) if *block != Block::air(SpriteKind::Empty) => {
+                                blocks.push((offs.map(|e| e as u8), *block));
+                            },
+                            _ => {},
+                        }
+                    }
+                }
+            }
+            blocks
+        };
+        let voxels = blocks
+            .iter()
+            .filter_map(|(offs, block)| Some((*offs, block.get_color()?)))
+            .collect::<Vec<_>>();
+
+        let blocks_path = structure_blocks_path(&path);
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| File::create(&path))
+            .and_then(|file| structure::write_vox(&mut BufWriter::new(file), size, &voxels))
+            .and_then(|_| File::create(&blocks_path))
+            .and_then(|file| structure::write_blocks(&mut BufWriter::new(file), &blocks));
+        match result {
+            Ok(()) => server.notify_client(
+                client,
+                ChatType::CommandInfo.server_msg(format!(
+                    "Saved {} blocks to structure \"{}\".",
+                    blocks.len(),
+                    name
+                )),
+            ),
+            Err(err) => {
+                error!(?err, ?path, "Failed to save structure");
+                server.notify_client(
+                    client,
+                    ChatType::CommandError
+                        .server_msg(format!("Failed to save structure \"{}\".", name)),
+                );
+            },
+        }
+    } else if structure_action.eq_ignore_ascii_case("place") {
+        let origin = match coords.as_slice() {
+            [x, y, z] => Vec3::new(*x, *y, *z),
+            [] => match server.state.read_component_copied::<comp::Pos>(target) {
+                Some(pos) => pos.0.map(|e| e.floor() as i32),
+                None => {
+                    server.notify_client(
+                        client,
+                        ChatType::CommandError.server_msg(String::from("You have no position.")),
+                    );
+                    return;
+                },
+            },
+            _ => {
+                server.notify_client(
+                    client,
+                    ChatType::CommandError.server_msg(action.help_string()),
+                );
+                return;
+            },
+        };
+
+        let blocks_path = structure_blocks_path(&path);
+        let blocks = if blocks_path.exists() {
+            structure::load_blocks(&blocks_path)
+        } else {
+            // Without the exact blocks, e.g. for a `.vox` file made elsewhere, only the
+            // colours are known
+            Structure::load_file(&path).map(|structure| {
+                let bounds = structure.get_bounds();
+                let mut blocks = Vec::new();
+                for x in bounds.min.x..bounds.max.x {
+                    for y in bounds.min.y..bounds.max.y {
+                        for z in bounds.min.z..bounds.max.z {
+                            let offs = Vec3::new(x, y, z);
+                            if let Ok(StructureBlock::Normal(color)) = structure.get(offs) {
+                                blocks.push((
+                                    (offs - bounds.min).map(|e| e as u8),
+                                    Block::new(structure.default_kind(), *color),
+                                ));
+                            }
+                        }
+                    }
+                }
+                blocks
+            })
+        };
+        let blocks = match blocks {
+            Ok(blocks) => blocks,
+            Err(_) => {
+                server.notify_client(
+                    client,
+                    ChatType::CommandError
+                        .server_msg(format!("No structure named \"{}\" could be loaded.", name)),
+                );
+                return;
+            },
+        };
+
+        for (offs, block) in &blocks {
+            server.state.set_block(origin + offs.map(i32::from), *block);
+        }
+        server.notify_client(
+            client,
+            ChatType::CommandInfo.server_msg(format!(
+                "Placed {} blocks of structure \"{}\".",
+                blocks.len(),
+                name
+            )),
+        );
+    } else {
+        server.notify_client(
+            client,
+            ChatType::CommandError.server_msg(action.help_string()),
+        );
+    }
+}
+
 fn handle_motd(
     server: &mut Server,
     client: EcsEntity,
@@ -274,6 +515,40 @@ fn handle_motd(
     );
 }
 
+fn handle_name_item(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) {
+    let name = scan_fmt!(&args, &action.arg_fmt(), String).ok();
+    let result = match server
+        .state
+        .ecs()
+        .write_storage::<comp::Loadout>()
+        .get_mut(target)
+        .and_then(|loadout| loadout.active_item.as_mut())
+    {
+        Some(item_config) => item_config.item.set_custom_name(name.clone()).map_err(|_| {
+            format!(
+                "Item names must be between 1 and {} characters long",
+                comp::item::instance::MAX_CUSTOM_NAME_LEN
+            )
+        }),
+        None => Err("You aren't holding anything to name".to_string()),
+    };
+
+    let msg = match (result, name) {
+        (Ok(()), Some(name)) => {
+            ChatType::CommandInfo.server_msg(format!("Item named \"{}\"", name))
+        },
+        (Ok(()), None) => ChatType::CommandInfo.server_msg("Removed the item's name".to_string()),
+        (Err(e), _) => ChatType::CommandError.server_msg(e),
+    };
+    server.notify_client(client, msg);
+}
+
 fn handle_set_motd(
     server: &mut Server,
     client: EcsEntity,
@@ -823,6 +1098,48 @@ fn handle_players(
     );
 }
 
+fn handle_pvp(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) {
+    if server.state.ecs().read_resource::<comp::PvpRules>().mode != comp::PvpMode::Flagged {
+        server.notify_client(
+            client,
+            ChatType::CommandError.server_msg("PvP is not opt-in on this server"),
+        );
+        return;
+    }
+    if server
+        .state
+        .read_storage::<comp::PvpFlag>()
+        .get(target)
+        .is_some()
+    {
+        server
+            .state
+            .ecs()
+            .write_storage::<comp::PvpFlag>()
+            .remove(target);
+        server.notify_client(
+            client,
+            ChatType::CommandInfo.server_msg("You no longer take part in PvP"),
+        );
+    } else {
+        let _ = server
+            .state
+            .ecs()
+            .write_storage::<comp::PvpFlag>()
+            .insert(target, comp::PvpFlag);
+        server.notify_client(
+            client,
+            ChatType::CommandInfo.server_msg("You now take part in PvP"),
+        );
+    }
+}
+
 fn handle_build(
     server: &mut Server,
     client: EcsEntity,
@@ -1137,6 +1454,7 @@ fn handle_explosion(
                         min_heal: 0,
                         terrain_destruction_power: power,
                         energy_regen: 0,
+                        element: None,
                     },
                     owner: ecs.read_storage::<Uid>().get(target).copied(),
                     friendly_damage: true,
@@ -1316,7 +1634,8 @@ fn handle_faction(
     } else {
         server.notify_client(
             client,
-            ChatType::CommandError.server_msg("Please join a faction with /join_faction"),
+            ChatType::CommandError
+                .server_msg("You are not in a faction. Create one with /create_faction"),
         );
     }
 }
@@ -1437,7 +1756,61 @@ fn handle_world(
     }
 }
 
-fn handle_join_faction(
+fn character_id_of(server: &Server, entity: EcsEntity) -> Option<CharacterId> {
+    server
+        .state
+        .ecs()
+        .read_storage::<comp::Player>()
+        .get(entity)
+        .and_then(|player| player.character_id)
+}
+
+fn find_player_by_alias(server: &Server, alias: &str) -> Option<EcsEntity> {
+    let ecs = server.state.ecs();
+    (&ecs.entities(), &ecs.read_storage::<comp::Player>())
+        .join()
+        .find(|(_, player)| player.alias == alias)
+        .map(|(entity, _)| entity)
+}
+
+fn find_player_by_character_id(server: &Server, character_id: CharacterId) -> Option<EcsEntity> {
+    let ecs = server.state.ecs();
+    (&ecs.entities(), &ecs.read_storage::<comp::Player>())
+        .join()
+        .find(|(_, player)| player.character_id == Some(character_id))
+        .map(|(entity, _)| entity)
+}
+
+fn character_name_of(server: &Server, entity: EcsEntity) -> String {
+    server
+        .state
+        .ecs()
+        .read_storage::<comp::Stats>()
+        .get(entity)
+        .map(|stats| stats.name.clone())
+        .unwrap_or_default()
+}
+
+/// Find a member of the faction of `character_id` by the alias of an online
+/// player, or by character name so that offline members can be managed too.
+/// Returns the member's character id and their entity if they are online.
+fn find_faction_member(
+    server: &Server,
+    character_id: CharacterId,
+    name: &str,
+) -> Option<(CharacterId, Option<EcsEntity>)> {
+    let member_id = find_player_by_alias(server, name)
+        .and_then(|entity| character_id_of(server, entity))
+        .or_else(|| {
+            let faction_manager = server.state.ecs().read_resource::<FactionManager>();
+            faction_manager
+                .faction_of(character_id)
+                .and_then(|faction| faction_manager.member_named(faction, name))
+        })?;
+    Some((member_id, find_player_by_character_id(server, member_id)))
+}
+
+fn handle_create_faction(
     server: &mut Server,
     client: EcsEntity,
     target: EcsEntity,
@@ -1452,50 +1825,373 @@ fn handle_join_faction(
         );
         return;
     }
-    if let Some(alias) = server
+    let name = match scan_fmt!(&args, &action.arg_fmt(), String) {
+        Ok(name) => name,
+        Err(_) => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
+            );
+            return;
+        },
+    };
+    let character_id = match character_id_of(server, client) {
+        Some(id) => id,
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg("You must be playing a character to do this"),
+            );
+            return;
+        },
+    };
+
+    let result = server
         .state
         .ecs()
-        .read_storage::<comp::Player>()
-        .get(target)
-        .map(|player| player.alias.clone())
-    {
-        let faction_leave = if let Ok(faction) = scan_fmt!(&args, &action.arg_fmt(), String) {
-            let mode = comp::ChatMode::Faction(faction.clone());
-            let _ = server.state.ecs().write_storage().insert(client, mode);
-            let faction_leave = server
-                .state
-                .ecs()
-                .write_storage()
-                .insert(client, comp::Faction(faction.clone()))
-                .ok()
-                .flatten()
-                .map(|f| f.0);
-            server.state.send_chat(
-                ChatType::FactionMeta(faction.clone())
-                    .chat_msg(format!("[{}] joined faction ({})", alias, faction)),
+        .write_resource::<FactionManager>()
+        .create_faction(
+            name.clone(),
+            character_id,
+            character_name_of(server, client),
+        );
+    match result {
+        Ok(()) => {
+            faction::enter_faction(&server.state, client, &name);
+            server.notify_client(
+                client,
+                ChatType::CommandInfo.server_msg(format!("Created faction ({})", name)),
             );
-            faction_leave
-        } else {
-            let mode = comp::ChatMode::default();
-            let _ = server.state.ecs().write_storage().insert(client, mode);
-            server
-                .state
-                .ecs()
-                .write_storage()
-                .remove(client)
-                .map(|comp::Faction(f)| f)
+        },
+        Err(error) => {
+            server.notify_client(client, ChatType::CommandError.server_msg(error.to_string()))
+        },
+    }
+}
+
+fn handle_faction_invite(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) {
+    if client != target {
+        // This happens when [ab]using /sudo
+        server.notify_client(
+            client,
+            ChatType::CommandError.server_msg("It's rude to impersonate people"),
+        );
+        return;
+    }
+    let alias = match scan_fmt!(&args, &action.arg_fmt(), String) {
+        Ok(alias) => alias,
+        Err(_) => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
+            );
+            return;
+        },
+    };
+    let invitee = match find_player_by_alias(server, &alias) {
+        Some(invitee) if invitee != client => invitee,
+        Some(_) => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg("You can't invite yourself."),
+            );
+            return;
+        },
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(format!("Player '{}' not found!", alias)),
+            );
+            return;
+        },
+    };
+    let (inviter_id, invitee_id) =
+        match character_id_of(server, client).zip(character_id_of(server, invitee)) {
+            Some(ids) => ids,
+            None => {
+                server.notify_client(
+                    client,
+                    ChatType::CommandError
+                        .server_msg(format!("Player '{}' is not playing a character.", alias)),
+                );
+                return;
+            },
         };
-        if let Some(faction) = faction_leave {
-            server.state.send_chat(
-                ChatType::FactionMeta(faction.clone())
-                    .chat_msg(format!("[{}] left faction ({})", alias, faction)),
+
+    let error = {
+        let faction_manager = server.state.ecs().read_resource::<FactionManager>();
+        if !faction_manager
+            .rank_of(inviter_id)
+            .map_or(false, FactionRank::can_manage_members)
+        {
+            Some("Only faction leaders and officers can invite new members.")
+        } else if faction_manager.faction_of(invitee_id).is_some() {
+            Some("This player is already in a faction.")
+        } else {
+            None
+        }
+    };
+    if let Some(error) = error {
+        server.notify_client(client, ChatType::CommandError.server_msg(error));
+        return;
+    }
+
+    let ecs = server.state.ecs();
+    let mut invites = ecs.write_storage::<Invite>();
+    if invites.contains(invitee) {
+        server.notify_client(
+            client,
+            ChatType::CommandError.server_msg("This player already has a pending invite."),
+        );
+        return;
+    }
+    if let Err(err) = invites.insert(invitee, Invite {
+        inviter: client,
+        kind: InviteKind::Faction,
+    }) {
+        error!("Failed to insert Invite component: {:?}", err);
+        return;
+    }
+    match ecs.write_storage::<PendingInvites>().entry(client) {
+        Ok(entry) => entry
+            .or_insert_with(|| PendingInvites(Vec::new()))
+            .0
+            .push((invitee, Instant::now() + INVITE_TIMEOUT_DUR)),
+        Err(err) => {
+            error!(
+                "Failed to get entry for pending invites component: {:?}",
+                err
+            );
+            // Cleanup
+            invites.remove(invitee);
+            return;
+        },
+    }
+
+    let uids = ecs.read_storage::<Uid>();
+    if let (Some(inviter_uid), Some(invitee_uid)) =
+        (uids.get(client).copied(), uids.get(invitee).copied())
+    {
+        server.notify_client(invitee, ServerGeneral::GroupInvite {
+            inviter: inviter_uid,
+            timeout: PRESENTED_INVITE_TIMEOUT_DUR,
+            kind: InviteKind::Faction,
+        });
+        server.notify_client(client, ServerGeneral::InvitePending(invitee_uid));
+    }
+}
+
+fn handle_faction_kick(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) {
+    if client != target {
+        // This happens when [ab]using /sudo
+        server.notify_client(
+            client,
+            ChatType::CommandError.server_msg("It's rude to impersonate people"),
+        );
+        return;
+    }
+    let alias = match scan_fmt!(&args, &action.arg_fmt(), String) {
+        Ok(alias) => alias,
+        Err(_) => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
             );
+            return;
+        },
+    };
+    let kicker_id = match character_id_of(server, client) {
+        Some(id) => id,
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg("You must be playing a character to do this"),
+            );
+            return;
+        },
+    };
+    let (kicked_id, kicked) = match find_faction_member(server, kicker_id, &alias) {
+        Some(member) => member,
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(format!("Player '{}' not found!", alias)),
+            );
+            return;
+        },
+    };
+
+    let result = {
+        let mut faction_manager = server.state.ecs().write_resource::<FactionManager>();
+        let same_faction = faction_manager.faction_of(kicker_id).is_some()
+            && faction_manager.faction_of(kicker_id) == faction_manager.faction_of(kicked_id);
+        match (
+            faction_manager.rank_of(kicker_id),
+            faction_manager.rank_of(kicked_id),
+        ) {
+            _ if !same_faction => Err("This player is not in your faction.".to_owned()),
+            (Some(kicker_rank), Some(kicked_rank))
+                if kicker_rank.can_manage_members() && kicker_rank > kicked_rank =>
+            {
+                faction_manager
+                    .remove_member(kicked_id, false)
+                    .map_err(|error| error.to_string())
+            },
+            _ => Err("You don't have permission to remove this member.".to_owned()),
         }
-    } else {
+    };
+    match result {
+        Ok(removed) => {
+            match kicked {
+                Some(kicked) => {
+                    faction::exit_faction(&server.state, kicked, &removed.faction);
+                    server.notify_client(
+                        kicked,
+                        ChatType::Meta.server_msg(format!(
+                            "You were removed from the faction ({}).",
+                            removed.faction
+                        )),
+                    );
+                },
+                None => {
+                    server
+                        .state
+                        .send_chat(ChatType::FactionMeta(removed.faction.clone()).chat_msg(
+                            format!("[{}] was removed from faction ({})", alias, removed.faction),
+                        ))
+                },
+            }
+        },
+        Err(error) => server.notify_client(client, ChatType::CommandError.server_msg(error)),
+    }
+}
+
+fn handle_faction_rank(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    args: String,
+    action: &ChatCommand,
+) {
+    if client != target {
+        // This happens when [ab]using /sudo
+        server.notify_client(
+            client,
+            ChatType::CommandError.server_msg("It's rude to impersonate people"),
+        );
+        return;
+    }
+    let (alias, rank) = match scan_fmt!(&args, &action.arg_fmt(), String, String) {
+        Ok((alias, rank)) => match rank.parse::<FactionRank>() {
+            Ok(rank) => (alias, rank),
+            Err(()) => {
+                server.notify_client(
+                    client,
+                    ChatType::CommandError.server_msg(format!("Unknown rank '{}'", rank)),
+                );
+                return;
+            },
+        },
+        Err(_) => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(action.help_string()),
+            );
+            return;
+        },
+    };
+    let leader_id = match character_id_of(server, client) {
+        Some(id) => id,
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg("You must be playing a character to do this"),
+            );
+            return;
+        },
+    };
+    let member_id = match find_faction_member(server, leader_id, &alias) {
+        Some((member_id, _)) => member_id,
+        None => {
+            server.notify_client(
+                client,
+                ChatType::CommandError.server_msg(format!("Player '{}' not found!", alias)),
+            );
+            return;
+        },
+    };
+
+    let result = {
+        let mut faction_manager = server.state.ecs().write_resource::<FactionManager>();
+        let faction = faction_manager.faction_of(leader_id).map(|f| f.to_owned());
+        if faction.is_none() || faction.as_deref() != faction_manager.faction_of(member_id) {
+            Err("This player is not in your faction.".to_owned())
+        } else if faction_manager.rank_of(leader_id) != Some(FactionRank::Leader) {
+            Err("Only the faction leader can change ranks.".to_owned())
+        } else {
+            faction_manager
+                .set_rank(member_id, rank)
+                .map(|()| faction.unwrap_or_default())
+                .map_err(|error| error.to_string())
+        }
+    };
+    match result {
+        Ok(faction) => server.state.send_chat(
+            ChatType::FactionMeta(faction.clone())
+                .chat_msg(format!("[{}] is now {} of ({})", alias, rank, faction)),
+        ),
+        Err(error) => server.notify_client(client, ChatType::CommandError.server_msg(error)),
+    }
+}
+
+fn handle_leave_faction(
+    server: &mut Server,
+    client: EcsEntity,
+    target: EcsEntity,
+    _args: String,
+    _action: &ChatCommand,
+) {
+    if client != target {
+        // This happens when [ab]using /sudo
         server.notify_client(
             client,
-            ChatType::CommandError.server_msg("Could not find your player alias"),
+            ChatType::CommandError.server_msg("It's rude to impersonate people"),
         );
+        return;
+    }
+    let result = match character_id_of(server, client) {
+        Some(character_id) => server
+            .state
+            .ecs()
+            .write_resource::<FactionManager>()
+            .remove_member(character_id, false),
+        None => Err(FactionError::NotInFaction),
+    };
+    match result {
+        Ok(removed) => {
+            faction::exit_faction(&server.state, client, &removed.faction);
+            let msg = if removed.disbanded {
+                format!("You left and disbanded the faction ({}).", removed.faction)
+            } else {
+                format!("You left the faction ({}).", removed.faction)
+            };
+            server.notify_client(client, ChatType::CommandInfo.server_msg(msg));
+        },
+        Err(error) => {
+            server.notify_client(client, ChatType::CommandError.server_msg(error.to_string()))
+        },
     }
 }
 
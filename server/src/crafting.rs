@@ -0,0 +1,36 @@
+//! Recipes that take time to craft
+//!
+//! Crafting a recipe with a crafting time doesn't happen straight away.
+//! Instead the craft is remembered in the [`PendingCrafts`] resource until it's
+//! done, at which point the crafting system asks for it to be finished.
+
+use hashbrown::HashMap;
+use specs::Entity as EcsEntity;
+
+pub struct PendingCraft {
+    pub recipe: String,
+    /// The `Time` at which the craft is done
+    pub finish_time: f64,
+}
+
+/// The craft each entity is currently working on
+#[derive(Default)]
+pub struct PendingCrafts {
+    crafts: HashMap<EcsEntity, PendingCraft>,
+}
+
+impl PendingCrafts {
+    /// Start crafting a recipe, replacing whatever the entity was crafting
+    /// before
+    pub fn start(&mut self, entity: EcsEntity, recipe: String, finish_time: f64) {
+        self.crafts.insert(entity, PendingCraft {
+            recipe,
+            finish_time,
+        });
+    }
+
+    /// Forget about all crafts for which `f` returns `false`
+    pub fn retain(&mut self, f: impl FnMut(&EcsEntity, &mut PendingCraft) -> bool) {
+        self.crafts.retain(f)
+    }
+}
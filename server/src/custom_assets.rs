@@ -0,0 +1,58 @@
+use common::assets::{self, GAMEPLAY_ASSET_DIR};
+use hashbrown::HashMap;
+use tracing::{info, warn};
+
+/// The gameplay assets this server provides through asset overlays. They are
+/// advertised to clients when joining, so that clients which don't have the
+/// same files can request them instead of needing the overlay installed.
+#[derive(Default)]
+pub struct CustomAssets {
+    files: HashMap<String, (u64, Vec<u8>)>,
+}
+
+impl CustomAssets {
+    /// Collects the overlay files from the current asset roots, which means
+    /// this has to be called after the overlays have been added
+    pub fn load() -> Self {
+        let files = assets::overlay_files(GAMEPLAY_ASSET_DIR)
+            .into_iter()
+            .filter_map(|path| match assets::read_file(&path) {
+                Ok(data) => Some((path, (assets::content_hash(&data), data))),
+                Err(e) => {
+                    warn!(?e, ?path, "Failed to read custom asset");
+                    None
+                },
+            })
+            .collect::<HashMap<_, _>>();
+
+        if !files.is_empty() {
+            info!("Providing {} custom assets to clients", files.len());
+        }
+
+        Self { files }
+    }
+
+    /// Paths and content hashes of all custom assets, sorted by path
+    pub fn hashes(&self) -> Vec<(String, u64)> {
+        let mut hashes = self
+            .files
+            .iter()
+            .map(|(path, (hash, _))| (path.clone(), *hash))
+            .collect::<Vec<_>>();
+        hashes.sort();
+        hashes
+    }
+
+    /// The contents of the requested assets. Paths which aren't custom assets
+    /// are ignored, so clients can't use this to read arbitrary files.
+    pub fn get(&self, paths: &[String]) -> Vec<(String, Vec<u8>)> {
+        paths
+            .iter()
+            .filter_map(|path| {
+                self.files
+                    .get(path)
+                    .map(|(_, data)| (path.clone(), data.clone()))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,53 @@
+//! Log of damage and healing
+//!
+//! Every [`Outcome::Damage`] the server produces is also handed to the
+//! [`DamageLog`]. When the server is configured with a `damage_log_file`, the
+//! log appends each of them to that file as one JSON object per line, so that
+//! weapons can be balanced from real fight data.
+
+use common::outcome::Outcome;
+use serde::Serialize;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, LineWriter, Write},
+    path::Path,
+};
+use tracing::{error, info};
+
+#[derive(Serialize)]
+struct Entry<'a> {
+    time: f64,
+    outcome: &'a Outcome,
+}
+
+#[derive(Default)]
+pub struct DamageLog {
+    file: Option<LineWriter<File>>,
+}
+
+impl DamageLog {
+    /// Append to the log file at `path`, creating it if needed
+    pub fn open(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        info!("Logging damage to {}", path.display());
+        Ok(Self {
+            file: Some(LineWriter::new(file)),
+        })
+    }
+
+    /// Write an outcome to the log. Does nothing when logging is disabled.
+    pub fn record(&mut self, time: f64, outcome: &Outcome) {
+        if let Some(file) = &mut self.file {
+            let result = serde_json::to_writer(&mut *file, &Entry { time, outcome })
+                .map_err(io::Error::from)
+                .and_then(|()| file.write_all(b"\n"));
+            if let Err(e) = result {
+                error!(?e, "Failed to write to the damage log, disabling it");
+                self.file = None;
+            }
+        }
+    }
+}
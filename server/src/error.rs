@@ -1,3 +1,4 @@
+use crate::persistence::PersistenceError;
 use network::{NetworkError, ParticipantError, StreamError};
 
 use std::fmt::{self, Display};
@@ -8,6 +9,7 @@ pub enum Error {
     ParticipantErr(ParticipantError),
     StreamErr(StreamError),
     DatabaseErr(diesel::result::Error),
+    PersistenceErr(PersistenceError),
     Other(String),
 }
 
@@ -27,6 +29,10 @@ impl From<diesel::result::Error> for Error {
     fn from(err: diesel::result::Error) -> Self { Error::DatabaseErr(err) }
 }
 
+impl From<PersistenceError> for Error {
+    fn from(err: PersistenceError) -> Self { Error::PersistenceErr(err) }
+}
+
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -34,6 +40,7 @@ impl Display for Error {
             Self::ParticipantErr(err) => write!(f, "Participant Error: {}", err),
             Self::StreamErr(err) => write!(f, "Stream Error: {}", err),
             Self::DatabaseErr(err) => write!(f, "Database Error: {}", err),
+            Self::PersistenceErr(err) => write!(f, "Persistence Error: {}", err),
             Self::Other(err) => write!(f, "Error: {}", err),
         }
     }
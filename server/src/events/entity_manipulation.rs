@@ -1,20 +1,17 @@
-use crate::{
-    client::Client,
-    comp::{biped_large, quadruped_medium, quadruped_small},
-    Server, SpawnPoint, StateExt,
-};
+use crate::{client::Client, damage_log::DamageLog, Server, SpawnPoint, StateExt};
 use common::{
     assets::Asset,
     comp::{
         self, buff,
         chat::{KillSource, KillType},
-        object, Alignment, Body, Damage, DamageSource, Group, HealthChange, HealthSource, Item,
-        Player, Pos, Stats,
+        object, Alignment, Body, Damage, DamageReport, DamageSource, Group, HealthChange,
+        HealthSource, Player, Pos, SkillGroupType, Stats,
     },
-    lottery::Lottery,
+    event::{EventBus, ServerEvent},
+    loot::{self, LootContext, LootTable},
     msg::{PlayerListUpdate, ServerGeneral},
     outcome::Outcome,
-    state::BlockChange,
+    state::{BlockChange, CombatRng, Time},
     sync::{Uid, UidAllocator, WorldSyncExt},
     sys::combat::BLOCK_ANGLE,
     terrain::{Block, TerrainGrid},
@@ -22,18 +19,127 @@ use common::{
     Explosion,
 };
 use comp::item::Reagent;
-use rand::prelude::*;
-use specs::{join::Join, saveload::MarkerAllocator, Entity as EcsEntity, WorldExt};
+use specs::{join::Join, saveload::MarkerAllocator, Entity as EcsEntity, ReadStorage, WorldExt};
 use tracing::error;
 use vek::Vec3;
 
-pub fn handle_damage(server: &Server, uid: Uid, change: HealthChange) {
+/// Tell clients and the damage log about a change to an entity's health
+fn log_damage(
+    ecs: &specs::World,
+    pos: Vec3<f32>,
+    attacker: Option<Uid>,
+    target: Uid,
+    report: Option<DamageReport>,
+    amount: i32,
+) {
+    let outcome = Outcome::Damage {
+        pos,
+        attacker,
+        target,
+        source: report.map(|r| r.source),
+        element: report.and_then(|r| r.element),
+        pre_mitigation: report.map_or(amount as f32, |r| r.pre_mitigation),
+        post_mitigation: amount as f32,
+        crit: report.map_or(false, |r| r.crit),
+    };
+    ecs.write_resource::<DamageLog>()
+        .record(ecs.read_resource::<Time>().0, &outcome);
+    ecs.write_resource::<Vec<Outcome>>().push(outcome);
+}
+
+/// Scale damage by the buffs of the attacker and the victim. Healing is left
+/// as it is.
+fn scale_damage_by_buffs(
+    buffs: &ReadStorage<comp::Buffs>,
+    attacker: Option<EcsEntity>,
+    target: EcsEntity,
+    amount: i32,
+) -> i32 {
+    if amount >= 0 {
+        return amount;
+    }
+    let modifier = attacker
+        .and_then(|attacker| buffs.get(attacker))
+        .map_or(1.0, |buffs| buffs.damage_dealt_modifier())
+        * buffs
+            .get(target)
+            .map_or(1.0, |buffs| buffs.damage_taken_modifier());
+    (amount as f32 * modifier) as i32
+}
+
+pub fn handle_damage(
+    server: &Server,
+    uid: Uid,
+    mut change: HealthChange,
+    report: Option<DamageReport>,
+) {
     let state = &server.state;
     let ecs = state.ecs();
     if let Some(entity) = ecs.entity_from_uid(uid.into()) {
+        let attacker = match change.cause {
+            HealthSource::Attack { by }
+            | HealthSource::Projectile { owner: Some(by) }
+            | HealthSource::Explosion { owner: Some(by) }
+            | HealthSource::Energy { owner: Some(by) } => ecs.entity_from_uid(by.into()),
+            _ => None,
+        };
+
+        change.amount = scale_damage_by_buffs(
+            &ecs.read_storage::<comp::Buffs>(),
+            attacker,
+            entity,
+            change.amount,
+        );
+
         if let Some(stats) = ecs.write_storage::<Stats>().get_mut(entity) {
             stats.health.change_by(change);
         }
+
+        if let Some(pos) = ecs.read_storage::<Pos>().get(entity) {
+            let by = match change.cause {
+                HealthSource::Attack { by }
+                | HealthSource::Projectile { owner: Some(by) }
+                | HealthSource::Explosion { owner: Some(by) }
+                | HealthSource::Energy { owner: Some(by) }
+                | HealthSource::Buff { owner: Some(by) }
+                | HealthSource::Healing { by: Some(by) } => Some(by),
+                _ => None,
+            };
+            log_damage(ecs, pos.0, by, uid, report, change.amount);
+        }
+
+        // Taking a hit wears down the victim's armor and the attacker's weapon. Damage
+        // over time from buffs and beams ticks several times a second, so it doesn't.
+        let direct_hit = matches!(
+            change.cause,
+            HealthSource::Attack { .. }
+                | HealthSource::Projectile { .. }
+                | HealthSource::Explosion { .. }
+        );
+        if change.amount < 0 && direct_hit {
+            let mut loadouts = ecs.write_storage::<comp::Loadout>();
+            if let Some(loadout) = loadouts.get_mut(entity) {
+                loadout.wear_armor(1);
+            }
+            if let Some(loadout) = attacker.and_then(|attacker| loadouts.get_mut(attacker)) {
+                loadout.wear_active_item(1);
+            }
+        }
+    }
+}
+
+/// Experience for a kill also goes to the general skill group and to the skill
+/// group of the weapon the killer is holding
+fn award_skill_exp(stats: &mut Stats, loadout: Option<&comp::Loadout>, exp: u32) {
+    stats.skill_set.add_experience(SkillGroupType::General, exp);
+    if let Some(skill_group) = loadout
+        .and_then(|loadout| loadout.active_item.as_ref())
+        .and_then(|item_config| match item_config.item.kind() {
+            comp::item::ItemKind::Tool(tool) => SkillGroupType::for_tool(&tool.kind),
+            _ => None,
+        })
+    {
+        stats.skill_set.add_experience(skill_group, exp);
     }
 }
 
@@ -63,7 +169,7 @@ pub fn handle_knockback(server: &Server, entity: EcsEntity, impulse: Vec3<f32>)
 // rescan every entity on the server again.
 #[allow(clippy::needless_collect)]
 pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSource) {
-    let state = server.state_mut();
+    let state = &mut server.state;
 
     // TODO: Investigate duplicate `Destroy` events (but don't remove this).
     // If the entity was already deleted, it can't be destroyed again.
@@ -247,6 +353,7 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
             as f32;
 
         // Distribute EXP to group
+        let loadouts = state.ecs().read_storage::<comp::Loadout>();
         let positions = state.ecs().read_storage::<Pos>();
         let alignments = state.ecs().read_storage::<Alignment>();
         let uids = state.ecs().read_storage::<Uid>();
@@ -281,6 +388,7 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
             members_in_range.into_iter().for_each(|e| {
                 if let Some(stats) = stats.get_mut(e) {
                     stats.exp.change_by(exp.ceil() as i64);
+                    award_skill_exp(stats, loadouts.get(e), exp.ceil() as u32);
                 }
             });
         }
@@ -289,6 +397,11 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
             // TODO: Discuss whether we should give EXP by Player
             // Killing or not.
             attacker_stats.exp.change_by(exp_reward.ceil() as i64);
+            award_skill_exp(
+                attacker_stats,
+                loadouts.get(attacker),
+                exp_reward.ceil() as u32,
+            );
         }
     })();
 
@@ -328,97 +441,42 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
 
         // Decide for a loot drop before turning into a lootbag
         let old_body = state.ecs().write_storage::<Body>().remove(entity);
-        let mut rng = rand::thread_rng();
-        let mut lottery = || {
-            Lottery::<String>::load_expect(match old_body {
-                Some(common::comp::Body::Humanoid(_)) => match rng.gen_range(0, 4) {
-                    0 => "common.loot_tables.loot_table_humanoids",
-                    1 => "common.loot_tables.loot_table_armor_light",
-                    2 => "common.loot_tables.loot_table_armor_cloth",
-                    3 => "common.loot_tables.loot_table_weapon_common",
-                    4 => "common.loots_tables.loot_table_armor_misc",
-                    _ => "common.loot_tables.loot_table_humanoids",
-                },
-                Some(common::comp::Body::QuadrupedSmall(quadruped_small)) => {
-                    match quadruped_small.species {
-                        quadruped_small::Species::Dodarock => match rng.gen_range(0, 6) {
-                            1 => "common.loot_tables.loot_table_rocks",
-                            _ => "common.loot_tables.loot_table_rocks",
-                        },
-                        _ => match rng.gen_range(0, 4) {
-                            0 => "common.loot_tables.loot_table_food",
-                            2 => "common.loot_tables.loot_table_animal_parts",
-                            _ => "common.loot_tables.loot_table_animal_parts",
-                        },
-                    }
-                },
-                Some(common::comp::Body::QuadrupedMedium(quadruped_medium)) => {
-                    match quadruped_medium.species {
-                        quadruped_medium::Species::Frostfang
-                        | quadruped_medium::Species::Roshwalr => {
-                            "common.loot_tables.loot_table_animal_ice"
-                        },
-                        _ => match rng.gen_range(0, 4) {
-                            0 => "common.loot_tables.loot_table_food",
-                            2 => "common.loot_tables.loot_table_animal_parts",
-                            _ => "common.loot_tables.loot_table_animal_parts",
-                        },
-                    }
-                },
-                Some(common::comp::Body::BirdMedium(_)) => match rng.gen_range(0, 3) {
-                    0 => "common.loot_tables.loot_table_food",
-                    _ => "common.loot_tables.loot_table",
-                },
-                Some(common::comp::Body::BipedLarge(biped_large)) => match biped_large.species {
-                    biped_large::Species::Wendigo => match rng.gen_range(0, 7) {
-                        0 => "common.loot_tables.loot_table_food",
-                        1 => "common.loot_tables.loot_table_wendigo",
-                        3 => "common.loot_tables.loot_table_armor_heavy",
-                        5 => "common.loot_tables.loot_table_weapon_uncommon",
-                        6 => "common.loot_tables.loot_table_weapon_rare",
-                        _ => "common.loot_tables.loot_table_cave_large",
-                    },
-                    _ => match rng.gen_range(0, 8) {
-                        0 => "common.loot_tables.loot_table_food",
-                        1 => "common.loot_tables.loot_table_armor_nature",
-                        3 => "common.loot_tables.loot_table_armor_heavy",
-                        5 => "common.loot_tables.loot_table_weapon_uncommon",
-                        6 => "common.loot_tables.loot_table_weapon_rare",
-                        _ => "common.loot_tables.loot_table_cave_large",
-                    },
-                },
-                Some(common::comp::Body::Golem(_)) => match rng.gen_range(0, 9) {
-                    0 => "common.loot_tables.loot_table_food",
-                    2 => "common.loot_tables.loot_table_armor_light",
-                    3 => "common.loot_tables.loot_table_armor_heavy",
-                    5 => "common.loot_tables.loot_table_weapon_common",
-                    6 => "common.loot_tables.loot_table_weapon_uncommon",
-                    7 => "common.loot_tables.loot_table_weapon_rare",
-                    _ => "common.loot_tables.loot_table",
-                },
-                Some(common::comp::Body::Theropod(_)) => {
-                    "common.loot_tables.loot_table_animal_parts"
-                },
-                Some(common::comp::Body::Dragon(_)) => "common.loot_tables.loot_table_weapon_rare",
-                Some(common::comp::Body::QuadrupedLow(_)) => match rng.gen_range(0, 3) {
-                    0 => "common.loot_tables.loot_table_food",
-                    1 => "common.loot_tables.loot_table_animal_parts",
-                    _ => "common.loot_tables.loot_table",
-                },
-                _ => "common.loot_tables.loot_table",
-            })
-        };
-
+        let pos = state.ecs().read_storage::<comp::Pos>().get(entity).cloned();
+        // Borrow the world on its own, a closure would capture all of `server`
+        let world = &server.world;
+        let biome = pos.and_then(|pos| {
+            world
+                .sim()
+                .get_wpos(pos.0.xy().map(|e| e as i32))
+                .map(|chunk| chunk.get_biome())
+        });
         let item = {
             let mut item_drops = state.ecs().write_storage::<comp::ItemDrop>();
-            item_drops.remove(entity).map_or_else(
-                || Item::new_from_asset_expect(lottery().choose()),
-                |item_drop| item_drop.0,
-            )
+            item_drops
+                .remove(entity)
+                .map(|item_drop| item_drop.0)
+                .or_else(|| {
+                    let ctx = LootContext {
+                        level: state
+                            .ecs()
+                            .read_storage::<Stats>()
+                            .get(entity)
+                            .map(|stats| stats.level.level()),
+                        biome,
+                        site: None,
+                    };
+                    let mut rng = rand::thread_rng();
+                    let table = old_body.as_ref().map_or(
+                        "common.loot_tables.creature.default",
+                        loot::creature_loot_table,
+                    );
+                    let mut item = LootTable::load_expect(table).roll(&ctx, &mut rng)?;
+                    item.roll_affixes(&mut rng);
+                    Some(item)
+                })
         };
 
-        let pos = state.ecs().read_storage::<comp::Pos>().get(entity).cloned();
-        if let Some(pos) = pos {
+        if let (Some(pos), Some(item)) = (pos, item) {
             let _ = state
                 .create_object(
                     comp::Pos(pos.0 + Vec3::unit_z() * 0.25),
@@ -426,7 +484,7 @@ pub fn handle_destroy(server: &mut Server, entity: EcsEntity, cause: HealthSourc
                 )
                 .with(item)
                 .build();
-        } else {
+        } else if pos.is_none() {
             error!(
                 ?entity,
                 "Entity doesn't have a position, no bag is being dropped"
@@ -459,14 +517,36 @@ pub fn handle_land_on_ground(server: &Server, entity: EcsEntity, vel: Vec3<f32>)
             let mut damage = Damage {
                 healthchange: -falldmg,
                 source: DamageSource::Falling,
+                element: None,
             };
-            if let Some(loadout) = state.ecs().read_storage::<comp::Loadout>().get(entity) {
-                damage.modify_damage(false, loadout);
-            }
+            let report = state
+                .ecs()
+                .read_storage::<comp::Loadout>()
+                .get(entity)
+                .map(|loadout| {
+                    damage.modify_damage(
+                        false,
+                        loadout,
+                        &mut *state.ecs().write_resource::<CombatRng>(),
+                    )
+                });
+            let amount = scale_damage_by_buffs(
+                &state.ecs().read_storage::<comp::Buffs>(),
+                None,
+                entity,
+                damage.healthchange as i32,
+            );
             stats.health.change_by(comp::HealthChange {
-                amount: damage.healthchange as i32,
+                amount,
                 cause: comp::HealthSource::World,
             });
+            let ecs = state.ecs();
+            if let (Some(pos), Some(uid)) = (
+                ecs.read_storage::<Pos>().get(entity),
+                ecs.read_storage::<Uid>().get(entity),
+            ) {
+                log_damage(ecs, pos.0, None, *uid, report, amount);
+            }
         }
     }
 }
@@ -542,10 +622,17 @@ pub fn handle_explosion(
             .retrieve_entity_internal(uid.into())
     });
     let groups = ecs.read_storage::<comp::Group>();
-
-    for (entity_b, pos_b, ori_b, character_b, stats_b, loadout_b) in (
+    let positions = ecs.read_storage::<comp::Pos>();
+    let players = ecs.read_storage::<Player>();
+    let pvp_flags = ecs.read_storage::<comp::PvpFlag>();
+    let pvp_rules = ecs.read_resource::<comp::PvpRules>();
+    let buffs = ecs.read_storage::<comp::Buffs>();
+    let server_eventbus = ecs.read_resource::<EventBus<ServerEvent>>();
+
+    for (entity_b, uid_b, pos_b, ori_b, character_b, stats_b, loadout_b) in (
         &ecs.entities(),
-        &ecs.read_storage::<comp::Pos>(),
+        &ecs.read_storage::<Uid>(),
+        &positions,
         &ecs.read_storage::<comp::Ori>(),
         ecs.read_storage::<comp::CharacterState>().maybe(),
         &mut ecs.write_storage::<comp::Stats>(),
@@ -570,7 +657,11 @@ pub fn handle_explosion(
             }
             // Don't heal if outside group
             // Don't damage in the same group
-            let is_damage = (friendly_damage || !same_group) && explosion.max_damage > 0;
+            let is_damage = (friendly_damage || !same_group)
+                && explosion.max_damage > 0
+                && owner_entity.map_or(true, |owner| {
+                    pvp_rules.allows(&players, &pvp_flags, &positions, owner, entity_b)
+                });
             let is_heal = same_group && explosion.max_heal > 0 && !friendly_damage;
             if !is_heal && !is_damage {
                 continue;
@@ -594,14 +685,15 @@ pub fn handle_explosion(
             let mut damage = Damage {
                 healthchange,
                 source,
+                element: explosion.element,
             };
 
             let block = character_b.map(|c_b| c_b.is_block()).unwrap_or(false)
                 && ori_b.0.angle_between(pos - pos_b.0) < BLOCK_ANGLE.to_radians() / 2.0;
 
-            if let Some(loadout) = loadout_b {
-                damage.modify_damage(block, loadout);
-            }
+            let report = loadout_b.map(|loadout| {
+                damage.modify_damage(block, loadout, &mut *ecs.write_resource::<CombatRng>())
+            });
 
             if damage.healthchange != 0.0 {
                 let cause = if is_heal {
@@ -609,10 +701,23 @@ pub fn handle_explosion(
                 } else {
                     HealthSource::Explosion { owner }
                 };
-                stats_b.health.change_by(HealthChange {
-                    amount: damage.healthchange as i32,
-                    cause,
+                let amount = scale_damage_by_buffs(
+                    &buffs,
+                    owner_entity,
+                    entity_b,
+                    damage.healthchange as i32,
+                );
+                stats_b.health.change_by(HealthChange { amount, cause });
+                log_damage(ecs, pos_b.0, owner, *uid_b, report, amount);
+                let buff_source = owner.map_or(buff::BuffSource::World, |by| {
+                    buff::BuffSource::Character { by }
                 });
+                if let Some(buff) = damage.on_hit_buff(block, buff_source) {
+                    server_eventbus.emit_now(ServerEvent::Buff {
+                        entity: entity_b,
+                        buff_change: buff::BuffChange::Add(buff),
+                    });
+                }
                 if let Some(owner) = owner_entity {
                     if let Some(energy) = ecs.write_storage::<comp::Energy>().get_mut(owner) {
                         energy
@@ -1,8 +1,8 @@
-use crate::{client::Client, Server};
+use crate::{client::Client, faction, Server};
 use common::{
     comp::{
         self,
-        group::{self, Group, GroupManager, Invite, PendingInvites},
+        group::{self, Group, GroupManager, Invite, InviteKind, PendingInvites},
         ChatType, GroupManip,
     },
     msg::{InviteAnswer, ServerGeneral},
@@ -14,9 +14,9 @@ use std::time::{Duration, Instant};
 use tracing::{error, warn};
 
 /// Time before invite times out
-const INVITE_TIMEOUT_DUR: Duration = Duration::from_secs(31);
+pub const INVITE_TIMEOUT_DUR: Duration = Duration::from_secs(31);
 /// Reduced duration shown to the client to help alleviate latency issues
-const PRESENTED_INVITE_TIMEOUT_DUR: Duration = Duration::from_secs(30);
+pub const PRESENTED_INVITE_TIMEOUT_DUR: Duration = Duration::from_secs(30);
 
 // TODO: turn chat messages into enums
 pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupManip) {
@@ -121,7 +121,10 @@ pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupMani
             let mut invite_sent = false;
             // Returns true if insertion was succesful
             let mut send_invite = || {
-                match invites.insert(invitee, group::Invite(entity)) {
+                match invites.insert(invitee, group::Invite {
+                    inviter: entity,
+                    kind: InviteKind::Group,
+                }) {
                     Err(err) => {
                         error!("Failed to insert Invite component: {:?}", err);
                         false
@@ -158,6 +161,7 @@ pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupMani
                     client.send_msg(ServerGeneral::GroupInvite {
                         inviter,
                         timeout: PRESENTED_INVITE_TIMEOUT_DUR,
+                        kind: InviteKind::Group,
                     });
                 }
             } else if agents.contains(invitee) {
@@ -179,8 +183,8 @@ pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupMani
             let mut clients = state.ecs().write_storage::<Client>();
             let uids = state.ecs().read_storage::<sync::Uid>();
             let mut invites = state.ecs().write_storage::<Invite>();
-            if let Some(inviter) = invites.remove(entity).and_then(|invite| {
-                let inviter = invite.0;
+            let accepted = invites.remove(entity).and_then(|invite| {
+                let inviter = invite.inviter;
                 let mut pending_invites = state.ecs().write_storage::<PendingInvites>();
                 let pending = &mut pending_invites.get_mut(inviter)?.0;
                 // Check that inviter has a pending invite and remove it from the list
@@ -191,8 +195,9 @@ pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupMani
                     pending_invites.remove(inviter);
                 }
 
-                Some(inviter)
-            }) {
+                Some((inviter, invite.kind))
+            });
+            if let Some((inviter, kind)) = accepted {
                 if let (Some(client), Some(target)) =
                     (clients.get_mut(inviter), uids.get(entity).copied())
                 {
@@ -201,6 +206,11 @@ pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupMani
                         answer: InviteAnswer::Accepted,
                     })
                 }
+                if kind == InviteKind::Faction {
+                    drop((clients, uids, invites));
+                    faction::handle_invite_accepted(state, inviter, entity);
+                    return;
+                }
                 let mut group_manager = state.ecs().write_resource::<GroupManager>();
                 group_manager.add_group_member(
                     inviter,
@@ -227,7 +237,7 @@ pub fn handle_group(server: &mut Server, entity: specs::Entity, manip: GroupMani
             let uids = state.ecs().read_storage::<sync::Uid>();
             let mut invites = state.ecs().write_storage::<Invite>();
             if let Some(inviter) = invites.remove(entity).and_then(|invite| {
-                let inviter = invite.0;
+                let inviter = invite.inviter;
                 let mut pending_invites = state.ecs().write_storage::<PendingInvites>();
                 let pending = &mut pending_invites.get_mut(inviter)?.0;
                 // Check that inviter has a pending invite and remove it from the list
@@ -3,12 +3,18 @@ use crate::{
     Server,
 };
 use common::{
+    assets::Asset,
     comp::{self, item},
+    lottery::Lottery,
     msg::ServerGeneral,
     sync::{Uid, WorldSyncExt},
+    vol::ReadVol,
 };
+use hashbrown::HashSet;
+use rand::Rng;
 use specs::{world::WorldExt, Entity as EcsEntity};
-use tracing::error;
+use tracing::{debug, error};
+use vek::*;
 
 pub fn handle_lantern(server: &mut Server, entity: EcsEntity, enable: bool) {
     let ecs = server.state_mut().ecs();
@@ -193,3 +199,159 @@ pub fn handle_possess(server: &Server, possessor_uid: Uid, possesse_uid: Uid) {
         }
     }
 }
+
+/// Open or close the gate at `pos`. Every connected block of the same sprite is
+/// toggled along with it, so that the whole gate moves as one.
+pub fn handle_toggle_sprite(server: &mut Server, entity: EcsEntity, pos: Vec3<i32>) {
+    // The largest gate that can be toggled at once
+    const MAX_GATE_BLOCKS: usize = 256;
+
+    let state = server.state_mut();
+
+    let in_range =
+        state
+            .ecs()
+            .read_storage::<comp::Pos>()
+            .get(entity)
+            .map_or(false, |entity_pos| {
+                entity_pos.0.distance_squared(pos.map(|e| e as f32 + 0.5))
+                    < comp::MAX_PICKUP_RANGE_SQR
+            });
+    if !in_range {
+        debug!(?entity, ?pos, "Entity is too far away to toggle sprite");
+        return;
+    }
+
+    let sprite = match state.terrain().get(pos).ok().and_then(|b| b.get_sprite()) {
+        Some(sprite) => sprite,
+        None => return,
+    };
+    let toggled = match sprite.toggled() {
+        Some(toggled) => toggled,
+        None => return,
+    };
+
+    // Flood fill to find the rest of the gate
+    let mut gate = vec![pos];
+    let mut visited = HashSet::new();
+    visited.insert(pos);
+    let mut i = 0;
+    while i < gate.len() && gate.len() < MAX_GATE_BLOCKS {
+        let current = gate[i];
+        i += 1;
+        for dir in &[
+            Vec3::unit_x(),
+            -Vec3::unit_x(),
+            Vec3::unit_y(),
+            -Vec3::unit_y(),
+            Vec3::unit_z(),
+            -Vec3::unit_z(),
+        ] {
+            let neighbor = current + *dir;
+            if visited.insert(neighbor)
+                && state
+                    .terrain()
+                    .get(neighbor)
+                    .ok()
+                    .and_then(|b| b.get_sprite())
+                    == Some(sprite)
+            {
+                gate.push(neighbor);
+            }
+        }
+    }
+
+    for pos in gate {
+        let block = state.terrain().get(pos).ok().copied();
+        if let Some(block) = block {
+            if state.can_set_block(pos) {
+                state.set_block(pos, block.with_sprite(toggled));
+            }
+        }
+    }
+}
+
+pub fn handle_mine_block(server: &mut Server, entity: EcsEntity, pos: Vec3<i32>) {
+    let state = server.state_mut();
+
+    let in_range =
+        state
+            .ecs()
+            .read_storage::<comp::Pos>()
+            .get(entity)
+            .map_or(false, |entity_pos| {
+                entity_pos.0.distance_squared(pos.map(|e| e as f32 + 0.5))
+                    < comp::MAX_PICKUP_RANGE_SQR
+            });
+    if !in_range {
+        debug!(?entity, ?pos, "Entity is too far away to mine block");
+        return;
+    }
+
+    // Go by the tool the entity actually has equipped
+    let tool = state
+        .ecs()
+        .read_storage::<comp::Loadout>()
+        .get(entity)
+        .and_then(|loadout| loadout.active_item.as_ref())
+        .and_then(|item_config| item_config.item.tool())
+        .map(|tool| (item::ToolCategory::from(&tool.kind), tool.base_power()));
+    let (tool, power) = match tool {
+        Some(tool) => tool,
+        None => return,
+    };
+
+    let block = match state.terrain().get(pos).ok().copied() {
+        Some(block) => block,
+        None => return,
+    };
+    let hardness = match block
+        .mine_hardness()
+        .filter(|_| block.mine_tool() == Some(tool))
+    {
+        Some(hardness) => hardness,
+        None => {
+            debug!(?entity, ?pos, ?tool, "Block can't be mined with this tool");
+            return;
+        },
+    };
+    // Every strike against a block the tool can work wears it down
+    if let Some(loadout) = state.ecs().write_storage::<comp::Loadout>().get_mut(entity) {
+        loadout.wear_active_item(1);
+    }
+
+    if power < hardness * 0.5
+        || !state.can_set_block(pos)
+        || rand::thread_rng().gen::<f32>() >= power / hardness
+    {
+        // The block holds, for now
+        return;
+    }
+
+    if let Some(table) = block.mine_drop_table() {
+        let item =
+            comp::Item::new_from_asset_expect(Lottery::<String>::load_expect(table).choose());
+        let pushed = state
+            .ecs()
+            .write_storage::<comp::Inventory>()
+            .get_mut(entity)
+            .map(|inv| inv.push(item.clone()).is_none());
+        match pushed {
+            Some(true) => state.write_component(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Collected(item)),
+            ),
+            Some(false) => {
+                // Leave the block where it is rather than lose what it would drop
+                state.write_component(
+                    entity,
+                    comp::InventoryUpdate::new(comp::InventoryUpdateEvent::CollectFailed),
+                );
+                return;
+            },
+            None => {},
+        }
+    }
+
+    state.set_block(pos, block.into_vacant());
+}
@@ -1,13 +1,23 @@
-use crate::{client::Client, Server, StateExt};
+use crate::{
+    client::Client,
+    crafting::PendingCrafts,
+    farming::Crops,
+    persistence::{character_updater::CharacterUpdater, storage_updater::StorageUpdate},
+    storage::StorageContainers,
+    Server, StateExt,
+};
 use common::{
     comp::{
         self, item,
         slot::{self, Slot},
         Pos, MAX_PICKUP_RANGE_SQR,
     },
+    farming::{self, CropKind},
     msg::ServerGeneral,
-    recipe::default_recipe_book,
+    recipe::{default_recipe_book, default_salvage_book, Recipe},
+    state::{BlockChange, State, Time},
     sync::{Uid, WorldSyncExt},
+    terrain::{Block, SpriteKind},
     vol::ReadVol,
 };
 use comp::LightEmitter;
@@ -37,6 +47,7 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
     let state = server.state_mut();
     let mut dropped_items = Vec::new();
     let mut thrown_items = Vec::new();
+    let mut storage_updates = Vec::new();
 
     match manip {
         comp::InventoryManip::Pickup(uid) => {
@@ -99,6 +110,21 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
             let block = state.terrain().get(pos).ok().copied();
 
             if let Some(block) = block {
+                // Containers have to be emptied before they can be picked up
+                if block.is_container()
+                    && state
+                        .ecs()
+                        .read_resource::<StorageContainers>()
+                        .get(pos)
+                        .map_or(false, |container| container.count() > 0)
+                {
+                    debug!("Can't pick up container at pos={}: it isn't empty", pos);
+                    state.write_component(
+                        entity,
+                        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::CollectFailed),
+                    );
+                    return;
+                }
                 if block.is_collectible() && state.can_set_block(pos) {
                     if let Some(item) = comp::Item::try_reclaim_from_block(block) {
                         let (event, item_was_added) = if let Some(inv) = state
@@ -131,7 +157,39 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
                             state.write_component(entity, event);
                             if item_was_added {
                                 // we made sure earlier the block was not already modified this tick
-                                state.set_block(pos, block.into_vacant())
+                                state.set_block(pos, block.into_vacant());
+                                if block.is_container() {
+                                    if let Some((_, update)) = state
+                                        .ecs()
+                                        .write_resource::<StorageContainers>()
+                                        .remove(pos)
+                                    {
+                                        storage_updates.push(update);
+                                    }
+                                }
+
+                                // Crops that were planted also give back a seed so the field can
+                                // be replanted
+                                let crop = state.ecs().write_resource::<Crops>().remove(pos);
+                                if let Some(crop) = crop {
+                                    let seed =
+                                        comp::Item::new_from_asset_expect(crop.kind.seed_item());
+                                    if let Some(mut seed) = state
+                                        .ecs()
+                                        .write_storage::<comp::Inventory>()
+                                        .get_mut(entity)
+                                        .and_then(|inv| inv.push(seed))
+                                    {
+                                        seed.put_in_world();
+                                        dropped_items.push((
+                                            Pos(pos.map(|e| e as f32 + 0.5)),
+                                            state
+                                                .read_component_copied::<comp::Ori>(entity)
+                                                .unwrap_or_default(),
+                                            seed,
+                                        ));
+                                    }
+                                }
                             };
                         }
                     } else {
@@ -304,6 +362,45 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
 
                                 Some(comp::InventoryUpdateEvent::Used)
                             },
+                            ItemKind::Utility {
+                                kind: comp::item::Utility::RepairKit,
+                                ..
+                            } => {
+                                let repaired = state
+                                    .ecs()
+                                    .write_storage::<comp::Loadout>()
+                                    .get_mut(entity)
+                                    .map_or(false, |loadout| loadout.repair_all());
+                                if repaired {
+                                    Some(comp::InventoryUpdateEvent::Used)
+                                } else {
+                                    inventory.insert_or_stack(slot, item).unwrap();
+                                    None
+                                }
+                            },
+                            ItemKind::Utility {
+                                kind: comp::item::Utility::StorageChest,
+                                ..
+                            } => {
+                                if let Some(update) = place_container(state, entity) {
+                                    storage_updates.push(update);
+                                    Some(comp::InventoryUpdateEvent::Used)
+                                } else {
+                                    inventory.insert_or_stack(slot, item).unwrap();
+                                    None
+                                }
+                            },
+                            ItemKind::Utility {
+                                kind: comp::item::Utility::Seed(crop),
+                                ..
+                            } => {
+                                if plant_seed(state, entity, *crop) {
+                                    Some(comp::InventoryUpdateEvent::Used)
+                                } else {
+                                    inventory.insert_or_stack(slot, item).unwrap();
+                                    None
+                                }
+                            },
                             _ => {
                                 inventory.insert_or_stack(slot, item).unwrap();
                                 None
@@ -389,54 +486,136 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
         },
 
         comp::InventoryManip::CraftRecipe(recipe) => {
-            if let Some(inv) = state
+            let craft_time = match default_recipe_book().get(&recipe) {
+                Some(r) if can_craft(state, entity, r) => r.craft_time,
+                _ => {
+                    debug!(?entity, ?recipe, "Can't craft recipe");
+                    return;
+                },
+            };
+
+            if craft_time > 0.0 {
+                // The recipe is crafted once the crafting system says it's done
+                let finish_time = state.ecs().read_resource::<Time>().0 + craft_time as f64;
+                state
+                    .ecs()
+                    .write_resource::<PendingCrafts>()
+                    .start(entity, recipe, finish_time);
+            } else {
+                craft(state, entity, &recipe, &mut dropped_items);
+            }
+        },
+
+        comp::InventoryManip::Salvage(slot) => {
+            let leftovers = state
                 .ecs()
                 .write_storage::<comp::Inventory>()
                 .get_mut(entity)
-            {
-                let recipe_book = default_recipe_book();
-                let craft_result = recipe_book.get(&recipe).and_then(|r| r.perform(inv).ok());
+                .and_then(|inv| default_salvage_book().salvage(inv, slot));
+
+            if let Some(leftovers) = leftovers {
+                // Drop the ingredients that didn't fit in the inventory
+                let pos = state
+                    .read_component_copied::<comp::Pos>(entity)
+                    .unwrap_or_default();
+                let ori = state
+                    .read_component_copied::<comp::Ori>(entity)
+                    .unwrap_or_default();
+                dropped_items.extend(leftovers.into_iter().map(|item| (pos, ori, item)));
+
+                state.write_component(
+                    entity,
+                    comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Salvaged),
+                );
+            } else {
+                debug!(?entity, ?slot, "Item can't be salvaged");
+            }
+        },
 
-                // FIXME: We should really require the drop and write to be atomic!
-                if craft_result.is_some() {
-                    let _ = state.ecs().write_storage().insert(
-                        entity,
-                        comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Craft),
-                    );
-                }
+        comp::InventoryManip::OpenContainer(pos) => {
+            if !container_within_range(state, entity, pos) {
+                return;
+            }
+            let container = state
+                .ecs()
+                .read_resource::<StorageContainers>()
+                .get(pos)
+                .cloned();
+            if let (Some(container), Some(client)) = (
+                container,
+                state.ecs().write_storage::<Client>().get_mut(entity),
+            ) {
+                client.send_msg(ServerGeneral::ContainerUpdate(pos, container));
+            }
+        },
 
-                // Drop the item if there wasn't enough space
-                if let Some(Some((item, amount))) = craft_result {
-                    for _ in 0..amount {
-                        dropped_items.push((
-                            state
-                                .read_component_copied::<comp::Pos>(entity)
-                                .unwrap_or_default(),
-                            state
-                                .read_component_copied::<comp::Ori>(entity)
-                                .unwrap_or_default(),
-                            item.clone(),
-                        ));
+        comp::InventoryManip::Deposit(pos, slot) => {
+            if !container_within_range(state, entity, pos) {
+                return;
+            }
+            let mut inventories = state.ecs().write_storage::<comp::Inventory>();
+            let inventory = match inventories.get_mut(entity) {
+                Some(inventory) => inventory,
+                None => return,
+            };
+            if let Some(item) = inventory.remove(slot) {
+                let result = state
+                    .ecs()
+                    .write_resource::<StorageContainers>()
+                    .modify(pos, |container| container.push(item));
+                if let Some((leftover, update)) = result {
+                    // Anything that didn't fit goes back where it came from
+                    if let Some(item) = leftover {
+                        let _ = inventory.insert(slot, item);
                     }
+                    storage_updates.push(update);
                 }
             }
+            drop(inventories);
+
+            state.write_component(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Swapped),
+            );
+            sync_container(state, pos);
         },
-    }
 
-    // Drop items
-    for (pos, ori, item) in dropped_items {
-        let vel = *ori.0 * 5.0
-            + Vec3::unit_z() * 10.0
-            + Vec3::<f32>::zero().map(|_| rand::thread_rng().gen::<f32>() - 0.5) * 4.0;
+        comp::InventoryManip::Withdraw(pos, slot) => {
+            if !container_within_range(state, entity, pos) {
+                return;
+            }
+            let mut inventories = state.ecs().write_storage::<comp::Inventory>();
+            let inventory = match inventories.get_mut(entity) {
+                Some(inventory) => inventory,
+                None => return,
+            };
+            let result =
+                state
+                    .ecs()
+                    .write_resource::<StorageContainers>()
+                    .modify(pos, |container| {
+                        if let Some(item) = container.remove(slot) {
+                            if let Some(item) = inventory.push(item) {
+                                let _ = container.insert(slot, item);
+                            }
+                        }
+                    });
+            if let Some(((), update)) = result {
+                storage_updates.push(update);
+            }
+            drop(inventories);
 
-        state
-            .create_object(Default::default(), comp::object::Body::Pouch)
-            .with(comp::Pos(pos.0 + Vec3::unit_z() * 0.25))
-            .with(item)
-            .with(comp::Vel(vel))
-            .build();
+            state.write_component(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Swapped),
+            );
+            sync_container(state, pos);
+        },
     }
 
+    drop_items(state, dropped_items);
+    persist_storage(state, entity, storage_updates);
+
     let mut rng = rand::thread_rng();
 
     // Throw items
@@ -501,6 +680,257 @@ pub fn handle_inventory(server: &mut Server, entity: EcsEntity, manip: comp::Inv
     }
 }
 
+pub fn handle_finish_craft(server: &mut Server, entity: EcsEntity, recipe: String) {
+    let state = server.state_mut();
+    let mut dropped_items = Vec::new();
+    craft(state, entity, &recipe, &mut dropped_items);
+    drop_items(state, dropped_items);
+}
+
+/// Whether the entity is near the recipe's station and has its ingredients
+fn can_craft(state: &State, entity: EcsEntity, recipe: &Recipe) -> bool {
+    let near_station = state
+        .ecs()
+        .read_storage::<comp::Pos>()
+        .get(entity)
+        .map_or(false, |pos| {
+            recipe.station_in_range(&*state.terrain(), pos.0)
+        });
+    near_station
+        && state
+            .ecs()
+            .read_storage::<comp::Inventory>()
+            .get(entity)
+            .map_or(false, |inv| inv.contains_ingredients(recipe).is_ok())
+}
+
+/// Craft a recipe, adding anything that doesn't fit in the entity's inventory
+/// to `dropped_items`
+fn craft(
+    state: &State,
+    entity: EcsEntity,
+    recipe: &str,
+    dropped_items: &mut Vec<(Pos, comp::Ori, comp::Item)>,
+) {
+    let recipe_book = default_recipe_book();
+    let recipe = match recipe_book.get(recipe) {
+        Some(recipe) => recipe,
+        None => return,
+    };
+    // Check the station again, crafts that take time may have been walked away from
+    if !can_craft(state, entity, recipe) {
+        debug!(?entity, "Can't finish crafting recipe");
+        return;
+    }
+
+    if let Some(inv) = state
+        .ecs()
+        .write_storage::<comp::Inventory>()
+        .get_mut(entity)
+    {
+        let craft_result = recipe.perform(inv).ok();
+
+        // FIXME: We should really require the drop and write to be atomic!
+        if craft_result.is_some() {
+            let _ = state.ecs().write_storage().insert(
+                entity,
+                comp::InventoryUpdate::new(comp::InventoryUpdateEvent::Craft),
+            );
+        }
+
+        // Drop the item if there wasn't enough space
+        if let Some(Some((item, amount))) = craft_result {
+            for _ in 0..amount {
+                dropped_items.push((
+                    state
+                        .read_component_copied::<comp::Pos>(entity)
+                        .unwrap_or_default(),
+                    state
+                        .read_component_copied::<comp::Ori>(entity)
+                        .unwrap_or_default(),
+                    item.clone(),
+                ));
+            }
+        }
+    }
+}
+
+fn drop_items(state: &mut State, dropped_items: Vec<(Pos, comp::Ori, comp::Item)>) {
+    for (pos, ori, item) in dropped_items {
+        let vel = *ori.0 * 5.0
+            + Vec3::unit_z() * 10.0
+            + Vec3::<f32>::zero().map(|_| rand::thread_rng().gen::<f32>() - 0.5) * 4.0;
+
+        state
+            .create_object(Default::default(), comp::object::Body::Pouch)
+            .with(comp::Pos(pos.0 + Vec3::unit_z() * 0.25))
+            .with(item)
+            .with(comp::Vel(vel))
+            .build();
+    }
+}
+
+/// Plant a crop above the farmland that the entity is aiming at, returning
+/// whether it could be planted
+fn plant_seed(state: &State, entity: EcsEntity, crop: CropKind) -> bool {
+    let ground_pos = match state
+        .ecs()
+        .read_storage::<comp::Controller>()
+        .get(entity)
+        .and_then(|controller| controller.inputs.select_pos)
+    {
+        Some(ground_pos) => ground_pos,
+        None => return false,
+    };
+    let pos = ground_pos + Vec3::unit_z();
+
+    if !within_pickup_range(
+        state.ecs().read_storage::<comp::Pos>().get(entity),
+        Some(&Pos(pos.map(|e| e as f32 + 0.5))),
+    ) {
+        debug!("Can't plant at pos={}: not within range", pos);
+        return false;
+    }
+
+    let (ground, block) = match (state.get_block(ground_pos), state.get_block(pos)) {
+        (Some(ground), Some(block)) => (ground, block),
+        _ => return false,
+    };
+    if !farming::is_farmland(ground) || !farming::can_plant_in(block) {
+        return false;
+    }
+
+    let mut block_change = state.ecs().write_resource::<BlockChange>();
+    if block_change
+        .try_set(ground_pos, farming::tilled(ground))
+        .and_then(|_| block_change.try_set(pos, Block::air(crop.stages()[0])))
+        .is_none()
+    {
+        debug!(
+            "Can't plant at pos={}: block was already set this tick",
+            pos
+        );
+        return false;
+    }
+    drop(block_change);
+
+    state
+        .ecs()
+        .write_resource::<Crops>()
+        .plant(pos, crop, state.get_time_of_day());
+    true
+}
+
+/// Place a storage container above the block that the entity is aiming at,
+/// returning the change to persist if it could be placed
+fn place_container(state: &State, entity: EcsEntity) -> Option<StorageUpdate> {
+    let ground_pos = state
+        .ecs()
+        .read_storage::<comp::Controller>()
+        .get(entity)
+        .and_then(|controller| controller.inputs.select_pos)?;
+    let pos = ground_pos + Vec3::unit_z();
+
+    if !within_pickup_range(
+        state.ecs().read_storage::<comp::Pos>().get(entity),
+        Some(&Pos(pos.map(|e| e as f32 + 0.5))),
+    ) {
+        debug!("Can't place container at pos={}: not within range", pos);
+        return None;
+    }
+
+    let (ground, block) = match (state.get_block(ground_pos), state.get_block(pos)) {
+        (Some(ground), Some(block)) => (ground, block),
+        _ => return None,
+    };
+    // Containers need the same free space as crops, but can stand on anything solid
+    if !ground.is_solid() || !farming::can_plant_in(block) {
+        return None;
+    }
+
+    if state
+        .ecs()
+        .write_resource::<BlockChange>()
+        .try_set(pos, Block::air(SpriteKind::StorageChest))
+        .is_none()
+    {
+        debug!(
+            "Can't place container at pos={}: block was already set this tick",
+            pos
+        );
+        return None;
+    }
+
+    Some(state.ecs().write_resource::<StorageContainers>().place(pos))
+}
+
+/// Write changes to storage containers to the DB, in the same transaction as
+/// the character of the entity that moved items in or out of them
+fn persist_storage(state: &State, entity: EcsEntity, storage_updates: Vec<StorageUpdate>) {
+    if storage_updates.is_empty() {
+        return;
+    }
+    let players = state.ecs().read_storage::<comp::Player>();
+    let stats = state.ecs().read_storage::<comp::Stats>();
+    let inventories = state.ecs().read_storage::<comp::Inventory>();
+    let loadouts = state.ecs().read_storage::<comp::Loadout>();
+    let character = players
+        .get(entity)
+        .and_then(|player| player.character_id)
+        .and_then(|character_id| {
+            Some((
+                character_id,
+                stats.get(entity)?,
+                inventories.get(entity)?,
+                loadouts.get(entity)?,
+            ))
+        });
+    state
+        .ecs()
+        .read_resource::<CharacterUpdater>()
+        .update_with_storage(character, storage_updates);
+}
+
+/// Whether there is a container at `pos` that the entity can reach
+fn container_within_range(state: &State, entity: EcsEntity, pos: Vec3<i32>) -> bool {
+    if !within_pickup_range(
+        state.ecs().read_storage::<comp::Pos>().get(entity),
+        Some(&Pos(pos.map(|e| e as f32 + 0.5))),
+    ) {
+        debug!("Can't reach container at pos={}: not within range", pos);
+        return false;
+    }
+    state
+        .ecs()
+        .read_resource::<StorageContainers>()
+        .get(pos)
+        .is_some()
+}
+
+/// Send the contents of the container at `pos` to every client close enough to
+/// have it open
+fn sync_container(state: &State, pos: Vec3<i32>) {
+    let container = match state
+        .ecs()
+        .read_resource::<StorageContainers>()
+        .get(pos)
+        .cloned()
+    {
+        Some(container) => container,
+        None => return,
+    };
+    let container_pos = Pos(pos.map(|e| e as f32 + 0.5));
+    for (client, client_pos) in (
+        &mut state.ecs().write_storage::<Client>(),
+        &state.ecs().read_storage::<comp::Pos>(),
+    )
+        .join()
+        .filter(|(_, client_pos)| within_pickup_range(Some(client_pos), Some(&container_pos)))
+    {
+        client.send_msg(ServerGeneral::ContainerUpdate(pos, container.clone()));
+    }
+}
+
 fn within_pickup_range(player_position: Option<&Pos>, item_position: Option<&Pos>) -> bool {
     match (player_position, item_position) {
         (Some(ppos), Some(ipos)) => ppos.0.distance_squared(ipos.0) < MAX_PICKUP_RANGE_SQR,
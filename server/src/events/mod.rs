@@ -12,14 +12,17 @@ use entity_manipulation::{
     handle_land_on_ground, handle_level_up, handle_respawn,
 };
 use group_manip::handle_group;
-use interaction::{handle_lantern, handle_mount, handle_possess, handle_unmount};
-use inventory_manip::handle_inventory;
+use interaction::{
+    handle_lantern, handle_mine_block, handle_mount, handle_possess, handle_toggle_sprite,
+    handle_unmount,
+};
+use inventory_manip::{handle_finish_craft, handle_inventory};
 use player::{handle_client_disconnect, handle_exit_ingame};
 use specs::{Entity as EcsEntity, WorldExt};
 
 mod entity_creation;
 mod entity_manipulation;
-mod group_manip;
+pub(crate) mod group_manip;
 mod interaction;
 mod inventory_manip;
 mod player;
@@ -83,7 +86,11 @@ impl Server {
                 ServerEvent::Knockback { entity, impulse } => {
                     handle_knockback(&self, entity, impulse)
                 },
-                ServerEvent::Damage { uid, change } => handle_damage(&self, uid, change),
+                ServerEvent::Damage {
+                    uid,
+                    change,
+                    report,
+                } => handle_damage(&self, uid, change, report),
                 ServerEvent::Destroy { entity, cause } => handle_destroy(self, entity, cause),
                 ServerEvent::InventoryManip(entity, manip) => handle_inventory(self, entity, manip),
                 ServerEvent::GroupManip(entity, manip) => handle_group(self, entity, manip),
@@ -137,6 +144,13 @@ impl Server {
                     entity,
                     buff_change,
                 } => handle_buff(self, entity, buff_change),
+                ServerEvent::ToggleSprite { entity, pos } => {
+                    handle_toggle_sprite(self, entity, pos)
+                },
+                ServerEvent::MineBlock { entity, pos } => handle_mine_block(self, entity, pos),
+                ServerEvent::FinishCraft { entity, recipe } => {
+                    handle_finish_craft(self, entity, recipe)
+                },
             }
         }
 
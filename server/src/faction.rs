@@ -0,0 +1,551 @@
+//! Persistent player factions
+//!
+//! A faction is created by a player, who becomes its leader. Leaders and
+//! officers can invite other players through the regular invite system, and
+//! membership is stored per character so it is restored on login.
+//!
+//! The [`FactionManager`] resource holds the current state in memory and
+//! forwards every change to the [`FactionUpdater`] to be written to the DB.
+
+use crate::{
+    client::Client,
+    persistence::{
+        faction_updater::{load_all_factions, FactionData, FactionUpdate, FactionUpdater},
+        PersistenceError,
+    },
+    state_ext::StateExt,
+};
+use common::{
+    character::CharacterId,
+    comp::{self, ChatType},
+    state::State,
+};
+use hashbrown::HashMap;
+use specs::{Entity as EcsEntity, WorldExt};
+use std::{fmt, path::Path, str::FromStr};
+use tracing::warn;
+
+/// Maximum length of a faction name, in characters
+pub const MAX_FACTION_NAME_LEN: usize = 32;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FactionRank {
+    Member,
+    Officer,
+    Leader,
+}
+
+impl FactionRank {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FactionRank::Member => "member",
+            FactionRank::Officer => "officer",
+            FactionRank::Leader => "leader",
+        }
+    }
+
+    /// Whether members with this rank may invite and kick other members
+    pub fn can_manage_members(self) -> bool { self >= FactionRank::Officer }
+}
+
+impl fmt::Display for FactionRank {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.as_str()) }
+}
+
+impl FromStr for FactionRank {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "member" => Ok(FactionRank::Member),
+            "officer" => Ok(FactionRank::Officer),
+            "leader" => Ok(FactionRank::Leader),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum FactionError {
+    InvalidName,
+    NameTaken,
+    AlreadyInFaction,
+    NotInFaction,
+    NoSuchFaction,
+    /// A leader can't leave or be demoted while other members remain, they
+    /// must hand over leadership first
+    LeaderMustTransfer,
+}
+
+impl fmt::Display for FactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", match self {
+            Self::InvalidName => "Faction names must be alphanumeric and at most 32 characters",
+            Self::NameTaken => "A faction with that name already exists",
+            Self::AlreadyInFaction => "Already in a faction",
+            Self::NotInFaction => "Not in a faction",
+            Self::NoSuchFaction => "That faction does not exist",
+            Self::LeaderMustTransfer => {
+                "The faction leader must assign a new leader before leaving or stepping down"
+            },
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FactionInfo {
+    pub members: HashMap<CharacterId, FactionRank>,
+}
+
+impl FactionInfo {
+    pub fn leader(&self) -> Option<CharacterId> {
+        self.members
+            .iter()
+            .find(|(_, rank)| **rank == FactionRank::Leader)
+            .map(|(id, _)| *id)
+    }
+}
+
+/// Outcome of a member leaving or being removed from a faction
+#[derive(Debug, PartialEq)]
+pub struct RemovedMember {
+    pub faction: String,
+    /// Set when the faction was disbanded because no members remained
+    pub disbanded: bool,
+}
+
+pub struct FactionManager {
+    factions: HashMap<String, FactionInfo>,
+    memberships: HashMap<CharacterId, String>,
+    /// Character names of all faction members, so that members can be managed
+    /// while they are offline
+    names: HashMap<CharacterId, String>,
+    updater: Option<FactionUpdater>,
+}
+
+impl FactionManager {
+    /// Load all factions from the DB and start the background updater
+    pub fn new(db_dir: &Path) -> Result<Self, PersistenceError> {
+        let mut manager = Self::from_data(load_all_factions(db_dir)?);
+        manager.updater = Some(FactionUpdater::new(db_dir)?);
+        Ok(manager)
+    }
+
+    /// Build the in-memory state without persisting any changes
+    fn from_data(data: Vec<FactionData>) -> Self {
+        let mut factions = HashMap::new();
+        let mut memberships = HashMap::new();
+        let mut names = HashMap::new();
+        for (name, members) in data {
+            let info = FactionInfo {
+                members: members
+                    .into_iter()
+                    .filter_map(|(character_id, rank, character_name)| match rank.parse() {
+                        Ok(rank) => {
+                            names.insert(character_id, character_name);
+                            Some((character_id, rank))
+                        },
+                        Err(()) => {
+                            warn!(?character_id, ?rank, "Ignoring invalid faction rank");
+                            None
+                        },
+                    })
+                    .collect(),
+            };
+            for character_id in info.members.keys() {
+                memberships.insert(*character_id, name.clone());
+            }
+            factions.insert(name, info);
+        }
+
+        Self {
+            factions,
+            memberships,
+            names,
+            updater: None,
+        }
+    }
+
+    fn persist(&self, update: FactionUpdate) {
+        if let Some(updater) = &self.updater {
+            updater.update(update);
+        }
+    }
+
+    pub fn faction_of(&self, character_id: CharacterId) -> Option<&str> {
+        self.memberships.get(&character_id).map(|s| s.as_str())
+    }
+
+    pub fn rank_of(&self, character_id: CharacterId) -> Option<FactionRank> {
+        let faction = self.memberships.get(&character_id)?;
+        self.factions
+            .get(faction)
+            .and_then(|info| info.members.get(&character_id))
+            .copied()
+    }
+
+    pub fn faction_info(&self, name: &str) -> Option<&FactionInfo> { self.factions.get(name) }
+
+    /// Find a member of `faction` by their character name, whether they are
+    /// online or not
+    pub fn member_named(&self, faction: &str, name: &str) -> Option<CharacterId> {
+        self.factions
+            .get(faction)?
+            .members
+            .keys()
+            .copied()
+            .find(|id| {
+                self.names
+                    .get(id)
+                    .map_or(false, |member| member.eq_ignore_ascii_case(name))
+            })
+    }
+
+    pub fn create_faction(
+        &mut self,
+        name: String,
+        leader: CharacterId,
+        leader_name: String,
+    ) -> Result<(), FactionError> {
+        if name.is_empty()
+            || name.chars().count() > MAX_FACTION_NAME_LEN
+            || !name.chars().all(|c| c.is_alphanumeric() || c == '_')
+        {
+            return Err(FactionError::InvalidName);
+        }
+        if self.factions.contains_key(&name) {
+            return Err(FactionError::NameTaken);
+        }
+        if self.memberships.contains_key(&leader) {
+            return Err(FactionError::AlreadyInFaction);
+        }
+
+        let mut info = FactionInfo::default();
+        info.members.insert(leader, FactionRank::Leader);
+        self.factions.insert(name.clone(), info);
+        self.memberships.insert(leader, name.clone());
+        self.names.insert(leader, leader_name);
+        self.persist(FactionUpdate::Create {
+            name,
+            leader,
+            leader_rank: FactionRank::Leader.as_str().to_owned(),
+        });
+
+        Ok(())
+    }
+
+    pub fn add_member(
+        &mut self,
+        faction: &str,
+        character_id: CharacterId,
+        character_name: String,
+    ) -> Result<(), FactionError> {
+        if self.memberships.contains_key(&character_id) {
+            return Err(FactionError::AlreadyInFaction);
+        }
+        let info = self
+            .factions
+            .get_mut(faction)
+            .ok_or(FactionError::NoSuchFaction)?;
+
+        info.members.insert(character_id, FactionRank::Member);
+        self.memberships.insert(character_id, faction.to_owned());
+        self.names.insert(character_id, character_name);
+        self.persist(FactionUpdate::SetMember {
+            faction: faction.to_owned(),
+            character_id,
+            rank: FactionRank::Member.as_str().to_owned(),
+        });
+
+        Ok(())
+    }
+
+    /// Change the rank of a member. Promoting a member to leader demotes the
+    /// current leader to officer.
+    pub fn set_rank(
+        &mut self,
+        character_id: CharacterId,
+        rank: FactionRank,
+    ) -> Result<(), FactionError> {
+        let faction = self
+            .memberships
+            .get(&character_id)
+            .ok_or(FactionError::NotInFaction)?
+            .clone();
+        let info = self
+            .factions
+            .get_mut(&faction)
+            .ok_or(FactionError::NoSuchFaction)?;
+
+        let mut changed = Vec::new();
+        match (info.members.get(&character_id).copied(), rank) {
+            (Some(FactionRank::Leader), FactionRank::Leader) => {},
+            (Some(FactionRank::Leader), _) => return Err(FactionError::LeaderMustTransfer),
+            (_, FactionRank::Leader) => {
+                if let Some(old_leader) = info.leader() {
+                    info.members.insert(old_leader, FactionRank::Officer);
+                    changed.push((old_leader, FactionRank::Officer));
+                }
+                info.members.insert(character_id, rank);
+                changed.push((character_id, rank));
+            },
+            (_, _) => {
+                info.members.insert(character_id, rank);
+                changed.push((character_id, rank));
+            },
+        }
+
+        for (character_id, rank) in changed {
+            self.persist(FactionUpdate::SetMember {
+                faction: faction.clone(),
+                character_id,
+                rank: rank.as_str().to_owned(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Remove a member from their faction. If `force` is not set, a leader
+    /// can only leave once they are the last member, which disbands the
+    /// faction. When forced (e.g. the character was deleted) the highest
+    /// ranked remaining member takes over.
+    pub fn remove_member(
+        &mut self,
+        character_id: CharacterId,
+        force: bool,
+    ) -> Result<RemovedMember, FactionError> {
+        let faction = self
+            .memberships
+            .get(&character_id)
+            .ok_or(FactionError::NotInFaction)?
+            .clone();
+        let info = self
+            .factions
+            .get_mut(&faction)
+            .ok_or(FactionError::NoSuchFaction)?;
+
+        let was_leader = info.members.get(&character_id) == Some(&FactionRank::Leader);
+        if was_leader && info.members.len() > 1 && !force {
+            return Err(FactionError::LeaderMustTransfer);
+        }
+
+        info.members.remove(&character_id);
+        let mut updates = vec![FactionUpdate::RemoveMember { character_id }];
+
+        let disbanded = info.members.is_empty();
+        if disbanded {
+            self.factions.remove(&faction);
+            updates.push(FactionUpdate::Disband {
+                name: faction.clone(),
+            });
+        } else if was_leader {
+            // Hand leadership to the highest ranked member, lowest id breaking ties
+            let successor = info
+                .members
+                .iter()
+                .max_by_key(|(id, rank)| (**rank, std::cmp::Reverse(**id)))
+                .map(|(id, _)| *id);
+            if let Some(successor) = successor {
+                info.members.insert(successor, FactionRank::Leader);
+                updates.push(FactionUpdate::SetMember {
+                    faction: faction.clone(),
+                    character_id: successor,
+                    rank: FactionRank::Leader.as_str().to_owned(),
+                });
+            }
+        }
+
+        self.memberships.remove(&character_id);
+        self.names.remove(&character_id);
+        for update in updates {
+            self.persist(update);
+        }
+
+        Ok(RemovedMember { faction, disbanded })
+    }
+}
+
+/// Add `invitee` to the faction of `inviter` after they accepted a faction
+/// invite
+pub fn handle_invite_accepted(state: &State, inviter: EcsEntity, invitee: EcsEntity) {
+    let ecs = state.ecs();
+    let character_ids = {
+        let players = ecs.read_storage::<comp::Player>();
+        players
+            .get(inviter)
+            .and_then(|p| p.character_id)
+            .zip(players.get(invitee).and_then(|p| p.character_id))
+    };
+    let invitee_name = ecs
+        .read_storage::<comp::Stats>()
+        .get(invitee)
+        .map(|stats| stats.name.clone())
+        .unwrap_or_default();
+    let (inviter_id, invitee_id) = match character_ids {
+        Some(ids) => ids,
+        None => return,
+    };
+
+    let result = {
+        let mut faction_manager = ecs.write_resource::<FactionManager>();
+        // The inviter may have left the faction or been demoted since sending the
+        // invite
+        match faction_manager
+            .faction_of(inviter_id)
+            .filter(|_| {
+                faction_manager
+                    .rank_of(inviter_id)
+                    .map_or(false, FactionRank::can_manage_members)
+            })
+            .map(|faction| faction.to_owned())
+        {
+            Some(faction) => faction_manager
+                .add_member(&faction, invitee_id, invitee_name)
+                .map(|()| faction),
+            None => Err(FactionError::NoSuchFaction),
+        }
+    };
+
+    match result {
+        Ok(faction) => enter_faction(state, invitee, &faction),
+        Err(error) => {
+            if let Some(client) = ecs.write_storage::<Client>().get_mut(invitee) {
+                client.send_msg(
+                    ChatType::Meta.server_msg(format!("Could not join faction: {}", error)),
+                );
+            }
+        },
+    }
+}
+
+/// Give an in-game entity the components for membership of `faction` and
+/// announce it to the faction
+pub fn enter_faction(state: &State, entity: EcsEntity, faction: &str) {
+    let ecs = state.ecs();
+    let _ = ecs
+        .write_storage()
+        .insert(entity, comp::ChatMode::Faction(faction.to_owned()));
+    let _ = ecs
+        .write_storage()
+        .insert(entity, comp::Faction(faction.to_owned()));
+    if let Some(alias) = ecs
+        .read_storage::<comp::Player>()
+        .get(entity)
+        .map(|player| player.alias.clone())
+    {
+        state.send_chat(
+            ChatType::FactionMeta(faction.to_owned())
+                .chat_msg(format!("[{}] joined faction ({})", alias, faction)),
+        );
+    }
+}
+
+/// Remove the faction components from an in-game entity and announce it to
+/// the faction they left
+pub fn exit_faction(state: &State, entity: EcsEntity, faction: &str) {
+    let ecs = state.ecs();
+    ecs.write_storage::<comp::Faction>().remove(entity);
+    if let Some(comp::ChatMode::Faction(_)) = ecs.read_storage::<comp::ChatMode>().get(entity) {
+        let _ = ecs
+            .write_storage()
+            .insert(entity, comp::ChatMode::default());
+    }
+    if let Some(alias) = ecs
+        .read_storage::<comp::Player>()
+        .get(entity)
+        .map(|player| player.alias.clone())
+    {
+        state.send_chat(
+            ChatType::FactionMeta(faction.to_owned())
+                .chat_msg(format!("[{}] left faction ({})", alias, faction)),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> FactionManager { FactionManager::from_data(Vec::new()) }
+
+    #[test]
+    fn create_and_join() {
+        let mut manager = manager();
+        assert_eq!(
+            manager.create_faction("Rangers".to_owned(), 1, "Ayla".to_owned()),
+            Ok(())
+        );
+        assert_eq!(
+            manager.create_faction("Rangers".to_owned(), 2, "Brom".to_owned()),
+            Err(FactionError::NameTaken)
+        );
+        assert_eq!(
+            manager.create_faction("Bad name!".to_owned(), 2, "Brom".to_owned()),
+            Err(FactionError::InvalidName)
+        );
+        // Names are limited in characters, not bytes
+        assert_eq!(
+            manager.create_faction("ö".repeat(MAX_FACTION_NAME_LEN + 1), 2, "Brom".to_owned()),
+            Err(FactionError::InvalidName)
+        );
+        assert_eq!(
+            manager.create_faction("ö".repeat(MAX_FACTION_NAME_LEN), 3, "Cato".to_owned()),
+            Ok(())
+        );
+        assert_eq!(manager.add_member("Rangers", 2, "Brom".to_owned()), Ok(()));
+        assert_eq!(
+            manager.add_member("Rangers", 2, "Brom".to_owned()),
+            Err(FactionError::AlreadyInFaction)
+        );
+        assert_eq!(manager.faction_of(2), Some("Rangers"));
+        assert_eq!(manager.rank_of(1), Some(FactionRank::Leader));
+        assert_eq!(manager.rank_of(2), Some(FactionRank::Member));
+        assert_eq!(manager.member_named("Rangers", "brom"), Some(2));
+        assert_eq!(manager.member_named("Rangers", "Cato"), None);
+    }
+
+    #[test]
+    fn leadership_transfer() {
+        let mut manager = manager();
+        manager
+            .create_faction("Rangers".to_owned(), 1, "Ayla".to_owned())
+            .unwrap();
+        manager.add_member("Rangers", 2, "Brom".to_owned()).unwrap();
+        assert_eq!(
+            manager.remove_member(1, false),
+            Err(FactionError::LeaderMustTransfer)
+        );
+        assert_eq!(manager.set_rank(2, FactionRank::Leader), Ok(()));
+        assert_eq!(manager.rank_of(1), Some(FactionRank::Officer));
+        assert_eq!(manager.rank_of(2), Some(FactionRank::Leader));
+    }
+
+    #[test]
+    fn forced_removal_promotes_successor() {
+        let mut manager = manager();
+        manager
+            .create_faction("Rangers".to_owned(), 1, "Ayla".to_owned())
+            .unwrap();
+        manager.add_member("Rangers", 2, "Brom".to_owned()).unwrap();
+        manager.add_member("Rangers", 3, "Cato".to_owned()).unwrap();
+        manager.set_rank(3, FactionRank::Officer).unwrap();
+        assert_eq!(
+            manager.remove_member(1, true),
+            Ok(RemovedMember {
+                faction: "Rangers".to_owned(),
+                disbanded: false,
+            })
+        );
+        assert_eq!(manager.rank_of(3), Some(FactionRank::Leader));
+        assert_eq!(
+            manager.remove_member(2, false).map(|r| r.disbanded),
+            Ok(false)
+        );
+        assert_eq!(
+            manager.remove_member(3, false).map(|r| r.disbanded),
+            Ok(true)
+        );
+        assert!(manager.faction_info("Rangers").is_none());
+    }
+}
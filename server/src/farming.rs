@@ -0,0 +1,65 @@
+//! Crops planted by players
+//!
+//! Generated chunks know nothing about crops, so the [`Crops`] resource keeps
+//! track of every planted crop and is used to restore them whenever their
+//! chunk is loaded again. A crop's growth stage is derived from the time of day
+//! at which it was planted, so crops keep growing while their chunk is
+//! unloaded.
+
+use common::{
+    farming::{self, CropKind},
+    terrain::{Block, SpriteKind, TerrainChunk, TerrainGrid},
+    vol::{ReadVol, WriteVol},
+};
+use hashbrown::HashMap;
+use vek::*;
+
+#[derive(Copy, Clone, Debug)]
+pub struct Crop {
+    pub kind: CropKind,
+    /// The `TimeOfDay` at which the crop was planted
+    pub planted_at: f64,
+}
+
+impl Crop {
+    /// The sprite the crop should currently be displayed as
+    pub fn stage(&self, time_of_day: f64) -> SpriteKind {
+        self.kind.stage_at(self.planted_at, time_of_day)
+    }
+}
+
+#[derive(Default)]
+pub struct Crops {
+    crops: HashMap<Vec3<i32>, Crop>,
+}
+
+impl Crops {
+    pub fn plant(&mut self, pos: Vec3<i32>, kind: CropKind, time_of_day: f64) {
+        self.crops.insert(pos, Crop {
+            kind,
+            planted_at: time_of_day,
+        });
+    }
+
+    /// Forget about the crop at `pos`, returning it if there was one
+    pub fn remove(&mut self, pos: Vec3<i32>) -> Option<Crop> { self.crops.remove(&pos) }
+
+    pub fn retain(&mut self, f: impl FnMut(&Vec3<i32>, &mut Crop) -> bool) { self.crops.retain(f) }
+
+    /// Write the crops that were planted in a chunk back into it after it has
+    /// been regenerated.
+    pub fn restore(&self, key: Vec2<i32>, chunk: &mut TerrainChunk, time_of_day: f64) {
+        for (wpos, crop) in self
+            .crops
+            .iter()
+            .filter(|(wpos, _)| TerrainGrid::chunk_key(**wpos) == key)
+        {
+            let rpos = TerrainGrid::chunk_offs(*wpos);
+            let below = rpos - Vec3::unit_z();
+            if let Ok(ground) = chunk.get(below).map(|b| *b) {
+                let _ = chunk.set(below, farming::tilled(ground));
+            }
+            let _ = chunk.set(rpos, Block::air(crop.stage(time_of_day)));
+        }
+    }
+}
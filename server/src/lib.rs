@@ -10,15 +10,21 @@ pub mod chunk_generator;
 pub mod client;
 pub mod cmd;
 pub mod connection_handler;
+pub mod crafting;
+pub mod custom_assets;
+pub mod damage_log;
 mod data_dir;
 pub mod error;
 pub mod events;
+pub mod faction;
+pub mod farming;
 pub mod input;
 pub mod login_provider;
 pub mod metrics;
 pub mod persistence;
 pub mod settings;
 pub mod state_ext;
+pub mod storage;
 pub mod sys;
 #[cfg(not(feature = "worldgen"))] mod test_world;
 
@@ -37,12 +43,19 @@ use crate::{
     client::{Client, RegionSubscription},
     cmd::ChatCommandExt,
     connection_handler::ConnectionHandler,
+    crafting::PendingCrafts,
+    custom_assets::CustomAssets,
+    damage_log::DamageLog,
     data_dir::DataDir,
+    faction::FactionManager,
+    farming::Crops,
     login_provider::LoginProvider,
     state_ext::StateExt,
+    storage::StorageContainers,
     sys::sentinel::{DeletedEntities, TrackedComps},
 };
 use common::{
+    assets,
     cmd::ChatCommand,
     comp::{self, ChatType},
     event::{EventBus, ServerEvent},
@@ -51,7 +64,7 @@ use common::{
     },
     outcome::Outcome,
     recipe::default_recipe_book,
-    state::{State, TimeOfDay},
+    state::{CombatRng, State, TimeOfDay},
     sync::WorldSyncExt,
     terrain::TerrainChunkSize,
     vol::{ReadVol, RectVolSize},
@@ -122,6 +135,19 @@ impl Server {
             info!("Authentication is disabled");
         }
 
+        // Asset overlays have to be registered before anything is loaded from the
+        // assets, most notably the world
+        for overlay in &settings.asset_overlays {
+            let overlay = data_dir.join(overlay);
+            assets::add_asset_root(overlay.clone()).map_err(|e| {
+                Error::Other(format!(
+                    "Failed to add asset overlay {}: {}",
+                    overlay.display(),
+                    e
+                ))
+            })?;
+        }
+
         // Relative to data_dir
         const PERSISTENCE_DB_DIR: &str = "saves";
         let persistence_db_dir = data_dir.join(PERSISTENCE_DB_DIR);
@@ -159,7 +185,33 @@ impl Server {
         state
             .ecs_mut()
             .insert(CharacterLoader::new(&persistence_db_dir)?);
+        state
+            .ecs_mut()
+            .insert(FactionManager::new(&persistence_db_dir)?);
+        state
+            .ecs_mut()
+            .insert(StorageContainers::new(&persistence_db_dir)?);
+        state.ecs_mut().insert(CustomAssets::load());
+        state.ecs_mut().insert(Crops::default());
+        state.ecs_mut().insert(PendingCrafts::default());
         state.ecs_mut().insert(Vec::<Outcome>::new());
+        state
+            .ecs_mut()
+            .insert(CombatRng::new(settings.world_seed as u64));
+        let damage_log = match &settings.damage_log_file {
+            Some(path) => {
+                let path = data_dir.join(path);
+                DamageLog::open(&path).map_err(|e| {
+                    Error::Other(format!(
+                        "Failed to open damage log {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?
+            },
+            None => DamageLog::default(),
+        };
+        state.ecs_mut().insert(damage_log);
 
         // System timers for performance monitoring
         state.ecs_mut().insert(sys::EntitySyncTimer::default());
@@ -171,6 +223,8 @@ impl Server {
         state.ecs_mut().insert(sys::WaypointTimer::default());
         state.ecs_mut().insert(sys::InviteTimeoutTimer::default());
         state.ecs_mut().insert(sys::PersistenceTimer::default());
+        state.ecs_mut().insert(sys::FarmingTimer::default());
+        state.ecs_mut().insert(sys::CraftingTimer::default());
 
         // System schedulers to control execution of systems
         state
@@ -221,7 +275,7 @@ impl Server {
                 // Load default map from assets.
                 FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into())
             },
-            ..WorldOpts::default()
+            gen_opts: settings.world_gen.clone(),
         });
         #[cfg(feature = "worldgen")]
         let map = world.get_map_data(index.as_index_ref());
@@ -305,6 +359,29 @@ impl Server {
         // set the spawn point we calculated above
         state.ecs_mut().insert(SpawnPoint(spawn_point));
 
+        #[cfg(feature = "worldgen")]
+        let town_bounds = index
+            .sites
+            .values()
+            .filter_map(|site| site.town_bounds())
+            .collect::<Vec<_>>();
+        #[cfg(not(feature = "worldgen"))]
+        let town_bounds = Vec::new();
+
+        // Towns are safe from PvP when it is only allowed outside settlements
+        let pvp_rules = comp::PvpRules {
+            mode: settings.pvp_mode,
+            zones: if settings.pvp_mode == comp::PvpMode::OutsideSettlements {
+                town_bounds
+                    .into_iter()
+                    .map(|bounds| (bounds, comp::PvpMode::Off))
+                    .collect()
+            } else {
+                Vec::new()
+            },
+        };
+        state.ecs_mut().insert(pvp_rules);
+
         // Set starting time for the server.
         state.ecs_mut().write_resource::<TimeOfDay>().0 = settings.start_time;
 
@@ -533,6 +610,27 @@ impl Server {
                         ServerGeneral::CharacterActionError(error.to_string()),
                     ),
                 },
+                CharacterLoaderResponseType::CharacterDeleted {
+                    character_id,
+                    result,
+                } => match result {
+                    Ok(character_list_data) => {
+                        // Keep the in-memory faction state in line with the DB
+                        let _ = self
+                            .state
+                            .ecs()
+                            .write_resource::<FactionManager>()
+                            .remove_member(character_id, true);
+                        self.notify_client(
+                            query_result.entity,
+                            ServerGeneral::CharacterListUpdate(character_list_data),
+                        )
+                    },
+                    Err(error) => self.notify_client(
+                        query_result.entity,
+                        ServerGeneral::CharacterActionError(error.to_string()),
+                    ),
+                },
                 CharacterLoaderResponseType::CharacterData(result) => {
                     let message = match *result {
                         Ok(character_data) => ServerEvent::UpdateCharacterData {
@@ -847,6 +945,7 @@ impl Server {
                     client_timeout: self.settings().client_timeout,
                     world_map: self.map.clone(),
                     recipe_book: (&*default_recipe_book()).clone(),
+                    custom_assets: self.state.ecs().read_resource::<CustomAssets>().hashes(),
                 })?;
 
             frontend_events.push(Event::ClientConnected { entity });
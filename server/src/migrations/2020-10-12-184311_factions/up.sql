@@ -0,0 +1,20 @@
+-- Persistent player factions and their members
+
+CREATE TABLE faction
+(
+    name TEXT NOT NULL
+        PRIMARY KEY
+);
+
+CREATE TABLE faction_member
+(
+    character_id INTEGER NOT NULL
+        PRIMARY KEY
+        REFERENCES character(character_id),
+    faction_name TEXT NOT NULL
+        REFERENCES faction(name),
+    rank         TEXT NOT NULL
+);
+
+CREATE INDEX idx_faction_member_faction_name
+    ON faction_member(faction_name);
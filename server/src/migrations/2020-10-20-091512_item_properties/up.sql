@@ -0,0 +1,3 @@
+-- Per-instance item state (durability, affixes and custom names) stored as JSON.
+-- An empty string means the item is in its default state.
+ALTER TABLE item ADD COLUMN properties TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,24 @@
+PRAGMA foreign_keys=off;
+
+-- SQLite does not support removing columns from tables so we must rename the current table,
+-- recreate the previous version of the table, then copy over the data from the renamed table
+ALTER TABLE stats RENAME TO _stats_old;
+
+CREATE TABLE stats
+(
+    stats_id INT NOT NULL
+        PRIMARY KEY
+        REFERENCES entity(entity_id),
+    level INT NOT NULL,
+    exp INT NOT NULL,
+    endurance INT NOT NULL,
+    fitness INT NOT NULL,
+    willpower INT NOT NULL
+);
+
+INSERT INTO stats (stats_id, level, exp, endurance, fitness, willpower)
+SELECT stats_id, level, exp, endurance, fitness, willpower FROM _stats_old;
+
+DROP TABLE _stats_old;
+
+PRAGMA foreign_keys=on;
@@ -0,0 +1,3 @@
+-- Skill groups and unlocked skills of the character, stored as JSON.
+-- An empty string means the character has the default skill set.
+ALTER TABLE stats ADD COLUMN skill_set TEXT NOT NULL DEFAULT '';
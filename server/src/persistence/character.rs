@@ -30,7 +30,7 @@ use tracing::{error, trace};
 /// Private module for very tightly coupled database conversion methods.  In
 /// general, these have many invariants that need to be maintained when they're
 /// called--do not assume it's safe to make these public!
-mod conversions;
+pub(in crate::persistence) mod conversions;
 
 pub(crate) type EntityId = i64;
 
@@ -39,7 +39,7 @@ const INVENTORY_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.
 const LOADOUT_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.loadout";
 const INVENTORY_PSEUDO_CONTAINER_POSITION: &str = "inventory";
 const LOADOUT_PSEUDO_CONTAINER_POSITION: &str = "loadout";
-const WORLD_PSEUDO_CONTAINER_ID: EntityId = 1;
+pub(in crate::persistence) const WORLD_PSEUDO_CONTAINER_ID: EntityId = 1;
 
 #[derive(Clone, Copy)]
 struct CharacterContainers {
@@ -86,7 +86,7 @@ pub fn load_character_data(
     Ok((
         convert_body_from_database(&char_body)?,
         convert_stats_from_database(&stats_data, character_data.alias),
-        convert_inventory_from_database_items(&inventory_items)?,
+        convert_inventory_from_database_items(&inventory_items, comp::Inventory::new_empty())?,
         convert_loadout_from_database_items(&loadout_items)?,
     ))
 }
@@ -173,6 +173,7 @@ pub fn create_character(
             parent_container_item_id: WORLD_PSEUDO_CONTAINER_ID,
             item_definition_id: CHARACTER_PSEUDO_CONTAINER_DEF_ID.to_owned(),
             position: character_id.to_string(),
+            properties: String::new(),
         },
         Item {
             stack_size: 1,
@@ -180,6 +181,7 @@ pub fn create_character(
             parent_container_item_id: character_id,
             item_definition_id: INVENTORY_PSEUDO_CONTAINER_DEF_ID.to_owned(),
             position: INVENTORY_PSEUDO_CONTAINER_POSITION.to_owned(),
+            properties: String::new(),
         },
         Item {
             stack_size: 1,
@@ -187,6 +189,7 @@ pub fn create_character(
             parent_container_item_id: character_id,
             item_definition_id: LOADOUT_PSEUDO_CONTAINER_DEF_ID.to_owned(),
             position: LOADOUT_PSEUDO_CONTAINER_POSITION.to_owned(),
+            properties: String::new(),
         },
     ];
     let pseudo_container_count = diesel::insert_into(item)
@@ -300,6 +303,14 @@ pub fn delete_character(
         )
         .first::<Character>(&*connection)?;
 
+    // Delete faction membership, if any. The in-memory faction state is updated
+    // separately by the `FactionManager`.
+    diesel::delete(
+        schema::faction_member::dsl::faction_member
+            .filter(schema::faction_member::dsl::character_id.eq(char_id)),
+    )
+    .execute(&*connection)?;
+
     // Delete character
     let character_count = diesel::delete(
         character
@@ -399,7 +410,7 @@ pub fn check_character_limit(uuid: &str, connection: VelorenTransaction) -> Resu
 /// count of new entity IDs.
 ///
 /// These are then inserted into the entities table.
-fn get_new_entity_ids(
+pub(in crate::persistence) fn get_new_entity_ids(
     conn: VelorenTransaction,
     mut max: impl FnMut(i64) -> i64,
 ) -> Result<Range<EntityId>, Error> {
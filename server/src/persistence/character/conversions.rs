@@ -12,6 +12,7 @@ use common::{
 use core::{convert::TryFrom, num::NonZeroU64};
 use itertools::{Either, Itertools};
 use std::sync::Arc;
+use tracing::warn;
 
 pub struct ItemModelPair {
     pub comp: Arc<common::comp::item::ItemId>,
@@ -145,6 +146,7 @@ pub fn convert_items_to_database_items(
                         } else {
                             1
                         },
+                        properties: convert_item_state_to_database_json(item.state()),
                     },
                     // Continue to remember the atomic, in case we detect an error later and want
                     // to roll back to preserve liveness.
@@ -156,6 +158,60 @@ pub fn convert_items_to_database_items(
         })
 }
 
+/// Rows for the contents of a storage container. Containers are always
+/// rewritten as a whole, so every item gets a fresh id, starting at `first_id`.
+pub fn convert_storage_to_database_items(
+    inventory: &Inventory,
+    container_id: EntityId,
+    first_id: EntityId,
+) -> Vec<Item> {
+    inventory
+        .slots()
+        .iter()
+        .enumerate()
+        .filter_map(|(slot, item)| item.as_ref().map(|item| (slot, item)))
+        .zip(first_id..)
+        .map(|((slot, item), item_id)| Item {
+            item_id,
+            parent_container_item_id: container_id,
+            item_definition_id: item.item_definition_id().to_owned(),
+            stack_size: if item.is_stackable() {
+                item.amount() as i32
+            } else {
+                1
+            },
+            position: slot.to_string(),
+            properties: convert_item_state_to_database_json(item.state()),
+        })
+        .collect()
+}
+
+/// Items in their default state are stored with empty properties, which keeps
+/// the common case of untouched stackable items cheap.
+fn convert_item_state_to_database_json(state: &item::ItemState) -> String {
+    if *state == item::ItemState::default() {
+        String::new()
+    } else {
+        serde_json::to_string(state).unwrap_or_else(|e| {
+            warn!(?e, "Failed to serialize item state, it will be reset");
+            String::new()
+        })
+    }
+}
+
+/// Items without properties, including every item saved before items had
+/// any, keep the state of a brand new item of their definition, so that they
+/// still wear out like new items do.
+fn convert_item_state_from_database_json(
+    item: &mut common::comp::Item,
+    properties: &str,
+) -> Result<(), Error> {
+    if !properties.is_empty() {
+        item.set_state(serde_json::de::from_str(properties)?);
+    }
+    Ok(())
+}
+
 pub fn convert_body_to_database_json(body: &CompBody) -> Result<String, Error> {
     let json_model = match body {
         common::comp::Body::Humanoid(humanoid_body) => HumanoidBody::from(humanoid_body),
@@ -173,13 +229,45 @@ pub fn convert_stats_to_database(character_id: CharacterId, stats: &common::comp
         endurance: stats.endurance as i32,
         fitness: stats.fitness as i32,
         willpower: stats.willpower as i32,
+        skill_set: convert_skill_set_to_database_json(&stats.skill_set),
+    }
+}
+
+/// Characters with the default skill set are stored with an empty skill set,
+/// like items in their default state.
+fn convert_skill_set_to_database_json(skill_set: &skills::SkillSet) -> String {
+    if *skill_set == skills::SkillSet::default() {
+        String::new()
+    } else {
+        serde_json::to_string(skill_set).unwrap_or_else(|e| {
+            warn!(?e, "Failed to serialize skill set, it will be reset");
+            String::new()
+        })
     }
 }
 
-pub fn convert_inventory_from_database_items(database_items: &[Item]) -> Result<Inventory, Error> {
-    let mut inventory = Inventory::new_empty();
+/// A skill set that can't be parsed, e.g. because a skill was removed from the
+/// game, is reset rather than locking the player out of their character.
+fn convert_skill_set_from_database_json(skill_set: &str) -> skills::SkillSet {
+    if skill_set.is_empty() {
+        skills::SkillSet::default()
+    } else {
+        serde_json::de::from_str(skill_set).unwrap_or_else(|e| {
+            warn!(?e, "Failed to parse skill set, it will be reset");
+            skills::SkillSet::default()
+        })
+    }
+}
+
+/// Fills the empty `inventory` with the given items, using their positions as
+/// slot indices
+pub fn convert_inventory_from_database_items(
+    database_items: &[Item],
+    mut inventory: Inventory,
+) -> Result<Inventory, Error> {
     for db_item in database_items.iter() {
         let mut item = common::comp::Item::new_from_asset(db_item.item_definition_id.as_str())?;
+        convert_item_state_from_database_json(&mut item, &db_item.properties)?;
 
         // NOTE: Since this is freshly loaded, the atomic is *unique.*
         let comp = item.get_item_id_for_database();
@@ -233,7 +321,8 @@ pub fn convert_inventory_from_database_items(database_items: &[Item]) -> Result<
 pub fn convert_loadout_from_database_items(database_items: &[Item]) -> Result<Loadout, Error> {
     let mut loadout = loadout_builder::LoadoutBuilder::new();
     for db_item in database_items.iter() {
-        let item = common::comp::Item::new_from_asset(db_item.item_definition_id.as_str())?;
+        let mut item = common::comp::Item::new_from_asset(db_item.item_definition_id.as_str())?;
+        convert_item_state_from_database_json(&mut item, &db_item.properties)?;
         // NOTE: item id is currently *unique*, so we can store the ID safely.
         let comp = item.get_item_id_for_database();
         comp.store(Some(NonZeroU64::try_from(db_item.item_id as u64).map_err(
@@ -326,6 +415,31 @@ pub fn convert_stats_from_database(stats: &Stats, alias: String) -> common::comp
     new_stats.endurance = stats.endurance as u32;
     new_stats.fitness = stats.fitness as u32;
     new_stats.willpower = stats.willpower as u32;
+    new_stats.skill_set = convert_skill_set_from_database_json(&stats.skill_set);
 
     new_stats
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_items_without_properties_wear_out() {
+        let db_item = Item {
+            item_id: 42,
+            parent_container_item_id: 2,
+            item_definition_id: "common.items.weapons.sword.starter_sword".to_owned(),
+            stack_size: 1,
+            position: "0".to_owned(),
+            properties: String::new(),
+        };
+        let inventory =
+            convert_inventory_from_database_items(&[db_item], Inventory::new_with_size(1)).unwrap();
+        let item = inventory.get(0).unwrap();
+
+        let new_item = common::comp::Item::new_from_asset_expect(item.item_definition_id());
+        assert!(item.state().durability.is_some());
+        assert_eq!(item.state(), new_item.state());
+    }
+}
@@ -38,6 +38,11 @@ enum CharacterLoaderRequestKind {
 pub enum CharacterLoaderResponseType {
     CharacterList(CharacterListResult),
     CharacterData(Box<CharacterDataResult>),
+    /// The updated character list after deleting the given character
+    CharacterDeleted {
+        character_id: CharacterId,
+        result: CharacterListResult,
+    },
 }
 
 /// Common message format dispatched in response to an update request
@@ -93,10 +98,11 @@ impl CharacterLoader {
                         CharacterLoaderRequestKind::DeleteCharacter {
                             player_uuid,
                             character_id,
-                        } => {
-                            CharacterLoaderResponseType::CharacterList(conn.transaction(|txn| {
+                        } => CharacterLoaderResponseType::CharacterDeleted {
+                            character_id,
+                            result: conn.transaction(|txn| {
                                 delete_character(&player_uuid, character_id, txn)
-                            }))
+                            }),
                         },
                         CharacterLoaderRequestKind::LoadCharacterList { player_uuid } => {
                             CharacterLoaderResponseType::CharacterList(
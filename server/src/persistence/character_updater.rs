@@ -1,34 +1,40 @@
 use crate::comp;
 use common::{character::CharacterId, comp::item::ItemId};
 
-use crate::persistence::{establish_connection, VelorenConnection};
+use crate::persistence::{
+    establish_connection,
+    storage_updater::{self, StorageUpdate},
+    VelorenConnection,
+};
 use crossbeam::channel;
 use std::{path::Path, sync::Arc};
 use tracing::{error, trace};
 
 pub type CharacterUpdateData = (comp::Stats, comp::Inventory, comp::Loadout);
 
+/// Changes which are written to the DB in a single transaction
+type UpdateBatch = (Vec<(CharacterId, CharacterUpdateData)>, Vec<StorageUpdate>);
+
 /// A unidirectional messaging resource for saving characters in a
 /// background thread.
 ///
 /// This is used to make updates to a character and their persisted components,
 /// such as inventory, loadout, etc...
 pub struct CharacterUpdater {
-    update_tx: Option<channel::Sender<Vec<(CharacterId, CharacterUpdateData)>>>,
+    update_tx: Option<channel::Sender<UpdateBatch>>,
     handle: Option<std::thread::JoinHandle<()>>,
 }
 
 impl CharacterUpdater {
     pub fn new(db_dir: &Path) -> diesel::QueryResult<Self> {
-        let (update_tx, update_rx) =
-            channel::unbounded::<Vec<(CharacterId, CharacterUpdateData)>>();
+        let (update_tx, update_rx) = channel::unbounded::<UpdateBatch>();
 
         let mut conn = establish_connection(db_dir)?;
 
         let handle = std::thread::spawn(move || {
-            while let Ok(updates) = update_rx.recv() {
+            while let Ok((updates, storage_updates)) = update_rx.recv() {
                 trace!("Persistence batch update starting");
-                execute_batch_update(updates, &mut conn);
+                execute_batch_update(updates, storage_updates, &mut conn);
                 trace!("Persistence batch update finished");
             }
         });
@@ -50,6 +56,37 @@ impl CharacterUpdater {
                 &'a comp::Loadout,
             ),
         >,
+    ) {
+        self.send(updates, Vec::new());
+    }
+
+    /// Updates storage containers together with the character that changed
+    /// them, if any, so that the items moved between them are never saved in
+    /// both places or in neither
+    pub fn update_with_storage<'a>(
+        &self,
+        character: Option<(
+            CharacterId,
+            &'a comp::Stats,
+            &'a comp::Inventory,
+            &'a comp::Loadout,
+        )>,
+        storage_updates: Vec<StorageUpdate>,
+    ) {
+        self.send(character.into_iter(), storage_updates);
+    }
+
+    fn send<'a>(
+        &self,
+        updates: impl Iterator<
+            Item = (
+                CharacterId,
+                &'a comp::Stats,
+                &'a comp::Inventory,
+                &'a comp::Loadout,
+            ),
+        >,
+        storage_updates: Vec<StorageUpdate>,
     ) {
         let updates = updates
             .map(|(character_id, stats, inventory, loadout)| {
@@ -60,7 +97,12 @@ impl CharacterUpdater {
             })
             .collect::<Vec<(CharacterId, (comp::Stats, comp::Inventory, comp::Loadout))>>();
 
-        if let Err(e) = self.update_tx.as_ref().unwrap().send(updates) {
+        if let Err(e) = self
+            .update_tx
+            .as_ref()
+            .unwrap()
+            .send((updates, storage_updates))
+        {
             error!(?e, "Could not send stats updates");
         }
     }
@@ -79,11 +121,16 @@ impl CharacterUpdater {
 
 fn execute_batch_update(
     updates: Vec<(CharacterId, CharacterUpdateData)>,
+    storage_updates: Vec<StorageUpdate>,
     connection: &mut VelorenConnection,
 ) {
     let mut inserted_items = Vec::<Arc<ItemId>>::new();
 
     if let Err(e) = connection.transaction::<_, super::error::Error, _>(|txn| {
+        for update in &storage_updates {
+            storage_updater::execute_update(update, txn)?;
+        }
+
         for (character_id, (stats, inventory, loadout)) in updates {
             inserted_items.append(&mut super::character::update(
                 character_id,
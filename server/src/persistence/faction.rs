@@ -0,0 +1,131 @@
+//! Database operations related to player factions
+//!
+//! Like the character operations, these are private to the persistence module.
+//! Faction data is loaded once at startup, after which changes are sent to the
+//! [`FactionUpdater`](super::faction_updater::FactionUpdater) to be written in
+//! a background thread.
+extern crate diesel;
+
+use super::{error::Error, faction_updater::FactionData, models::*, schema, VelorenTransaction};
+use common::character::CharacterId;
+use diesel::prelude::*;
+use hashbrown::HashMap;
+use tracing::trace;
+
+/// Load every faction along with its members and their character names
+pub fn load_factions(connection: VelorenTransaction) -> Result<Vec<FactionData>, Error> {
+    use schema::{faction::dsl::*, faction_member::dsl::*};
+
+    let factions = faction.load::<Faction>(&*connection)?;
+    let mut members = faction_member.load::<FactionMember>(&*connection)?;
+    let character_names = schema::character::table
+        .select((schema::character::character_id, schema::character::alias))
+        .load::<(CharacterId, String)>(&*connection)?
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+    Ok(factions
+        .into_iter()
+        .map(|f| {
+            let faction_members = members
+                .drain_filter(|m| m.faction_name == f.name)
+                .map(|m| {
+                    let name = character_names
+                        .get(&m.character_id)
+                        .cloned()
+                        .unwrap_or_default();
+                    (m.character_id, m.rank, name)
+                })
+                .collect();
+            (f.name, faction_members)
+        })
+        .collect())
+}
+
+pub fn create_faction(
+    faction_name_: &str,
+    leader: CharacterId,
+    leader_rank: &str,
+    connection: VelorenTransaction,
+) -> Result<(), Error> {
+    use schema::faction::dsl::*;
+
+    let faction_count = diesel::insert_into(faction)
+        .values(&Faction {
+            name: faction_name_.to_owned(),
+        })
+        .execute(&*connection)?;
+
+    if faction_count != 1 {
+        return Err(Error::OtherError(format!(
+            "Error inserting into faction table for faction {}",
+            faction_name_
+        )));
+    }
+
+    set_member(faction_name_, leader, leader_rank, connection)
+}
+
+/// Insert a faction member, or update their faction and rank if they already
+/// have an entry
+pub fn set_member(
+    faction_name_: &str,
+    character_id_: CharacterId,
+    rank_: &str,
+    connection: VelorenTransaction,
+) -> Result<(), Error> {
+    use schema::faction_member::dsl::*;
+
+    let member_count = diesel::replace_into(faction_member)
+        .values(&FactionMember {
+            character_id: character_id_,
+            faction_name: faction_name_.to_owned(),
+            rank: rank_.to_owned(),
+        })
+        .execute(&*connection)?;
+
+    if member_count != 1 {
+        return Err(Error::OtherError(format!(
+            "Error updating faction_member table for char_id {}",
+            character_id_
+        )));
+    }
+
+    Ok(())
+}
+
+pub fn remove_member(
+    character_id_: CharacterId,
+    connection: VelorenTransaction,
+) -> Result<(), Error> {
+    use schema::faction_member::dsl::*;
+
+    let delete_count = diesel::delete(faction_member.filter(character_id.eq(character_id_)))
+        .execute(&*connection)?;
+    trace!(
+        "Deleted {} faction memberships for char_id {}",
+        delete_count,
+        character_id_
+    );
+
+    Ok(())
+}
+
+/// Delete a faction and any members still assigned to it
+pub fn disband_faction(faction_name_: &str, connection: VelorenTransaction) -> Result<(), Error> {
+    use schema::{faction::dsl::*, faction_member::dsl::*};
+
+    diesel::delete(faction_member.filter(faction_name.eq(faction_name_))).execute(&*connection)?;
+
+    let faction_count =
+        diesel::delete(faction.filter(name.eq(faction_name_))).execute(&*connection)?;
+
+    if faction_count != 1 {
+        return Err(Error::OtherError(format!(
+            "Error deleting from faction table for faction {}",
+            faction_name_
+        )));
+    }
+
+    Ok(())
+}
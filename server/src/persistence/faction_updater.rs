@@ -0,0 +1,106 @@
+use crate::persistence::{
+    error::Error,
+    establish_connection,
+    faction::{create_faction, disband_faction, load_factions, remove_member, set_member},
+    VelorenConnection,
+};
+use common::character::CharacterId;
+use crossbeam::channel;
+use std::path::Path;
+use tracing::{error, trace};
+
+/// A faction name with its `(character_id, rank, character name)` members, as
+/// stored in the DB
+pub type FactionData = (String, Vec<(CharacterId, String, String)>);
+
+/// A change to a faction which should be written to the DB
+#[derive(Debug)]
+pub enum FactionUpdate {
+    Create {
+        name: String,
+        leader: CharacterId,
+        leader_rank: String,
+    },
+    SetMember {
+        faction: String,
+        character_id: CharacterId,
+        rank: String,
+    },
+    RemoveMember {
+        character_id: CharacterId,
+    },
+    Disband {
+        name: String,
+    },
+}
+
+/// A unidirectional messaging resource for saving faction changes in a
+/// background thread.
+pub struct FactionUpdater {
+    update_tx: Option<channel::Sender<FactionUpdate>>,
+    handle: Option<std::thread::JoinHandle<()>>,
+}
+
+impl FactionUpdater {
+    pub fn new(db_dir: &Path) -> diesel::QueryResult<Self> {
+        let (update_tx, update_rx) = channel::unbounded::<FactionUpdate>();
+
+        let mut conn = establish_connection(db_dir)?;
+
+        let handle = std::thread::spawn(move || {
+            while let Ok(update) = update_rx.recv() {
+                trace!(?update, "Persistence faction update starting");
+                execute_update(update, &mut conn);
+            }
+        });
+
+        Ok(Self {
+            update_tx: Some(update_tx),
+            handle: Some(handle),
+        })
+    }
+
+    pub fn update(&self, update: FactionUpdate) {
+        if let Err(e) = self.update_tx.as_ref().unwrap().send(update) {
+            error!(?e, "Could not send faction update");
+        }
+    }
+}
+
+/// Loads all factions and their members. This is a blocking call, intended to
+/// be used once during server startup. A failure is returned rather than
+/// starting without factions, which would let new factions collide with the
+/// ones in the DB.
+pub fn load_all_factions(db_dir: &Path) -> Result<Vec<FactionData>, Error> {
+    let mut conn = establish_connection(db_dir)?;
+
+    conn.transaction::<_, Error, _>(load_factions)
+}
+
+fn execute_update(update: FactionUpdate, connection: &mut VelorenConnection) {
+    if let Err(e) = connection.transaction::<_, Error, _>(|txn| match &update {
+        FactionUpdate::Create {
+            name,
+            leader,
+            leader_rank,
+        } => create_faction(name, *leader, leader_rank, txn),
+        FactionUpdate::SetMember {
+            faction,
+            character_id,
+            rank,
+        } => set_member(faction, *character_id, rank, txn),
+        FactionUpdate::RemoveMember { character_id } => remove_member(*character_id, txn),
+        FactionUpdate::Disband { name } => disband_faction(name, txn),
+    }) {
+        error!(?e, ?update, "Error during faction update transaction");
+    }
+}
+
+impl Drop for FactionUpdater {
+    fn drop(&mut self) {
+        drop(self.update_tx.take());
+        if let Err(e) = self.handle.take().unwrap().join() {
+            error!(?e, "Error from joining faction update thread");
+        }
+    }
+}
@@ -10,9 +10,15 @@ pub(in crate::persistence) mod character;
 pub mod character_loader;
 pub mod character_updater;
 mod error;
+pub(in crate::persistence) mod faction;
+pub mod faction_updater;
 mod json_models;
 mod models;
 mod schema;
+pub(in crate::persistence) mod storage;
+pub mod storage_updater;
+
+pub use error::Error as PersistenceError;
 
 use common::comp;
 use diesel::{connection::SimpleConnection, prelude::*};
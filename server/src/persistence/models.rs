@@ -1,6 +1,6 @@
 extern crate serde_json;
 
-use super::schema::{body, character, entity, item, stats};
+use super::schema::{body, character, entity, faction, faction_member, item, stats};
 
 #[derive(Debug, Insertable, PartialEq)]
 #[table_name = "entity"]
@@ -34,6 +34,8 @@ pub struct Item {
     pub item_definition_id: String,
     pub stack_size: i32,
     pub position: String,
+    /// Per-instance item state serialized as JSON, empty for the default state
+    pub properties: String,
 }
 
 #[derive(Associations, AsChangeset, Identifiable, Queryable, Debug, Insertable)]
@@ -46,6 +48,7 @@ pub struct Stats {
     pub endurance: i32,
     pub fitness: i32,
     pub willpower: i32,
+    pub skill_set: String,
 }
 
 #[derive(Associations, Identifiable, Insertable, Queryable, Debug)]
@@ -56,3 +59,19 @@ pub struct Body {
     pub variant: String,
     pub body_data: String,
 }
+
+#[derive(Identifiable, Insertable, Queryable, Debug)]
+#[primary_key(name)]
+#[table_name = "faction"]
+pub struct Faction {
+    pub name: String,
+}
+
+#[derive(Identifiable, Insertable, Queryable, AsChangeset, Debug)]
+#[primary_key(character_id)]
+#[table_name = "faction_member"]
+pub struct FactionMember {
+    pub character_id: i64,
+    pub faction_name: String,
+    pub rank: String,
+}
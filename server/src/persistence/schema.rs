@@ -20,6 +20,20 @@ table! {
     }
 }
 
+table! {
+    faction (name) {
+        name -> Text,
+    }
+}
+
+table! {
+    faction_member (character_id) {
+        character_id -> BigInt,
+        faction_name -> Text,
+        rank -> Text,
+    }
+}
+
 table! {
     item (item_id) {
         item_id -> BigInt,
@@ -27,6 +41,7 @@ table! {
         item_definition_id -> Text,
         stack_size -> Integer,
         position -> Text,
+        properties -> Text,
     }
 }
 
@@ -38,10 +53,20 @@ table! {
         endurance -> Integer,
         fitness -> Integer,
         willpower -> Integer,
+        skill_set -> Text,
     }
 }
 
 joinable!(character -> body (character_id));
 joinable!(character -> stats (character_id));
+joinable!(faction_member -> faction (faction_name));
 
-allow_tables_to_appear_in_same_query!(body, character, entity, item, stats,);
+allow_tables_to_appear_in_same_query!(
+    body,
+    character,
+    entity,
+    faction,
+    faction_member,
+    item,
+    stats,
+);
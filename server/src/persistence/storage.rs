@@ -0,0 +1,178 @@
+//! Database operations related to storage containers placed in the world
+//!
+//! Like the character operations, these are private to the persistence module.
+//! Containers are loaded once at startup, after which changes are sent to the
+//! [`CharacterUpdater`](super::character_updater::CharacterUpdater) to be
+//! written in a background thread, together with the character that made them.
+//!
+//! Each container is a pseudo container item in the world pseudo container,
+//! positioned by its block position, and its contents are the items inside it.
+extern crate diesel;
+
+use super::{
+    character::{
+        conversions::{convert_inventory_from_database_items, convert_storage_to_database_items},
+        get_new_entity_ids, EntityId, WORLD_PSEUDO_CONTAINER_ID,
+    },
+    error::Error,
+    models::*,
+    schema, VelorenTransaction,
+};
+use common::comp::Inventory;
+use diesel::prelude::*;
+use tracing::{trace, warn};
+use vek::*;
+
+const STORAGE_PSEUDO_CONTAINER_DEF_ID: &str = "veloren.core.pseudo_containers.storage";
+
+fn convert_pos_to_database(pos: Vec3<i32>) -> String { format!("{},{},{}", pos.x, pos.y, pos.z) }
+
+fn convert_pos_from_database(position_: &str) -> Result<Vec3<i32>, Error> {
+    let coords = position_
+        .split(',')
+        .map(|coord| coord.parse::<i32>().ok())
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default();
+    match coords.as_slice() {
+        [x, y, z] => Ok(Vec3::new(*x, *y, *z)),
+        _ => Err(Error::ConversionError(format!(
+            "Failed to parse storage container position: {}",
+            position_
+        ))),
+    }
+}
+
+/// Load every storage container along with its contents, as an inventory of
+/// `size` slots
+pub fn load_storage(
+    size: usize,
+    connection: VelorenTransaction,
+) -> Result<Vec<(Vec3<i32>, Inventory)>, Error> {
+    use schema::item::dsl::*;
+
+    let containers = item
+        .filter(
+            parent_container_item_id
+                .eq(WORLD_PSEUDO_CONTAINER_ID)
+                .and(item_definition_id.eq(STORAGE_PSEUDO_CONTAINER_DEF_ID)),
+        )
+        .load::<Item>(&*connection)?;
+
+    let mut storage = Vec::with_capacity(containers.len());
+    for container in containers {
+        let contents = item
+            .filter(parent_container_item_id.eq(container.item_id))
+            .load::<Item>(&*connection)?;
+        match convert_pos_from_database(&container.position).and_then(|pos| {
+            Ok((
+                pos,
+                convert_inventory_from_database_items(&contents, Inventory::new_with_size(size))?,
+            ))
+        }) {
+            Ok(loaded) => storage.push(loaded),
+            // A single broken container shouldn't stop the server from starting
+            Err(e) => warn!(
+                ?e,
+                container_id = container.item_id,
+                "Skipping invalid storage container"
+            ),
+        }
+    }
+
+    Ok(storage)
+}
+
+fn get_container_id(
+    pos: Vec3<i32>,
+    connection: VelorenTransaction,
+) -> Result<Option<EntityId>, Error> {
+    use schema::item::dsl::*;
+
+    Ok(item
+        .select(item_id)
+        .filter(
+            parent_container_item_id
+                .eq(WORLD_PSEUDO_CONTAINER_ID)
+                .and(position.eq(convert_pos_to_database(pos))),
+        )
+        .first::<EntityId>(&*connection)
+        .optional()?)
+}
+
+/// Replace the stored contents of the container at `pos`, creating the
+/// container if it doesn't exist yet
+pub fn save_container(
+    pos: Vec3<i32>,
+    inventory: &Inventory,
+    connection: VelorenTransaction,
+) -> Result<(), Error> {
+    use schema::item::dsl::*;
+
+    let container_id = match get_container_id(pos, connection)? {
+        Some(container_id) => container_id,
+        None => {
+            let container_id = get_new_entity_ids(connection, |next_id| next_id + 1)?
+                .next()
+                .unwrap();
+            let container_count = diesel::insert_into(item)
+                .values(&Item {
+                    item_id: container_id,
+                    parent_container_item_id: WORLD_PSEUDO_CONTAINER_ID,
+                    item_definition_id: STORAGE_PSEUDO_CONTAINER_DEF_ID.to_owned(),
+                    stack_size: 1,
+                    position: convert_pos_to_database(pos),
+                    properties: String::new(),
+                })
+                .execute(&*connection)?;
+
+            if container_count != 1 {
+                return Err(Error::OtherError(format!(
+                    "Error inserting storage container at {}",
+                    pos
+                )));
+            }
+            container_id
+        },
+    };
+
+    let delete_count = diesel::delete(item.filter(parent_container_item_id.eq(container_id)))
+        .execute(&*connection)?;
+    trace!(
+        "Deleted {} items from storage container at {}",
+        delete_count,
+        pos
+    );
+
+    let item_count = inventory.count() as i64;
+    if item_count > 0 {
+        let new_ids = get_new_entity_ids(connection, |next_id| next_id + item_count)?;
+        let contents = convert_storage_to_database_items(inventory, container_id, new_ids.start);
+        let insert_count = diesel::insert_into(item)
+            .values(&contents)
+            .execute(&*connection)?;
+
+        if insert_count != contents.len() {
+            return Err(Error::OtherError(format!(
+                "Expected insertions={}, actual={}, for storage container at {}",
+                contents.len(),
+                insert_count,
+                pos
+            )));
+        }
+    }
+
+    Ok(())
+}
+
+/// Delete the container at `pos` along with anything still inside it
+pub fn remove_container(pos: Vec3<i32>, connection: VelorenTransaction) -> Result<(), Error> {
+    use schema::item::dsl::*;
+
+    if let Some(container_id) = get_container_id(pos, connection)? {
+        diesel::delete(item.filter(parent_container_item_id.eq(container_id)))
+            .execute(&*connection)?;
+        diesel::delete(item.filter(item_id.eq(container_id))).execute(&*connection)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use crate::persistence::{
+    error::Error,
+    establish_connection,
+    storage::{load_storage, remove_container, save_container},
+    VelorenTransaction,
+};
+use common::comp::Inventory;
+use std::path::Path;
+use vek::*;
+
+/// A change to a storage container which should be written to the DB.
+///
+/// Changing a container always moves items from or to a character, so these
+/// are written by the
+/// [`CharacterUpdater`](super::character_updater::CharacterUpdater) in the same
+/// transaction as that character. Otherwise a crash between the two writes
+/// could duplicate or lose the items that were moved.
+#[derive(Debug)]
+pub enum StorageUpdate {
+    Save {
+        pos: Vec3<i32>,
+        inventory: Inventory,
+    },
+    Remove {
+        pos: Vec3<i32>,
+    },
+}
+
+/// Loads all storage containers and their contents, as inventories of `size`
+/// slots. This is a blocking call, intended to be used once during server
+/// startup. A failure is returned rather than starting without containers,
+/// which would make their contents unreachable.
+pub fn load_all_storage(db_dir: &Path, size: usize) -> Result<Vec<(Vec3<i32>, Inventory)>, Error> {
+    let mut conn = establish_connection(db_dir)?;
+
+    conn.transaction::<_, Error, _>(|txn| load_storage(size, txn))
+}
+
+pub(super) fn execute_update(update: &StorageUpdate, txn: VelorenTransaction) -> Result<(), Error> {
+    match update {
+        StorageUpdate::Save { pos, inventory } => save_container(*pos, inventory, txn),
+        StorageUpdate::Remove { pos } => remove_container(*pos, txn),
+    }
+}
@@ -3,6 +3,7 @@ mod editable;
 pub use editable::EditableSetting;
 
 use authc::Uuid;
+use common::comp::PvpMode;
 use hashbrown::{HashMap, HashSet};
 use portpicker::pick_unused_port;
 use serde::{Deserialize, Serialize};
@@ -14,7 +15,7 @@ use std::{
     time::Duration,
 };
 use tracing::{error, warn};
-use world::sim::FileOpts;
+use world::sim::{FileOpts, GenOpts};
 
 const DEFAULT_WORLD_SEED: u32 = 59686;
 const CONFIG_DIR: &str = "server_config";
@@ -32,16 +33,29 @@ pub struct Settings {
     pub auth_server_address: Option<String>,
     pub max_players: usize,
     pub world_seed: u32,
-    //pub pvp_enabled: bool,
+    /// Whether players may harm each other. Under `OutsideSettlements`, towns
+    /// are safe areas.
+    pub pvp_mode: PvpMode,
     pub server_name: String,
     pub start_time: f64,
     /// When set to None, loads the default map file (if available); otherwise,
     /// uses the value of the file options to decide how to proceed.
     pub map_file: Option<FileOpts>,
+    /// Size, sea level and continent scale used when generating a new map
+    /// (i.e. when `map_file` is `Generate` or `Save`).
+    pub world_gen: GenOpts,
     pub max_view_distance: Option<u32>,
     pub banned_words_files: Vec<PathBuf>,
     pub max_player_group_size: u32,
     pub client_timeout: Duration,
+    /// Additional asset directories which override or extend the base assets,
+    /// in order of increasing priority. Relative paths are resolved against
+    /// the server's data directory.
+    pub asset_overlays: Vec<PathBuf>,
+    /// File every damage and heal is appended to as a line of JSON, for
+    /// balancing. Relative paths are resolved against the server's data
+    /// directory.
+    pub damage_log_file: Option<PathBuf>,
 }
 
 impl Default for Settings {
@@ -51,14 +65,18 @@ impl Default for Settings {
             metrics_address: SocketAddr::from(([0; 4], 14005)),
             auth_server_address: Some("https://auth.veloren.net".into()),
             world_seed: DEFAULT_WORLD_SEED,
+            pvp_mode: PvpMode::On,
             server_name: "Veloren Alpha".into(),
             max_players: 100,
             start_time: 9.0 * 3600.0,
             map_file: None,
+            world_gen: GenOpts::default(),
             max_view_distance: Some(30),
             banned_words_files: Vec::new(),
             max_player_group_size: 6,
             client_timeout: Duration::from_secs(40),
+            asset_overlays: Vec::new(),
+            damage_log_file: None,
         }
     }
 }
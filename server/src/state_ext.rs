@@ -1,5 +1,6 @@
 use crate::{
-    client::Client, persistence::PersistedComponents, sys::sentinel::DeletedEntities, SpawnPoint,
+    client::Client, faction::FactionManager, persistence::PersistedComponents,
+    sys::sentinel::DeletedEntities, SpawnPoint,
 };
 use common::{
     character::CharacterId,
@@ -83,6 +84,19 @@ impl StateExt for State {
                     .get_mut(entity)
                     .map(|stats| stats.exp.change_by(xp));
             },
+            Effect::Buff { kind, data } => {
+                self.ecs()
+                    .write_storage::<comp::Buffs>()
+                    .get_mut(entity)
+                    .map(|buffs| {
+                        buffs.insert(comp::Buff::new(
+                            kind,
+                            data,
+                            Vec::new(),
+                            comp::BuffSource::Item,
+                        ))
+                    });
+            },
         }
     }
 
@@ -249,6 +263,22 @@ impl StateExt for State {
             self.write_component(entity, inventory);
             self.write_component(entity, loadout);
 
+            // Restore faction membership
+            let faction = self
+                .ecs()
+                .read_storage::<comp::Player>()
+                .get(entity)
+                .and_then(|player| player.character_id)
+                .and_then(|character_id| {
+                    self.ecs()
+                        .read_resource::<FactionManager>()
+                        .faction_of(character_id)
+                        .map(|faction| faction.to_owned())
+                });
+            if let Some(faction) = faction {
+                self.write_component(entity, comp::Faction(faction));
+            }
+
             self.write_component(
                 entity,
                 comp::InventoryUpdate::new(comp::InventoryUpdateEvent::default()),
@@ -0,0 +1,87 @@
+//! Storage containers placed by players
+//!
+//! Like crops, placed containers are not part of the generated terrain, so the
+//! [`StorageContainers`] resource keeps track of them and restores their
+//! blocks whenever their chunk is loaded again. Every change to a container
+//! returns a [`StorageUpdate`], which the caller has to write to the DB along
+//! with the character that made the change.
+
+use crate::persistence::{
+    storage_updater::{load_all_storage, StorageUpdate},
+    PersistenceError,
+};
+use common::{
+    comp::Inventory,
+    terrain::{Block, SpriteKind, TerrainChunk, TerrainGrid},
+    vol::WriteVol,
+};
+use hashbrown::HashMap;
+use std::path::Path;
+use vek::*;
+
+/// Number of item slots in a placed container
+pub const CONTAINER_SLOTS: usize = 18;
+
+#[derive(Default)]
+pub struct StorageContainers {
+    containers: HashMap<Vec3<i32>, Inventory>,
+}
+
+impl StorageContainers {
+    /// Load all containers from the DB
+    pub fn new(db_dir: &Path) -> Result<Self, PersistenceError> {
+        Ok(Self {
+            containers: load_all_storage(db_dir, CONTAINER_SLOTS)?
+                .into_iter()
+                .collect(),
+        })
+    }
+
+    /// Start tracking a newly placed, empty container
+    #[must_use]
+    pub fn place(&mut self, pos: Vec3<i32>) -> StorageUpdate {
+        let inventory = Inventory::new_with_size(CONTAINER_SLOTS);
+        self.containers.insert(pos, inventory.clone());
+        StorageUpdate::Save { pos, inventory }
+    }
+
+    pub fn get(&self, pos: Vec3<i32>) -> Option<&Inventory> { self.containers.get(&pos) }
+
+    /// Change the contents of the container at `pos`, if there is one
+    #[must_use]
+    pub fn modify<R>(
+        &mut self,
+        pos: Vec3<i32>,
+        f: impl FnOnce(&mut Inventory) -> R,
+    ) -> Option<(R, StorageUpdate)> {
+        let inventory = self.containers.get_mut(&pos)?;
+        let result = f(inventory);
+        let update = StorageUpdate::Save {
+            pos,
+            inventory: inventory.clone(),
+        };
+        Some((result, update))
+    }
+
+    /// Stop tracking the container at `pos`, returning its contents
+    #[must_use]
+    pub fn remove(&mut self, pos: Vec3<i32>) -> Option<(Inventory, StorageUpdate)> {
+        let inventory = self.containers.remove(&pos)?;
+        Some((inventory, StorageUpdate::Remove { pos }))
+    }
+
+    /// Write the containers that were placed in a chunk back into it after it
+    /// has been regenerated.
+    pub fn restore(&self, key: Vec2<i32>, chunk: &mut TerrainChunk) {
+        for wpos in self
+            .containers
+            .keys()
+            .filter(|wpos| TerrainGrid::chunk_key(**wpos) == key)
+        {
+            let _ = chunk.set(
+                TerrainGrid::chunk_offs(*wpos),
+                Block::air(SpriteKind::StorageChest),
+            );
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use super::SysTimer;
+use crate::crafting::PendingCrafts;
+use common::{
+    event::{EventBus, ServerEvent},
+    span,
+    state::Time,
+};
+use specs::{Entities, Read, System, Write};
+
+/// This system finishes crafts once their crafting time has passed, and
+/// forgets about crafts of entities that no longer exist
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    #[allow(clippy::type_complexity)] // TODO: Pending review in #587
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, Time>,
+        Read<'a, EventBus<ServerEvent>>,
+        Write<'a, PendingCrafts>,
+        Write<'a, SysTimer<Self>>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, time, server_event_bus, mut pending_crafts, mut timer): Self::SystemData,
+    ) {
+        span!(_guard, "run", "crafting::Sys::run");
+        timer.start();
+
+        let mut server_emitter = server_event_bus.emitter();
+        pending_crafts.retain(|entity, craft| {
+            if !entities.is_alive(*entity) {
+                false
+            } else if craft.finish_time <= time.0 {
+                server_emitter.emit(ServerEvent::FinishCraft {
+                    entity: *entity,
+                    recipe: std::mem::take(&mut craft.recipe),
+                });
+                false
+            } else {
+                true
+            }
+        });
+
+        timer.end()
+    }
+}
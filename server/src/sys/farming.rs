@@ -0,0 +1,57 @@
+use super::SysTimer;
+use crate::{farming::Crops, Tick};
+use common::{
+    span,
+    state::{BlockChange, TimeOfDay},
+    terrain::TerrainGrid,
+    vol::ReadVol,
+};
+use specs::{Read, ReadExpect, System, Write};
+
+/// How many ticks to wait between crop growth updates
+const CROP_UPDATE_INTERVAL: u64 = 30;
+
+/// This system advances planted crops through their growth stages, and forgets
+/// about crops whose block has been harvested or destroyed
+pub struct Sys;
+impl<'a> System<'a> for Sys {
+    #[allow(clippy::type_complexity)] // TODO: Pending review in #587
+    type SystemData = (
+        Read<'a, Tick>,
+        Read<'a, TimeOfDay>,
+        ReadExpect<'a, TerrainGrid>,
+        Write<'a, BlockChange>,
+        Write<'a, Crops>,
+        Write<'a, SysTimer<Self>>,
+    );
+
+    fn run(
+        &mut self,
+        (tick, time_of_day, terrain, mut block_change, mut crops, mut timer): Self::SystemData,
+    ) {
+        span!(_guard, "run", "farming::Sys::run");
+        timer.start();
+
+        if tick.0 % CROP_UPDATE_INTERVAL == 0 {
+            crops.retain(|pos, crop| {
+                let block = match terrain.get(*pos) {
+                    Ok(block) => *block,
+                    // The chunk isn't loaded, the crop will be restored once it is
+                    Err(_) => return true,
+                };
+                match block.get_sprite() {
+                    Some(sprite) if crop.kind.stages().contains(&sprite) => {
+                        let stage = crop.stage(time_of_day.0);
+                        if stage != sprite {
+                            block_change.try_set(*pos, block.with_sprite(stage));
+                        }
+                        true
+                    },
+                    _ => false,
+                }
+            });
+        }
+
+        timer.end()
+    }
+}
@@ -3,6 +3,7 @@ use crate::{
     alias_validator::AliasValidator,
     character_creator,
     client::Client,
+    custom_assets::CustomAssets,
     login_provider::LoginProvider,
     metrics::{NetworkRequestMetrics, PlayerMetrics},
     persistence::character_loader::CharacterLoader,
@@ -44,6 +45,7 @@ impl Sys {
         player_metrics: &ReadExpect<'_, PlayerMetrics>,
         uids: &ReadStorage<'_, Uid>,
         chat_modes: &ReadStorage<'_, ChatMode>,
+        custom_assets: &ReadExpect<'_, CustomAssets>,
         msg: ClientGeneral,
     ) -> Result<(), crate::error::Error> {
         match msg {
@@ -78,6 +80,11 @@ impl Sys {
                     .inc();
                 server_emitter.emit(ServerEvent::ClientDisconnect(entity));
             },
+            ClientGeneral::RequestAssets(paths) => {
+                if client.registered {
+                    client.send_msg(ServerGeneral::CustomAssets(custom_assets.get(&paths)));
+                }
+            },
             _ => unreachable!("not a client_general msg"),
         }
         Ok(())
@@ -445,6 +452,7 @@ impl Sys {
         settings: &Read<'_, Settings>,
         editable_settings: &ReadExpect<'_, EditableSettings>,
         alias_validator: &ReadExpect<'_, AliasValidator>,
+        custom_assets: &ReadExpect<'_, CustomAssets>,
     ) -> Result<(), crate::error::Error> {
         let (mut b1, mut b2, mut b3, mut b4, mut b5) = (
             client.network_error,
@@ -483,6 +491,7 @@ impl Sys {
                     player_metrics,
                     uids,
                     chat_modes,
+                    custom_assets,
                     msg?,
                 )?;
             }
@@ -575,6 +584,7 @@ impl<'a> System<'a> for Sys {
         Read<'a, Settings>,
         ReadExpect<'a, EditableSettings>,
         ReadExpect<'a, AliasValidator>,
+        ReadExpect<'a, CustomAssets>,
     );
 
     #[allow(clippy::match_ref_pats)] // TODO: Pending review in #587
@@ -608,6 +618,7 @@ impl<'a> System<'a> for Sys {
             settings,
             editable_settings,
             alias_validator,
+            custom_assets,
         ): Self::SystemData,
     ) {
         span!(_guard, "run", "message::Sys::run");
@@ -668,6 +679,7 @@ impl<'a> System<'a> for Sys {
                     &settings,
                     &editable_settings,
                     &alias_validator,
+                    &custom_assets,
                 );
                 select!(
                     _ = Delay::new(std::time::Duration::from_micros(20)).fuse() => Ok(()),
@@ -1,4 +1,6 @@
+pub mod crafting;
 pub mod entity_sync;
+pub mod farming;
 pub mod invite_timeout;
 pub mod message;
 pub mod object;
@@ -15,7 +17,9 @@ use std::{
     time::{Duration, Instant},
 };
 
+pub type CraftingTimer = SysTimer<crafting::Sys>;
 pub type EntitySyncTimer = SysTimer<entity_sync::Sys>;
+pub type FarmingTimer = SysTimer<farming::Sys>;
 pub type MessageTimer = SysTimer<message::Sys>;
 pub type SentinelTimer = SysTimer<sentinel::Sys>;
 pub type SubscriptionTimer = SysTimer<subscription::Sys>;
@@ -37,6 +41,8 @@ const WAYPOINT_SYS: &str = "server_waypoint_sys";
 const INVITE_TIMEOUT_SYS: &str = "server_invite_timeout_sys";
 const PERSISTENCE_SYS: &str = "server_persistence_sys";
 const OBJECT_SYS: &str = "server_object_sys";
+const FARMING_SYS: &str = "server_farming_sys";
+const CRAFTING_SYS: &str = "server_crafting_sys";
 
 pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch_builder.add(terrain::Sys, TERRAIN_SYS, &[]);
@@ -44,6 +50,8 @@ pub fn add_server_systems(dispatch_builder: &mut DispatcherBuilder) {
     dispatch_builder.add(invite_timeout::Sys, INVITE_TIMEOUT_SYS, &[]);
     dispatch_builder.add(persistence::Sys, PERSISTENCE_SYS, &[]);
     dispatch_builder.add(object::Sys, OBJECT_SYS, &[]);
+    dispatch_builder.add(farming::Sys, FARMING_SYS, &[]);
+    dispatch_builder.add(crafting::Sys, CRAFTING_SYS, &[]);
 }
 
 pub fn run_sync_systems(ecs: &mut specs::World) {
@@ -55,6 +55,7 @@ impl<'a> System<'a> for Sys {
                                 min_heal: 0,
                                 terrain_destruction_power: 4.0,
                                 energy_regen: 0,
+                                element: None,
                             },
                             owner: *owner,
                             friendly_damage: true,
@@ -78,6 +79,7 @@ impl<'a> System<'a> for Sys {
                                 min_heal: 0,
                                 terrain_destruction_power: 4.0,
                                 energy_regen: 0,
+                                element: None,
                             },
                             owner: *owner,
                             friendly_damage: true,
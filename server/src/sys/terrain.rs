@@ -1,5 +1,8 @@
 use super::SysTimer;
-use crate::{chunk_generator::ChunkGenerator, client::Client, Tick};
+use crate::{
+    chunk_generator::ChunkGenerator, client::Client, farming::Crops, storage::StorageContainers,
+    Tick,
+};
 use common::{
     comp::{self, bird_medium, Alignment, Player, Pos},
     event::{EventBus, ServerEvent},
@@ -7,7 +10,7 @@ use common::{
     msg::ServerGeneral,
     npc::NPC_NAMES,
     span,
-    state::TerrainChanges,
+    state::{TerrainChanges, TimeOfDay},
     terrain::TerrainGrid,
     LoadoutBuilder,
 };
@@ -28,6 +31,9 @@ impl<'a> System<'a> for Sys {
     type SystemData = (
         Read<'a, EventBus<ServerEvent>>,
         Read<'a, Tick>,
+        Read<'a, TimeOfDay>,
+        Read<'a, Crops>,
+        Read<'a, StorageContainers>,
         Write<'a, SysTimer<Self>>,
         WriteExpect<'a, ChunkGenerator>,
         WriteExpect<'a, TerrainGrid>,
@@ -42,6 +48,9 @@ impl<'a> System<'a> for Sys {
         (
             server_event_bus,
             tick,
+            time_of_day,
+            crops,
+            storage,
             mut timer,
             mut chunk_generator,
             mut terrain,
@@ -59,7 +68,7 @@ impl<'a> System<'a> for Sys {
         // Fetch any generated `TerrainChunk`s and insert them into the terrain.
         // Also, send the chunk data to anybody that is close by.
         'insert_terrain_chunks: while let Some((key, res)) = chunk_generator.recv_new_chunk() {
-            let (chunk, supplement) = match res {
+            let (mut chunk, supplement) = match res {
                 Ok((chunk, supplement)) => (chunk, supplement),
                 Err(Some(entity)) => {
                     if let Some(client) = clients.get_mut(entity) {
@@ -74,6 +83,11 @@ impl<'a> System<'a> for Sys {
                     continue 'insert_terrain_chunks;
                 },
             };
+            // Put back any crops that were planted in the chunk before it was unloaded
+            crops.restore(key, &mut chunk, time_of_day.0);
+            // ... and any storage containers that were placed in it
+            storage.restore(key, &mut chunk);
+
             // Send the chunk to all nearby players.
             for (view_distance, pos, client) in (&players, &positions, &mut clients)
                 .join()
@@ -181,7 +195,12 @@ impl<'a> System<'a> for Sys {
                     stats,
                     loadout,
                     agent: if entity.has_agency {
-                        Some(comp::Agent::new(entity.pos, can_speak, &body))
+                        let agent = comp::Agent::new(entity.pos, can_speak, &body);
+                        Some(if entity.patrol_route.is_empty() {
+                            agent
+                        } else {
+                            agent.with_patrol_route(entity.patrol_route)
+                        })
                     } else {
                         None
                     },
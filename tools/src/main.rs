@@ -1,19 +1,64 @@
 #![deny(clippy::clone_on_ref_ptr)]
 
-use std::error::Error;
+use image::{Rgb, RgbImage};
+use std::{
+    error::Error,
+    fs,
+    path::{Path, PathBuf},
+};
 use structopt::StructOpt;
+use vek::*;
 
-use common::comp;
+use common::{
+    assets::{self, Asset},
+    comp,
+    loot::{LootContext, LootSite, LootTable},
+    terrain::BiomeKind,
+};
 use comp::item::{
     armor::{ArmorKind, Protection},
     tool::ToolKind,
     ItemKind,
 };
+use world::{
+    sim::{FileOpts, RiverKind, SimChunk, WorldOpts, DEFAULT_WORLD_MAP},
+    site::SiteKind,
+    World, CONFIG,
+};
 
 #[derive(StructOpt)]
 struct Cli {
-    /// Available arguments: "armor_stats", "weapon_stats", "all_items"
+    /// Available arguments: "armor_stats", "weapon_stats", "all_items",
+    /// "pack_assets", "world_map", "loot_sim"
     function: String,
+    /// Where "pack_assets" writes the asset pack
+    #[structopt(long, parse(from_os_str), default_value = "assets.pack")]
+    output: PathBuf,
+    /// Directory "world_map" writes the map layers and site index to
+    #[structopt(long, parse(from_os_str), default_value = "world_map")]
+    map_dir: PathBuf,
+    /// World seed used by "world_map"
+    #[structopt(long, default_value = "59686")]
+    seed: u32,
+    /// World file loaded by "world_map". Without it, the default map is used,
+    /// the same as on a server without a `map_file` setting.
+    #[structopt(long, parse(from_os_str))]
+    world_file: Option<PathBuf>,
+    /// Loot table rolled by "loot_sim"
+    #[structopt(long, default_value = "common.loot_tables.creature.default")]
+    table: String,
+    /// How many times "loot_sim" rolls the table
+    #[structopt(long, default_value = "10000")]
+    rolls: u32,
+    /// Level "loot_sim" rolls for
+    #[structopt(long)]
+    level: Option<u32>,
+    /// Biome "loot_sim" rolls in, e.g. "forest"
+    #[structopt(long)]
+    biome: Option<String>,
+    /// Site "loot_sim" rolls in: "settlement", "dungeon" or "castle"
+    #[structopt(long)]
+    site: Option<String>,
 }
 
 fn armor_stats() -> Result<(), Box<dyn Error>> {
@@ -82,6 +127,8 @@ fn get_tool_kind(kind: &ToolKind) -> String {
         ToolKind::Shield(_) => "Shield".to_string(),
         ToolKind::Debug(_) => "Debug".to_string(),
         ToolKind::Farming(_) => "Farming".to_string(),
+        ToolKind::Pick(_) => "Pick".to_string(),
+        ToolKind::Hatchet(_) => "Hatchet".to_string(),
         ToolKind::NpcWeapon(_) => "NpcWeapon".to_string(),
         ToolKind::Empty => "Empty".to_string(),
     }
@@ -99,6 +146,8 @@ fn get_tool_kind_kind(kind: &ToolKind) -> String {
         ToolKind::Shield(x) => x.clone(),
         ToolKind::Debug(x) => x.clone(),
         ToolKind::Farming(x) => x.clone(),
+        ToolKind::Pick(x) => x.clone(),
+        ToolKind::Hatchet(x) => x.clone(),
         ToolKind::NpcWeapon(x) => x.clone(),
         ToolKind::Empty => "".to_string(),
     }
@@ -157,6 +206,235 @@ fn all_items() -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
+fn pack_assets(output: &Path) -> Result<(), Box<dyn Error>> {
+    let assets_dir = &*assets::ASSETS_PATH;
+    if !assets_dir.is_dir() {
+        return Err(format!("{} is not an asset directory", assets_dir.display()).into());
+    }
+
+    let count = assets::pack::write_pack(assets_dir, output)?;
+    println!(
+        "Packed {} files from {} into {}",
+        count,
+        assets_dir.display(),
+        output.display()
+    );
+    Ok(())
+}
+
+fn biome_color(biome: BiomeKind) -> [u8; 3] {
+    match biome {
+        BiomeKind::Void => [0, 0, 0],
+        BiomeKind::Grassland => [110, 170, 60],
+        BiomeKind::Ocean => [30, 60, 140],
+        BiomeKind::Mountain => [120, 110, 100],
+        BiomeKind::Snowlands => [235, 240, 245],
+        BiomeKind::Desert => [220, 200, 120],
+        BiomeKind::Swamp => [70, 90, 50],
+        BiomeKind::Forest => [40, 110, 40],
+        BiomeKind::Jungle => [20, 120, 30],
+        BiomeKind::Savannah => [190, 170, 70],
+        BiomeKind::Taiga => [40, 80, 60],
+    }
+}
+
+fn site_kind_name(kind: &SiteKind) -> &'static str {
+    match kind {
+        SiteKind::Settlement(_) => "Settlement",
+        SiteKind::Dungeon(_) => "Dungeon",
+        SiteKind::Castle(_) => "Castle",
+    }
+}
+
+fn site_color(kind: &SiteKind) -> [u8; 3] {
+    match kind {
+        SiteKind::Settlement(_) => [255, 200, 60],
+        SiteKind::Dungeon(_) => [200, 40, 40],
+        SiteKind::Castle(_) => [180, 180, 255],
+    }
+}
+
+/// Writes one PNG per map layer, with one pixel per chunk and north at the
+/// top, plus a JSON index of all sites.
+fn world_map(map_dir: &Path, seed: u32, world_file: Option<PathBuf>) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(map_dir)?;
+
+    println!("Generating world...");
+    let (world, index) = World::generate(seed, WorldOpts {
+        seed_elements: true,
+        world_file: world_file.map_or_else(
+            || FileOpts::LoadAsset(DEFAULT_WORLD_MAP.into()),
+            FileOpts::Load,
+        ),
+        ..WorldOpts::default()
+    });
+    let sim = world.sim();
+    let size = sim.get_size();
+    // Image rows go from north to south, while chunk y grows to the north
+    let to_chunk_pos = |x: u32, y: u32| Vec2::new(x as i32, (size.y - 1 - y) as i32);
+
+    let write_layer = |name: &str, color: &dyn Fn(&SimChunk) -> [u8; 3]| {
+        let path = map_dir.join(name).with_extension("png");
+        RgbImage::from_fn(size.x, size.y, |x, y| {
+            Rgb(sim.get(to_chunk_pos(x, y)).map_or([0; 3], color))
+        })
+        .save(&path)?;
+        println!("Wrote {}", path.display());
+        Ok::<_, Box<dyn Error>>(())
+    };
+
+    let unit = |x: f32| (x.max(0.0).min(1.0) * 255.0) as u8;
+    write_layer("altitude", &|chunk| {
+        if chunk.alt < CONFIG.sea_level {
+            [0, 0, unit((chunk.alt / CONFIG.sea_level) * 0.5 + 0.25)]
+        } else {
+            [unit((chunk.alt - CONFIG.sea_level) / sim.max_height); 3]
+        }
+    })?;
+    write_layer("biome", &|chunk| biome_color(chunk.get_biome()))?;
+    write_layer("rivers", &|chunk| match chunk.river.river_kind {
+        Some(RiverKind::Ocean) => [30, 60, 140],
+        Some(RiverKind::Lake { .. }) => [60, 120, 220],
+        Some(RiverKind::River { .. }) => [120, 200, 255],
+        None => [0, 0, 0],
+    })?;
+    write_layer("paths", &|chunk| {
+        if chunk.path.0.is_way() {
+            [255, 255, 255]
+        } else {
+            [0, 0, 0]
+        }
+    })?;
+    // Temperature is roughly in [-1, 1] and humidity in [0, 1]
+    write_layer("temperature", &|chunk| {
+        let t = (chunk.temp + 1.0) * 0.5;
+        [unit(t), 0, unit(1.0 - t)]
+    })?;
+    write_layer("humidity", &|chunk| [0, unit(chunk.humidity), 0])?;
+
+    let map = world.get_map_data(index.as_index_ref());
+    let path = map_dir.join("map.png");
+    RgbImage::from_fn(size.x, size.y, |x, y| {
+        let pos = to_chunk_pos(x, y);
+        let [r, g, b, _] = map.rgba[(pos.y as u32 * size.x + pos.x as u32) as usize].to_le_bytes();
+        Rgb([r, g, b])
+    })
+    .save(&path)?;
+    println!("Wrote {}", path.display());
+
+    let chunk_size = common::terrain::TerrainChunkSize::RECT_SIZE;
+    let mut sites_img = RgbImage::new(size.x, size.y);
+    let mut sites = Vec::new();
+    for site in index.sites.values() {
+        let origin = site.get_origin();
+        let radius = site.radius();
+        let chunk_pos = origin.map2(chunk_size, |e, sz| e.div_euclid(sz as i32));
+        let chunk_radius = (radius / chunk_size.x as f32).ceil() as i32;
+        for y in -chunk_radius..=chunk_radius {
+            for x in -chunk_radius..=chunk_radius {
+                let pos = chunk_pos + Vec2::new(x, y);
+                if x * x + y * y <= chunk_radius * chunk_radius
+                    && pos.x >= 0
+                    && pos.y >= 0
+                    && (pos.x as u32) < size.x
+                    && (pos.y as u32) < size.y
+                {
+                    sites_img.put_pixel(
+                        pos.x as u32,
+                        size.y - 1 - pos.y as u32,
+                        Rgb(site_color(&site.kind)),
+                    );
+                }
+            }
+        }
+
+        sites.push(serde_json::json!({
+            "kind": site_kind_name(&site.kind),
+            "position": [origin.x, origin.y],
+            "radius": radius,
+            "population": site.economy.pop,
+            "theme": match &site.kind {
+                SiteKind::Dungeon(dungeon) => Some(format!("{:?}", dungeon.theme())),
+                _ => None,
+            },
+        }));
+    }
+    let path = map_dir.join("sites.png");
+    sites_img.save(&path)?;
+    println!("Wrote {}", path.display());
+
+    let path = map_dir.join("sites.json");
+    fs::write(
+        &path,
+        serde_json::to_string_pretty(&serde_json::json!({
+            "seed": seed,
+            "size_chunks": [size.x, size.y],
+            "chunk_size": [chunk_size.x, chunk_size.y],
+            "sea_level": CONFIG.sea_level,
+            "max_height": sim.max_height,
+            "sites": sites,
+        }))?,
+    )?;
+    println!("Wrote {}", path.display());
+
+    Ok(())
+}
+
+const BIOMES: [BiomeKind; 11] = [
+    BiomeKind::Void,
+    BiomeKind::Grassland,
+    BiomeKind::Ocean,
+    BiomeKind::Mountain,
+    BiomeKind::Snowlands,
+    BiomeKind::Desert,
+    BiomeKind::Swamp,
+    BiomeKind::Forest,
+    BiomeKind::Jungle,
+    BiomeKind::Savannah,
+    BiomeKind::Taiga,
+];
+
+fn loot_sim(args: &Cli) -> Result<(), Box<dyn Error>> {
+    let biome = match &args.biome {
+        Some(name) => Some(
+            *BIOMES
+                .iter()
+                .find(|biome| format!("{:?}", biome).eq_ignore_ascii_case(name))
+                .ok_or_else(|| format!("Unknown biome '{}'", name))?,
+        ),
+        None => None,
+    };
+    let site = match args.site.as_deref().map(str::to_lowercase).as_deref() {
+        Some("settlement") => Some(LootSite::Settlement),
+        Some("dungeon") => Some(LootSite::Dungeon),
+        Some("castle") => Some(LootSite::Castle),
+        Some(name) => return Err(format!("Unknown site '{}'", name).into()),
+        None => None,
+    };
+    let ctx = LootContext {
+        level: args.level,
+        biome,
+        site,
+    };
+
+    let table = LootTable::load(&args.table).map_err(|e| format!("{:?}", e))?;
+    let drops = table.simulate(&ctx, args.rolls);
+    let rolls = args.rolls.max(1) as f32;
+    let nothing = args.rolls - drops.iter().map(|(_, times, _)| times).sum::<u32>();
+
+    println!("{} rolls of {} ({:?})", args.rolls, args.table, ctx);
+    for (item, times, amount) in &drops {
+        println!(
+            "{:>7.3}%  avg. {:>5.2}  {}",
+            *times as f32 / rolls * 100.0,
+            *amount as f32 / *times as f32,
+            item
+        );
+    }
+    println!("{:>7.3}%  nothing", nothing as f32 / rolls * 100.0);
+    Ok(())
+}
+
 fn main() {
     let args = Cli::from_args();
     if args.function.eq_ignore_ascii_case("armor_stats") {
@@ -171,10 +449,23 @@ fn main() {
         if let Err(e) = all_items() {
             println!("Error: {}", e)
         }
+    } else if args.function.eq_ignore_ascii_case("pack_assets") {
+        if let Err(e) = pack_assets(&args.output) {
+            println!("Error: {}", e)
+        }
+    } else if args.function.eq_ignore_ascii_case("loot_sim") {
+        if let Err(e) = loot_sim(&args) {
+            println!("Error: {}", e)
+        }
+    } else if args.function.eq_ignore_ascii_case("world_map") {
+        if let Err(e) = world_map(&args.map_dir, args.seed, args.world_file) {
+            println!("Error: {}", e)
+        }
     } else {
         println!(
             "Invalid argument, available \
-             arguments:\n\"armor_stats\"\n\"weapon_stats\"\n\"all_items\""
+             arguments:\n\"armor_stats\"\n\"weapon_stats\"\n\"all_items\"\n\"pack_assets\"\n\"\
+             world_map\"\n\"loot_sim\""
         )
     }
 }
@@ -199,7 +199,7 @@ impl Animation for AlphaAnimation {
                     * Quaternion::rotation_y(0.0);
                 next.torso.scale = Vec3::one() / 11.0 * skeleton_attr.scaler;
             },
-            Some(ToolKind::Axe(_)) => {
+            Some(ToolKind::Axe(_) | ToolKind::Hatchet(_)) => {
                 next.head.position =
                     Vec3::new(0.0, 0.0 + skeleton_attr.head.0, skeleton_attr.head.1);
                 next.head.orientation = Quaternion::rotation_z(0.1 + axe * 0.2)
@@ -60,7 +60,7 @@ impl Animation for EquipAnimation {
                     Quaternion::rotation_y(2.5) * Quaternion::rotation_z(1.57);
                 next.control.scale = Vec3::one();
             },
-            Some(ToolKind::Axe(_)) => {
+            Some(ToolKind::Axe(_) | ToolKind::Hatchet(_)) => {
                 next.hand_l.position = Vec3::new(-4.0, 3.0, 6.0);
                 next.hand_l.orientation = Quaternion::rotation_x(-0.3)
                     * Quaternion::rotation_z(3.14 - 0.3)
@@ -194,7 +194,7 @@ impl Animation for SwimWieldAnimation {
 
                 next.control_r.position = Vec3::new(7.0, 0.0, 0.0);
             },
-            Some(ToolKind::Axe(_)) => {
+            Some(ToolKind::Axe(_) | ToolKind::Hatchet(_)) => {
                 if velocity < 0.5 {
                     next.head.position = Vec3::new(
                         0.0,
@@ -363,7 +363,7 @@ impl Animation for SwimWieldAnimation {
                     Quaternion::rotation_x(u_slow * 0.2) * Quaternion::rotation_z(u_slowalt * 0.1);
                 next.control.scale = Vec3::one();
             },
-            Some(ToolKind::Farming(_)) => {
+            Some(ToolKind::Farming(_) | ToolKind::Pick(_)) => {
                 if velocity < 0.5 {
                     next.head.orientation = Quaternion::rotation_z(head_look.x)
                         * Quaternion::rotation_x(-0.2 + head_look.y.abs());
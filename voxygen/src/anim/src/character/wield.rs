@@ -170,7 +170,7 @@ impl Animation for WieldAnimation {
 
                 next.control_r.position = Vec3::new(7.0, 0.0, 0.0);
             },
-            Some(ToolKind::Axe(_)) => {
+            Some(ToolKind::Axe(_) | ToolKind::Hatchet(_)) => {
                 if speed < 0.5 {
                     next.head.position = Vec3::new(
                         0.0,
@@ -337,7 +337,7 @@ impl Animation for WieldAnimation {
                 next.torso.orientation = Quaternion::rotation_x(0.0);
                 next.torso.scale = Vec3::one() / 11.0 * skeleton_attr.scaler;
             },
-            Some(ToolKind::Farming(_)) => {
+            Some(ToolKind::Farming(_) | ToolKind::Pick(_)) => {
                 if speed < 0.5 {
                     next.head.orientation = Quaternion::rotation_z(head_look.x)
                         * Quaternion::rotation_x(-0.2 + head_look.y.abs());
@@ -317,6 +317,7 @@ impl SfxMgr {
                     },
                 }
             },
+            Outcome::Damage { .. } => {},
         }
     }
 
@@ -225,8 +225,10 @@ impl<'a> Widget for Bag<'a> {
         Text::new(
             &self
                 .localized_strings
-                .get("hud.bag.inventory")
-                .replace("{playername}", &self.stats.name.to_string().as_str()),
+                .get_formatted("hud.bag.inventory", &[(
+                    "playername",
+                    self.stats.name.as_str().into(),
+                )]),
         )
         .mid_top_with_margin_on(state.ids.bg_frame, 9.0)
         .font_id(self.fonts.cyri.conrod_id)
@@ -236,8 +238,10 @@ impl<'a> Widget for Bag<'a> {
         Text::new(
             &self
                 .localized_strings
-                .get("hud.bag.inventory")
-                .replace("{playername}", &self.stats.name.to_string().as_str()),
+                .get_formatted("hud.bag.inventory", &[(
+                    "playername",
+                    self.stats.name.as_str().into(),
+                )]),
         )
         .top_left_with_margins_on(state.ids.inventory_title_bg, 2.0, 2.0)
         .font_id(self.fonts.cyri.conrod_id)
@@ -290,8 +294,10 @@ impl<'a> Widget for Bag<'a> {
             Text::new(
                 &self
                     .localized_strings
-                    .get("hud.bag.inventory")
-                    .replace("{playername}", &self.stats.name.to_string().as_str()),
+                    .get_formatted("hud.bag.inventory", &[(
+                        "playername",
+                        self.stats.name.as_str().into(),
+                    )]),
             )
             .mid_top_with_margin_on(state.ids.bg_frame, 9.0)
             .font_id(self.fonts.cyri.conrod_id)
@@ -301,8 +307,10 @@ impl<'a> Widget for Bag<'a> {
             Text::new(
                 &self
                     .localized_strings
-                    .get("hud.bag.inventory")
-                    .replace("{playername}", &self.stats.name.to_string().as_str()),
+                    .get_formatted("hud.bag.inventory", &[(
+                        "playername",
+                        self.stats.name.as_str().into(),
+                    )]),
             )
             .top_left_with_margins_on(state.ids.inventory_title_bg, 2.0, 2.0)
             .font_id(self.fonts.cyri.conrod_id)
@@ -661,8 +669,10 @@ impl<'a> Widget for Bag<'a> {
             Text::new(
                 &self
                     .localized_strings
-                    .get("hud.bag.stats_title")
-                    .replace("{playername}", &self.stats.name.to_string().as_str()),
+                    .get_formatted("hud.bag.stats_title", &[(
+                        "playername",
+                        self.stats.name.as_str().into(),
+                    )]),
             )
             .mid_top_with_margin_on(state.ids.bg_frame, 9.0)
             .font_id(self.fonts.cyri.conrod_id)
@@ -672,8 +682,10 @@ impl<'a> Widget for Bag<'a> {
             Text::new(
                 &self
                     .localized_strings
-                    .get("hud.bag.stats_title")
-                    .replace("{playername}", &self.stats.name.to_string().as_str()),
+                    .get_formatted("hud.bag.stats_title", &[(
+                        "playername",
+                        self.stats.name.as_str().into(),
+                    )]),
             )
             .top_left_with_margins_on(state.ids.inventory_title_bg, 2.0, 2.0)
             .font_id(self.fonts.cyri.conrod_id)
@@ -1,6 +1,6 @@
 use super::{
     img_ids::{Imgs, ImgsRot},
-    BUFF_COLOR, DEBUFF_COLOR, TEXT_COLOR,
+    util, BUFF_COLOR, DEBUFF_COLOR, TEXT_COLOR,
 };
 use crate::{
     hud::{get_buff_info, BuffPosition},
@@ -203,24 +203,14 @@ impl<'a> Widget for BuffsBar<'a> {
                         )
                         .set(id, ui);
                     // Create Buff tooltip
-                    let title = match buff.kind {
-                        BuffKind::Regeneration { .. } => {
-                            localized_strings.get("buff.title.heal_test")
-                        },
-                        _ => localized_strings.get("buff.title.missing"),
-                    };
+                    let title = localized_strings.get(util::buff_title_key(buff.kind));
                     let remaining_time = if current_duration.is_none() {
                         "Permanent".to_string()
                     } else {
                         format!("Remaining: {:.0}s", current_duration.unwrap().as_secs_f32())
                     };
                     let click_to_remove = format!("<{}>", &localized_strings.get("buff.remove"));
-                    let desc_txt = match buff.kind {
-                        BuffKind::Regeneration { .. } => {
-                            localized_strings.get("buff.desc.heal_test")
-                        },
-                        _ => localized_strings.get("buff.desc.missing"),
-                    };
+                    let desc_txt = localized_strings.get(util::buff_desc_key(buff.kind));
                     let desc = format!("{}\n\n{}\n\n{}", desc_txt, remaining_time, click_to_remove);
                     // Timer overlay
                     if Button::image(match duration_percentage as u64 {
@@ -295,23 +285,13 @@ impl<'a> Widget for BuffsBar<'a> {
                         )
                         .set(id, ui);
                     // Create Debuff tooltip
-                    let title = match debuff.kind {
-                        BuffKind::Bleeding { .. } => {
-                            localized_strings.get("debuff.title.bleed_test")
-                        },
-                        _ => localized_strings.get("buff.title.missing"),
-                    };
+                    let title = localized_strings.get(util::buff_title_key(debuff.kind));
                     let remaining_time = if current_duration.is_none() {
                         "Permanent".to_string()
                     } else {
                         format!("Remaining: {:.0}s", current_duration.unwrap().as_secs_f32())
                     };
-                    let desc_txt = match debuff.kind {
-                        BuffKind::Bleeding { .. } => {
-                            localized_strings.get("debuff.desc.bleed_test")
-                        },
-                        _ => localized_strings.get("debuff.desc.missing"),
-                    };
+                    let desc_txt = localized_strings.get(util::buff_desc_key(debuff.kind));
                     let desc = format!("{}\n\n{}", desc_txt, remaining_time);
                     Image::new(match duration_percentage as u64 {
                         875..=1000 => self.imgs.nothing, // 8/8
@@ -401,30 +381,14 @@ impl<'a> Widget for BuffsBar<'a> {
                         )
                         .set(id, ui);
                     // Create Buff tooltip
-                    let title = match buff.kind {
-                        BuffKind::Regeneration { .. } => {
-                            localized_strings.get("buff.title.heal_test")
-                        },
-                        BuffKind::Bleeding { .. } => {
-                            localized_strings.get("debuff.title.bleed_test")
-                        },
-                        _ => localized_strings.get("buff.title.missing"),
-                    };
+                    let title = localized_strings.get(util::buff_title_key(buff.kind));
                     let remaining_time = if current_duration.is_none() {
                         "".to_string()
                     } else {
                         format!("{:.0}s", current_duration.unwrap().as_secs_f32())
                     };
                     let click_to_remove = format!("<{}>", &localized_strings.get("buff.remove"));
-                    let desc_txt = match buff.kind {
-                        BuffKind::Regeneration { .. } => {
-                            localized_strings.get("buff.desc.heal_test")
-                        },
-                        BuffKind::Bleeding { .. } => {
-                            localized_strings.get("debuff.desc.bleed_test")
-                        },
-                        _ => localized_strings.get("buff.desc.missing"),
-                    };
+                    let desc_txt = localized_strings.get(util::buff_desc_key(buff.kind));
                     let desc = if buff.is_buff {
                         format!("{}\n\n{}", desc_txt, click_to_remove)
                     } else {
@@ -159,9 +159,10 @@ impl<'a> Widget for Buttons<'a> {
         .w_h(420.0 / 10.0, 480.0 / 10.0)
         .with_tooltip(
             self.tooltip_manager,
-            &localized_strings
-                .get("hud.bag.inventory")
-                .replace("{playername}", &self.stats.name.to_string().as_str()),
+            &localized_strings.get_formatted("hud.bag.inventory", &[(
+                "playername",
+                self.stats.name.as_str().into(),
+            )]),
             "",
             &button_tooltip,
             TEXT_COLOR,
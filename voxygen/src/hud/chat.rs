@@ -2,7 +2,11 @@ use super::{
     img_ids::Imgs, ERROR_COLOR, FACTION_COLOR, GROUP_COLOR, INFO_COLOR, KILL_COLOR, LOOT_COLOR,
     OFFLINE_COLOR, ONLINE_COLOR, REGION_COLOR, SAY_COLOR, TELL_COLOR, TEXT_COLOR, WORLD_COLOR,
 };
-use crate::{i18n::VoxygenLocalization, ui::fonts::ConrodVoxygenFonts, GlobalState};
+use crate::{
+    i18n::{FormatArg, VoxygenLocalization},
+    ui::fonts::ConrodVoxygenFonts,
+    GlobalState,
+};
 use client::{cmd, Client};
 use common::{
     comp::{
@@ -340,83 +344,51 @@ impl<'a> Widget for Chat<'a> {
         while let Some(item) = items.next(ui) {
             // This would be easier if conrod used the v-metrics from rusttype.
             if item.i < state.messages.len() {
-                let mut message = state.messages[item.i].clone();
+                let message = &state.messages[item.i];
                 let (color, icon) = render_chat_line(&message.chat_type, &self.imgs);
-                let ChatMsg { chat_type, .. } = &message;
-                // For each ChatType needing localization get/set matching pre-formatted
-                // localized string. This string will be formatted with the data
-                // provided in ChatType in the client/src/lib.rs
-                // fn format_message called below
-                message.message = match chat_type {
-                    ChatType::Online(_) => self
-                        .localized_strings
-                        .get("hud.chat.online_msg")
-                        .to_string(),
-                    ChatType::Offline(_) => self
-                        .localized_strings
-                        .get("hud.chat.offline_msg")
-                        .to_string(),
-                    ChatType::Kill(kill_source, _) => match kill_source {
-                        KillSource::Player(_, KillType::Melee) => self
-                            .localized_strings
-                            .get("hud.chat.pvp_melee_kill_msg")
-                            .to_string(),
-                        KillSource::Player(_, KillType::Projectile) => self
-                            .localized_strings
-                            .get("hud.chat.pvp_ranged_kill_msg")
-                            .to_string(),
-                        KillSource::Player(_, KillType::Explosion) => self
-                            .localized_strings
-                            .get("hud.chat.pvp_explosion_kill_msg")
-                            .to_string(),
-                        KillSource::Player(_, KillType::Energy) => self
-                            .localized_strings
-                            .get("hud.chat.pvp_energy_kill_msg")
-                            .to_string(),
-                        KillSource::Player(_, KillType::Buff) => self
-                            .localized_strings
-                            .get("hud.chat.pvp_buff_kill_msg")
-                            .to_string(),
-                        KillSource::NonPlayer(_, KillType::Melee) => self
-                            .localized_strings
-                            .get("hud.chat.npc_melee_kill_msg")
-                            .to_string(),
-                        KillSource::NonPlayer(_, KillType::Projectile) => self
-                            .localized_strings
-                            .get("hud.chat.npc_ranged_kill_msg")
-                            .to_string(),
-                        KillSource::NonPlayer(_, KillType::Explosion) => self
-                            .localized_strings
-                            .get("hud.chat.npc_explosion_kill_msg")
-                            .to_string(),
-                        KillSource::NonPlayer(_, KillType::Energy) => self
-                            .localized_strings
-                            .get("hud.chat.npc_energy_kill_msg")
-                            .to_string(),
-                        KillSource::NonPlayer(_, KillType::Buff) => self
-                            .localized_strings
-                            .get("hud.chat.npc_buff_kill_msg")
-                            .to_string(),
-                        KillSource::Environment(_) => self
-                            .localized_strings
-                            .get("hud.chat.environmental_kill_msg")
-                            .to_string(),
-                        KillSource::FallDamage => self
-                            .localized_strings
-                            .get("hud.chat.fall_kill_msg")
-                            .to_string(),
-                        KillSource::Suicide => self
-                            .localized_strings
-                            .get("hud.chat.suicide_msg")
-                            .to_string(),
-                        KillSource::Other => self
-                            .localized_strings
-                            .get("hud.chat.default_death_msg")
-                            .to_string(),
+                // ChatType::{Online, Offline, Kill} are localized here, the rest is
+                // formatted in the client/src/lib.rs fn format_message
+                let i18n = &self.localized_strings;
+                let alias = |uid| FormatArg::from(self.client.player_alias(uid));
+                let msg = match &message.chat_type {
+                    ChatType::Online(uid) => {
+                        i18n.get_formatted("hud.chat.online_msg", &[("name", alias(uid))])
+                    },
+                    ChatType::Offline(uid) => {
+                        i18n.get_formatted("hud.chat.offline_msg", &[("name", alias(uid))])
+                    },
+                    ChatType::Kill(kill_source, victim) => match kill_source {
+                        KillSource::Player(attacker, kill_type) => {
+                            i18n.get_formatted(pvp_kill_msg(kill_type), &[
+                                ("attacker", alias(attacker)),
+                                ("victim", alias(victim)),
+                            ])
+                        },
+                        KillSource::NonPlayer(attacker, kill_type) => {
+                            i18n.get_formatted(npc_kill_msg(kill_type), &[
+                                ("attacker", attacker.as_str().into()),
+                                ("victim", alias(victim)),
+                            ])
+                        },
+                        KillSource::Environment(environment) => {
+                            i18n.get_formatted("hud.chat.environmental_kill_msg", &[
+                                ("name", alias(victim)),
+                                ("environment", environment.as_str().into()),
+                            ])
+                        },
+                        KillSource::FallDamage => {
+                            i18n.get_formatted("hud.chat.fall_kill_msg", &[("name", alias(victim))])
+                        },
+                        KillSource::Suicide => {
+                            i18n.get_formatted("hud.chat.suicide_msg", &[("name", alias(victim))])
+                        },
+                        KillSource::Other => i18n.get_formatted("hud.chat.default_death_msg", &[(
+                            "name",
+                            alias(victim),
+                        )]),
                     },
-                    _ => message.message,
+                    _ => self.client.format_message(message, show_char_name),
                 };
-                let msg = self.client.format_message(&message, show_char_name);
                 let text = Text::new(&msg)
                     .font_size(self.fonts.opensans.scale(15))
                     .font_id(self.fonts.opensans.conrod_id)
@@ -573,3 +545,25 @@ fn render_chat_line(chat_type: &ChatType<String>, imgs: &Imgs) -> (Color, conrod
         ChatType::Meta => (INFO_COLOR, imgs.chat_command_info_small),
     }
 }
+
+/// Localization key of the message for a player killing another one
+fn pvp_kill_msg(kill_type: &KillType) -> &'static str {
+    match kill_type {
+        KillType::Melee => "hud.chat.pvp_melee_kill_msg",
+        KillType::Projectile => "hud.chat.pvp_ranged_kill_msg",
+        KillType::Explosion => "hud.chat.pvp_explosion_kill_msg",
+        KillType::Energy => "hud.chat.pvp_energy_kill_msg",
+        KillType::Buff => "hud.chat.pvp_buff_kill_msg",
+    }
+}
+
+/// Localization key of the message for an NPC killing a player
+fn npc_kill_msg(kill_type: &KillType) -> &'static str {
+    match kill_type {
+        KillType::Melee => "hud.chat.npc_melee_kill_msg",
+        KillType::Projectile => "hud.chat.npc_ranged_kill_msg",
+        KillType::Explosion => "hud.chat.npc_explosion_kill_msg",
+        KillType::Energy => "hud.chat.npc_energy_kill_msg",
+        KillType::Buff => "hud.chat.npc_buff_kill_msg",
+    }
+}
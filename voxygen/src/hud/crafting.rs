@@ -1,23 +1,33 @@
 use super::{
     img_ids::{Imgs, ImgsRot},
     item_imgs::ItemImgs,
+    slots::{SalvageSlot, SlotManager},
     TEXT_COLOR, TEXT_DULL_RED_COLOR, TEXT_GRAY_COLOR, UI_HIGHLIGHT_0, UI_MAIN,
 };
 use crate::{
     hud::get_quality_col,
     i18n::VoxygenLocalization,
-    ui::{fonts::ConrodVoxygenFonts, ImageFrame, Tooltip, TooltipManager, Tooltipable},
+    ui::{
+        fonts::ConrodVoxygenFonts,
+        slot::{ContentSize, SlotMaker},
+        ImageFrame, Tooltip, TooltipManager, Tooltipable,
+    },
 };
 use client::{self, Client};
-use common::comp::{
-    item::{ItemDesc, Quality},
-    Inventory,
+use common::{
+    comp::{
+        item::{ItemDesc, Quality},
+        Inventory,
+    },
+    recipe::CraftingStation,
 };
 use conrod_core::{
     color,
     widget::{self, Button, Image, Rectangle, Scrollbar, Text},
     widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
 };
+use vek::Vec2;
+
 widget_ids! {
     pub struct Ids {
         window,
@@ -32,6 +42,9 @@ widget_ids! {
         align_ing,
         scrollbar_ing,
         btn_craft,
+        btn_station_filter,
+        salvage_slot,
+        station_txt,
         recipe_names[],
         recipe_img_frame[],
         recipe_img[],
@@ -59,6 +72,7 @@ pub struct Crafting<'a> {
     localized_strings: &'a std::sync::Arc<VoxygenLocalization>,
     rot_imgs: &'a ImgsRot,
     tooltip_manager: &'a mut TooltipManager,
+    slot_manager: &'a mut SlotManager,
     item_imgs: &'a ItemImgs,
     inventory: &'a Inventory,
     #[conrod(common_builder)]
@@ -73,6 +87,7 @@ impl<'a> Crafting<'a> {
         localized_strings: &'a std::sync::Arc<VoxygenLocalization>,
         rot_imgs: &'a ImgsRot,
         tooltip_manager: &'a mut TooltipManager,
+        slot_manager: &'a mut SlotManager,
         item_imgs: &'a ItemImgs,
         inventory: &'a Inventory,
     ) -> Self {
@@ -83,6 +98,7 @@ impl<'a> Crafting<'a> {
             localized_strings,
             rot_imgs,
             tooltip_manager,
+            slot_manager,
             item_imgs,
             inventory,
             common: widget::CommonBuilder::default(),
@@ -93,6 +109,18 @@ impl<'a> Crafting<'a> {
 pub struct State {
     ids: Ids,
     selected_recipe: Option<String>,
+    /// Only show recipes needing this station, `Some(None)` being recipes that
+    /// don't need one
+    station_filter: Option<Option<CraftingStation>>,
+}
+
+fn station_name_key(station: Option<CraftingStation>) -> &'static str {
+    match station {
+        None => "hud.crafting.by_hand",
+        Some(CraftingStation::Anvil) => "hud.crafting.anvil",
+        Some(CraftingStation::Cauldron) => "hud.crafting.cauldron",
+        Some(CraftingStation::Loom) => "hud.crafting.loom",
+    }
 }
 
 impl<'a> Widget for Crafting<'a> {
@@ -104,6 +132,7 @@ impl<'a> Widget for Crafting<'a> {
         State {
             ids: Ids::new(id_gen),
             selected_recipe: None,
+            station_filter: None,
         }
     }
 
@@ -168,6 +197,37 @@ impl<'a> Widget for Crafting<'a> {
             events.push(Event::Close);
         }
 
+        // Salvage Slot
+        let mut slot_maker = SlotMaker {
+            empty_slot: self.imgs.inv_slot,
+            filled_slot: self.imgs.inv_slot,
+            selected_slot: self.imgs.inv_slot_sel,
+            background_color: Some(UI_MAIN),
+            content_size: ContentSize {
+                width_height_ratio: 1.0,
+                max_fraction: 0.75,
+            },
+            selected_content_scale: 1.067,
+            amount_font: self.fonts.cyri.conrod_id,
+            amount_margins: Vec2::new(-4.0, 0.0),
+            amount_font_size: self.fonts.cyri.scale(12),
+            amount_text_color: TEXT_COLOR,
+            content_source: self.inventory,
+            image_source: self.item_imgs,
+            slot_manager: Some(self.slot_manager),
+        };
+        slot_maker
+            .fabricate(SalvageSlot, [32.0; 2])
+            .left_from(ids.close, 6.0)
+            .with_tooltip(
+                self.tooltip_manager,
+                &self.localized_strings.get("hud.crafting.salvage"),
+                &self.localized_strings.get("hud.crafting.salvage_desc"),
+                &item_tooltip,
+                TEXT_COLOR,
+            )
+            .set(ids.salvage_slot, ui);
+
         // Title
         Text::new(&self.localized_strings.get("hud.crafting"))
             .mid_top_with_margin_on(ids.window_frame, 9.0)
@@ -176,6 +236,36 @@ impl<'a> Widget for Crafting<'a> {
             .color(TEXT_COLOR)
             .set(ids.title_main, ui);
 
+        // Station Filter
+        if Button::image(self.imgs.button)
+            .w_h(105.0, 25.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .label(&self.localized_strings.get(match state.station_filter {
+                None => "hud.crafting.all_stations",
+                Some(station) => station_name_key(station),
+            }))
+            .label_y(conrod_core::position::Relative::Scalar(1.0))
+            .label_color(TEXT_COLOR)
+            .label_font_size(self.fonts.cyri.scale(12))
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .right_from(ids.icon, 8.0)
+            .set(ids.btn_station_filter, ui)
+            .was_clicked()
+        {
+            // Cycle through all recipes, recipes made by hand and then each station
+            let next_filter = match state.station_filter {
+                None => Some(None),
+                Some(station) => CraftingStation::ALL
+                    .iter()
+                    .skip_while(|s| station.map_or(false, |station| **s != station))
+                    .nth(station.map_or(0, |_| 1))
+                    .map(|s| Some(*s)),
+            };
+            state.update(|s| s.station_filter = next_filter);
+        }
+        let ids = &state.ids;
+
         // Alignment
         Rectangle::fill_with([136.0, 378.0], color::TRANSPARENT)
             .top_left_with_margins_on(ids.window_frame, 74.0, 5.0)
@@ -185,25 +275,40 @@ impl<'a> Widget for Crafting<'a> {
             .top_right_with_margins_on(ids.window, 74.0, 5.0)
             .set(ids.align_ing, ui);
         let client = &self.client;
+        let nearby_stations = client.nearby_stations();
+        let station_filter = state.station_filter;
+        let can_perform = |name: &str| {
+            client.available_recipes().contains(name)
+                && client.recipe_book().get(name).map_or(false, |recipe| {
+                    recipe
+                        .station
+                        .map_or(true, |station| nearby_stations.contains(&station))
+                })
+        };
         // First available recipes, then unavailable ones
         // TODO Sort these alphabetically by using "sort_by_key(|x| x.to_lowercase())"
         let recipe_iter = self
             .client
             .recipe_book()
             .iter()
-            .filter(|(name, _)| client.available_recipes().contains(name.as_str()))
+            .filter(|(_, recipe)| station_filter.map_or(true, |station| recipe.station == station))
+            .filter(|(name, _)| can_perform(name.as_str()))
             .map(|(name, recipe)| (name, recipe, true))
             .chain(
                 client
                     .recipe_book()
                     .iter()
-                    .filter(|(name, _)| !client.available_recipes().contains(name.as_str()))
+                    .filter(|(_, recipe)| {
+                        station_filter.map_or(true, |station| recipe.station == station)
+                    })
+                    .filter(|(name, _)| !can_perform(name.as_str()))
                     .map(|(name, recipe)| (name, recipe, false)),
             );
         match &state.selected_recipe {
             None => {},
             Some(recipe) => {
-                let can_perform = client.available_recipes().contains(recipe.as_str());
+                let craft_progress = client.craft_progress(recipe);
+                let can_perform = can_perform(recipe.as_str()) && craft_progress.is_none();
                 // Ingredients Text
                 Text::new(&self.localized_strings.get("hud.crafting.ingredients"))
                     .top_left_with_margins_on(state.ids.align_ing, 10.0, 5.0)
@@ -224,7 +329,10 @@ impl<'a> Widget for Crafting<'a> {
                             .then_some(self.imgs.button_press)
                             .unwrap_or(self.imgs.button),
                     )
-                    .label(&self.localized_strings.get("hud.crafting.craft"))
+                    .label(&match craft_progress {
+                        Some(progress) => format!("{:.0}%", progress * 100.0),
+                        None => self.localized_strings.get("hud.crafting.craft").to_string(),
+                    })
                     .label_y(conrod_core::position::Relative::Scalar(1.0))
                     .label_color(can_perform.then_some(TEXT_COLOR).unwrap_or(TEXT_GRAY_COLOR))
                     .label_font_size(self.fonts.cyri.scale(12))
@@ -236,6 +344,34 @@ impl<'a> Widget for Crafting<'a> {
                 {
                     events.push(Event::CraftRecipe(recipe.clone()));
                 }
+                // Station and crafting time
+                if let Some(recipe) = client.recipe_book().get(recipe.as_str()) {
+                    let station_col = if recipe
+                        .station
+                        .map_or(true, |station| nearby_stations.contains(&station))
+                    {
+                        TEXT_COLOR
+                    } else {
+                        TEXT_DULL_RED_COLOR
+                    };
+                    let station_text = if recipe.craft_time > 0.0 {
+                        format!(
+                            "{} ({}s)",
+                            self.localized_strings.get(station_name_key(recipe.station)),
+                            recipe.craft_time
+                        )
+                    } else {
+                        self.localized_strings
+                            .get(station_name_key(recipe.station))
+                            .to_string()
+                    };
+                    Text::new(&station_text)
+                        .bottom_left_with_margins_on(ids.align_ing, -25.0, 5.0)
+                        .font_id(self.fonts.cyri.conrod_id)
+                        .font_size(self.fonts.cyri.scale(12))
+                        .color(station_col)
+                        .set(ids.station_txt, ui);
+                }
                 // Result Image BG
                 let quality_col_img = if let Some(recipe) = state
                     .selected_recipe
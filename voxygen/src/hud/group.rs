@@ -1,6 +1,6 @@
 use super::{
     img_ids::{Imgs, ImgsRot},
-    Show, BLACK, BUFF_COLOR, DEBUFF_COLOR, ERROR_COLOR, GROUP_COLOR, HP_COLOR, KILL_COLOR,
+    util, Show, BLACK, BUFF_COLOR, DEBUFF_COLOR, ERROR_COLOR, GROUP_COLOR, HP_COLOR, KILL_COLOR,
     LOW_HP_COLOR, STAMINA_COLOR, TEXT_COLOR, TEXT_COLOR_GREY, UI_HIGHLIGHT_0, UI_MAIN,
 };
 
@@ -14,7 +14,10 @@ use crate::{
 };
 use client::{self, Client};
 use common::{
-    comp::{group::Role, BuffKind, Stats},
+    comp::{
+        group::{InviteKind, Role},
+        BuffKind, Stats,
+    },
     sync::{Uid, WorldSyncExt},
 };
 use conrod_core::{
@@ -212,7 +215,7 @@ impl<'a> Widget for Group<'a> {
                 .crop_kids()
                 .set(state.ids.bg, ui);
         }
-        if let Some((_, timeout_start, timeout_dur)) = open_invite {
+        if let Some((_, timeout_start, timeout_dur, _)) = open_invite {
             // Group Menu button
             Button::image(self.imgs.group_icon)
                 .w_h(49.0, 26.0)
@@ -503,15 +506,7 @@ impl<'a> Widget for Group<'a> {
                                     )
                                     .set(id, ui);
                                 // Create Buff tooltip
-                                let title = match buff.kind {
-                                    BuffKind::Regeneration { .. } => {
-                                        localized_strings.get("buff.title.heal_test")
-                                    },
-                                    BuffKind::Bleeding { .. } => {
-                                        localized_strings.get("debuff.title.bleed_test")
-                                    },
-                                    _ => localized_strings.get("buff.title.missing"),
-                                };
+                                let title = localized_strings.get(util::buff_title_key(buff.kind));
                                 let remaining_time = if current_duration.is_none() {
                                     "Permanent".to_string()
                                 } else {
@@ -520,15 +515,8 @@ impl<'a> Widget for Group<'a> {
                                         current_duration.unwrap().as_secs_f32()
                                     )
                                 };
-                                let desc_txt = match buff.kind {
-                                    BuffKind::Regeneration { .. } => {
-                                        localized_strings.get("buff.desc.heal_test")
-                                    },
-                                    BuffKind::Bleeding { .. } => {
-                                        localized_strings.get("debuff.desc.bleed_test")
-                                    },
-                                    _ => localized_strings.get("buff.desc.missing"),
-                                };
+                                let desc_txt =
+                                    localized_strings.get(util::buff_desc_key(buff.kind));
                                 let desc = format!("{}\n\n{}", desc_txt, remaining_time);
                                 Image::new(match duration_percentage as u64 {
                                     875..=1000 => self.imgs.nothing, // 8/8
@@ -754,16 +742,19 @@ impl<'a> Widget for Group<'a> {
                 // into the maximum group size.
             }
         }
-        if let Some((invite_uid, _, _)) = open_invite {
+        if let Some((invite_uid, _, _, kind)) = open_invite {
             self.show.group = true; // Auto open group menu
             // TODO: add group name here too
             // Invite text
 
             let name = uid_to_name_text(invite_uid, &self.client);
-            let invite_text = self
-                .localized_strings
-                .get("hud.group.invite_to_join")
-                .replace("{name}", &name);
+            let invite_text = self.localized_strings.get_formatted(
+                match kind {
+                    InviteKind::Group => "hud.group.invite_to_join",
+                    InviteKind::Faction => "hud.group.invite_to_join_faction",
+                },
+                &[("name", name.into())],
+            );
             Text::new(&invite_text)
                 .mid_top_with_margin_on(state.ids.bg, 5.0)
                 .font_size(12)
@@ -1,6 +1,6 @@
 use crate::ui::{Graphic, SampleStrat, Transform, Ui};
 use common::{
-    assets::{self, watch::ReloadIndicator, Asset},
+    assets::{self, watch::ReloadIndicator, Asset, AssetFile},
     comp::item::{
         armor::{Armor, ArmorKind},
         tool::{Tool, ToolKind},
@@ -13,7 +13,7 @@ use dot_vox::DotVoxData;
 use hashbrown::HashMap;
 use image::DynamicImage;
 use serde::{Deserialize, Serialize};
-use std::{fs::File, io::BufReader, sync::Arc};
+use std::{io::BufReader, sync::Arc};
 use tracing::{error, warn};
 use vek::*;
 
@@ -85,7 +85,7 @@ struct ItemImagesSpec(HashMap<ItemKey, ImageSpec>);
 impl Asset for ItemImagesSpec {
     const ENDINGS: &'static [&'static str] = &["ron"];
 
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
         ron::de::from_reader(buf_reader).map_err(assets::Error::parse_error)
     }
 }
@@ -18,6 +18,7 @@ mod skillbar;
 mod slots;
 mod social;
 mod spell;
+mod storage;
 mod util;
 
 pub use hotbar::{SlotContents as HotbarSlotContents, State as HotbarState};
@@ -42,6 +43,7 @@ use settings_window::{SettingsTab, SettingsWindow};
 use skillbar::Skillbar;
 use social::{Social, SocialTab};
 use spell::Spell;
+use storage::Storage;
 
 use crate::{
     ecs::{comp as vcomp, comp::HpFloaterList},
@@ -251,6 +253,7 @@ widget_ids! {
         small_window,
         social_window,
         crafting_window,
+        storage_window,
         settings_window,
         group_window,
 
@@ -359,6 +362,7 @@ pub enum Event {
     ChangeAutoWalkBehavior(PressBehavior),
     ChangeStopAutoWalkOnInput(bool),
     CraftRecipe(String),
+    SalvageSlot(usize),
     InviteMember(common::sync::Uid),
     AcceptInvite,
     DeclineInvite,
@@ -366,6 +370,10 @@ pub enum Event {
     LeaveGroup,
     AssignLeader(common::sync::Uid),
     RemoveBuff(BuffKind),
+    Deposit(usize),
+    Withdraw(usize),
+    CloseContainer,
+    PickUpContainer(Vec3<i32>),
 }
 
 // TODO: Are these the possible layouts we want?
@@ -424,6 +432,7 @@ pub struct Show {
     intro: bool,
     help: bool,
     crafting: bool,
+    storage: bool,
     debug: bool,
     bag: bool,
     social: bool,
@@ -458,6 +467,7 @@ impl Show {
             self.map = open;
             self.bag = false;
             self.crafting = false;
+            self.storage = false;
             self.social = false;
             self.spell = false;
             self.want_grab = !open;
@@ -475,6 +485,17 @@ impl Show {
     fn crafting(&mut self, open: bool) {
         if !self.esc_menu {
             self.crafting = open;
+            self.storage = false;
+            self.bag = open;
+            self.map = false;
+            self.want_grab = !open;
+        }
+    }
+
+    fn storage(&mut self, open: bool) {
+        if !self.esc_menu {
+            self.storage = open;
+            self.crafting = false;
             self.bag = open;
             self.map = false;
             self.want_grab = !open;
@@ -504,6 +525,7 @@ impl Show {
             self.bag = false;
             self.social = false;
             self.crafting = false;
+            self.storage = false;
             self.spell = false;
             self.want_grab = !open;
         }
@@ -536,6 +558,7 @@ impl Show {
             || self.map
             || self.social
             || self.crafting
+            || self.storage
             || self.spell
             || self.help
             || self.intro
@@ -549,6 +572,7 @@ impl Show {
             self.social = false;
             self.spell = false;
             self.crafting = false;
+            self.storage = false;
             self.open_windows = Windows::None;
             self.want_grab = true;
 
@@ -687,6 +711,7 @@ impl Hud {
                 open_windows: Windows::None,
                 map: false,
                 crafting: false,
+                storage: false,
                 ui: true,
                 social: false,
                 spell: false,
@@ -1653,12 +1678,10 @@ impl Hud {
 
             // Help Window
             if let Some(help_key) = global_state.settings.controls.get_binding(GameInput::Help) {
-                Text::new(
-                    &self
-                        .voxygen_i18n
-                        .get("hud.press_key_to_toggle_keybindings_fmt")
-                        .replace("{key}", help_key.to_string().as_str()),
-                )
+                Text::new(&self.voxygen_i18n.get_formatted(
+                    "hud.press_key_to_toggle_keybindings_fmt",
+                    &[("key", help_key.to_string().into())],
+                ))
                 .color(TEXT_COLOR)
                 .down_from(self.ids.num_particles, 5.0)
                 .font_id(self.fonts.cyri.conrod_id)
@@ -1671,12 +1694,10 @@ impl Hud {
                 .controls
                 .get_binding(GameInput::ToggleDebug)
             {
-                Text::new(
-                    &self
-                        .voxygen_i18n
-                        .get("hud.press_key_to_toggle_debug_info_fmt")
-                        .replace("{key}", toggle_debug_key.to_string().as_str()),
-                )
+                Text::new(&self.voxygen_i18n.get_formatted(
+                    "hud.press_key_to_toggle_debug_info_fmt",
+                    &[("key", toggle_debug_key.to_string().into())],
+                ))
                 .color(TEXT_COLOR)
                 .down_from(self.ids.help_info, 5.0)
                 .font_id(self.fonts.cyri.conrod_id)
@@ -1686,12 +1707,10 @@ impl Hud {
         } else {
             // Help Window
             if let Some(help_key) = global_state.settings.controls.get_binding(GameInput::Help) {
-                Text::new(
-                    &self
-                        .voxygen_i18n
-                        .get("hud.press_key_to_show_keybindings_fmt")
-                        .replace("{key}", help_key.to_string().as_str()),
-                )
+                Text::new(&self.voxygen_i18n.get_formatted(
+                    "hud.press_key_to_show_keybindings_fmt",
+                    &[("key", help_key.to_string().into())],
+                ))
                 .color(TEXT_COLOR)
                 .bottom_left_with_margins_on(ui_widgets.window, 210.0, 10.0)
                 .font_id(self.fonts.cyri.conrod_id)
@@ -1704,12 +1723,10 @@ impl Hud {
                 .controls
                 .get_binding(GameInput::ToggleDebug)
             {
-                Text::new(
-                    &self
-                        .voxygen_i18n
-                        .get("hud.press_key_to_show_debug_info_fmt")
-                        .replace("{key}", toggle_debug_key.to_string().as_str()),
-                )
+                Text::new(&self.voxygen_i18n.get_formatted(
+                    "hud.press_key_to_show_debug_info_fmt",
+                    &[("key", toggle_debug_key.to_string().into())],
+                ))
                 .color(TEXT_COLOR)
                 .top_left_with_margins_on(ui_widgets.window, 5.0, 5.0)
                 .font_id(self.fonts.cyri.conrod_id)
@@ -1722,12 +1739,10 @@ impl Hud {
                 .controls
                 .get_binding(GameInput::ToggleLantern)
             {
-                Text::new(
-                    &self
-                        .voxygen_i18n
-                        .get("hud.press_key_to_toggle_lantern_fmt")
-                        .replace("{key}", toggle_lantern_key.to_string().as_str()),
-                )
+                Text::new(&self.voxygen_i18n.get_formatted(
+                    "hud.press_key_to_toggle_lantern_fmt",
+                    &[("key", toggle_lantern_key.to_string().into())],
+                ))
                 .color(TEXT_COLOR)
                 .up_from(self.ids.help_info, 2.0)
                 .font_id(self.fonts.cyri.conrod_id)
@@ -1875,6 +1890,7 @@ impl Hud {
                         self.show.stats = false;
                         self.show.bag(false);
                         self.show.crafting(false);
+                        self.show.storage(false);
                         if !self.show.social {
                             self.show.want_grab = true;
                             self.force_ungrab = false;
@@ -1944,6 +1960,7 @@ impl Hud {
                     &self.voxygen_i18n,
                     &self.rot_imgs,
                     tooltip_manager,
+                    &mut self.slot_manager,
                     &self.item_imgs,
                     &inventory,
                 )
@@ -1969,6 +1986,57 @@ impl Hud {
             }
         }
 
+        // Storage
+        if self.show.storage {
+            if let Some((pos, container)) = client.container() {
+                for event in Storage::new(
+                    &self.imgs,
+                    &self.fonts,
+                    &self.voxygen_i18n,
+                    &self.rot_imgs,
+                    tooltip_manager,
+                    &mut self.slot_manager,
+                    &self.item_imgs,
+                    container,
+                )
+                .set(self.ids.storage_window, ui_widgets)
+                {
+                    match event {
+                        storage::Event::PickUp => {
+                            events.push(Event::PickUpContainer(pos));
+                            self.show.storage(false);
+                        },
+                        storage::Event::Close => {
+                            self.show.stats = false;
+                            self.show.storage(false);
+                            if !self.show.social {
+                                self.show.want_grab = true;
+                                self.force_ungrab = false;
+                            } else {
+                                self.force_ungrab = true
+                            };
+                        },
+                    }
+                }
+            }
+        }
+        // Let go of containers once their window is closed or they're out of reach
+        if let Some((pos, _)) = client.container() {
+            let in_range =
+                ecs.read_storage::<comp::Pos>()
+                    .get(entity)
+                    .map_or(false, |player_pos| {
+                        player_pos.0.distance_squared(pos.map(|e| e as f32 + 0.5))
+                            < common::comp::MAX_PICKUP_RANGE_SQR
+                    });
+            if !in_range {
+                self.show.storage(false);
+            }
+            if !self.show.storage {
+                events.push(Event::CloseContainer);
+            }
+        }
+
         // Don't put NPC messages in chat box.
         self.new_messages
             .retain(|m| !matches!(m.chat_type, comp::ChatType::Npc(_, _)));
@@ -2288,8 +2356,10 @@ impl Hud {
                 Text::new(
                     &self
                         .voxygen_i18n
-                        .get("hud.free_look_indicator")
-                        .replace("{key}", freelook_key.to_string().as_str()),
+                        .get_formatted("hud.free_look_indicator", &[(
+                            "key",
+                            freelook_key.to_string().into(),
+                        )]),
                 )
                 .color(TEXT_BG)
                 .mid_top_with_margin_on(ui_widgets.window, 40.0)
@@ -2299,8 +2369,10 @@ impl Hud {
                 Text::new(
                     &self
                         .voxygen_i18n
-                        .get("hud.free_look_indicator")
-                        .replace("{key}", freelook_key.to_string().as_str()),
+                        .get_formatted("hud.free_look_indicator", &[(
+                            "key",
+                            freelook_key.to_string().into(),
+                        )]),
                 )
                 .color(KILL_COLOR)
                 .top_left_with_margins_on(self.ids.free_look_bg, -1.0, -1.0)
@@ -2333,7 +2405,7 @@ impl Hud {
             let to_slot = |slot_kind| match slot_kind {
                 Inventory(i) => Some(Slot::Inventory(i.0)),
                 Equip(e) => Some(Slot::Equip(e)),
-                Hotbar(_) => None,
+                Hotbar(_) | Container(_) | Salvage(_) => None,
             };
             match event {
                 slot::Event::Dragged(a, b) => {
@@ -2346,6 +2418,12 @@ impl Hud {
                     } else if let (Hotbar(a), Hotbar(b)) = (a, b) {
                         self.hotbar.swap(a, b);
                         events.push(Event::ChangeHotbarState(Box::new(self.hotbar.to_owned())));
+                    } else if let (Inventory(i), Container(_)) = (a, b) {
+                        events.push(Event::Deposit(i.0));
+                    } else if let (Container(c), Inventory(_)) = (a, b) {
+                        events.push(Event::Withdraw(c.0));
+                    } else if let (Inventory(i), Salvage(_)) = (a, b) {
+                        events.push(Event::SalvageSlot(i.0));
                     }
                 },
                 slot::Event::Dropped(from) => {
@@ -2371,6 +2449,8 @@ impl Hud {
                                                                        * sticks */
                             }
                         });
+                    } else if let Container(c) = from {
+                        events.push(Event::Withdraw(c.0));
                     }
                 },
             }
@@ -2712,6 +2792,9 @@ impl Hud {
     pub fn free_look(&mut self, free_look: bool) { self.show.free_look = free_look; }
 
     pub fn auto_walk(&mut self, auto_walk: bool) { self.show.auto_walk = auto_walk; }
+
+    /// Show the contents of the container the client just opened
+    pub fn open_container(&mut self) { self.show.storage(true); }
 }
 // Get item qualities of equipped items and assign a tooltip title/frame color
 pub fn get_quality_col<I: ItemDesc>(item: &I) -> Color {
@@ -264,9 +264,10 @@ impl<'a> Widget for Skillbar<'a> {
             Rectangle::fill_with([82.0 * 4.0, 40.0 * 4.0], color::TRANSPARENT)
                 .mid_top_with_margin_on(ui.window, 300.0)
                 .set(state.ids.level_align, ui);
-            let level_up_text = &localized_strings
-                .get("char_selection.level_fmt")
-                .replace("{level_nb}", &self.stats.level.level().to_string());
+            let level_up_text = &localized_strings.get_formatted("char_selection.level_fmt", &[(
+                "level_nb",
+                self.stats.level.level().into(),
+            )]);
             Text::new(&level_up_text)
                 .middle_of(state.ids.level_align)
                 .font_size(self.fonts.cyri.scale(30))
@@ -307,9 +308,10 @@ impl<'a> Widget for Skillbar<'a> {
                     .color(Color::Rgba(0.0, 0.0, 0.0, 1.0))
                     .set(state.ids.death_message_1_bg, ui);
                 Text::new(
-                    &localized_strings
-                        .get("hud.press_key_to_respawn")
-                        .replace("{key}", key.to_string().as_str()),
+                    &localized_strings.get_formatted("hud.press_key_to_respawn", &[(
+                        "key",
+                        key.to_string().into(),
+                    )]),
                 )
                 .mid_bottom_with_margin_on(state.ids.death_message_1_bg, -120.0)
                 .font_size(self.fonts.cyri.scale(30))
@@ -323,9 +325,10 @@ impl<'a> Widget for Skillbar<'a> {
                     .color(CRITICAL_HP_COLOR)
                     .set(state.ids.death_message_1, ui);
                 Text::new(
-                    &localized_strings
-                        .get("hud.press_key_to_respawn")
-                        .replace("{key}", key.to_string().as_str()),
+                    &localized_strings.get_formatted("hud.press_key_to_respawn", &[(
+                        "key",
+                        key.to_string().into(),
+                    )]),
                 )
                 .bottom_left_with_margins_on(state.ids.death_message_2_bg, 2.0, 2.0)
                 .font_size(self.fonts.cyri.scale(30))
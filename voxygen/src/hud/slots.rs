@@ -20,6 +20,8 @@ pub enum SlotKind {
     Inventory(InventorySlot),
     Equip(EquipSlot),
     Hotbar(HotbarSlot),
+    Container(ContainerSlot),
+    Salvage(SalvageSlot),
     /* Spellbook(SpellbookSlot), TODO */
 }
 
@@ -47,6 +49,40 @@ impl SlotKey<Inventory, ItemImgs> for InventorySlot {
     }
 }
 
+/// A slot in an open storage container
+#[derive(Clone, Copy, PartialEq)]
+pub struct ContainerSlot(pub usize);
+
+impl SlotKey<Inventory, ItemImgs> for ContainerSlot {
+    type ImageKey = ItemKey;
+
+    fn image_key(&self, source: &Inventory) -> Option<(Self::ImageKey, Option<Color>)> {
+        InventorySlot(self.0).image_key(source)
+    }
+
+    fn amount(&self, source: &Inventory) -> Option<u32> { InventorySlot(self.0).amount(source) }
+
+    fn image_id(key: &Self::ImageKey, source: &ItemImgs) -> image::Id {
+        source.img_id_or_not_found_img(key.clone())
+    }
+}
+
+/// The slot items are dragged into to salvage them
+#[derive(Clone, Copy, PartialEq)]
+pub struct SalvageSlot;
+
+impl SlotKey<Inventory, ItemImgs> for SalvageSlot {
+    type ImageKey = ItemKey;
+
+    fn image_key(&self, _: &Inventory) -> Option<(Self::ImageKey, Option<Color>)> { None }
+
+    fn amount(&self, _: &Inventory) -> Option<u32> { None }
+
+    fn image_id(key: &Self::ImageKey, source: &ItemImgs) -> image::Id {
+        source.img_id_or_not_found_img(key.clone())
+    }
+}
+
 impl SlotKey<Loadout, ItemImgs> for EquipSlot {
     type ImageKey = ItemKey;
 
@@ -187,4 +223,12 @@ impl From<HotbarSlot> for SlotKind {
     fn from(hotbar: HotbarSlot) -> Self { Self::Hotbar(hotbar) }
 }
 
+impl From<ContainerSlot> for SlotKind {
+    fn from(container: ContainerSlot) -> Self { Self::Container(container) }
+}
+
+impl From<SalvageSlot> for SlotKind {
+    fn from(salvage: SalvageSlot) -> Self { Self::Salvage(salvage) }
+}
+
 impl SumSlot for SlotKind {}
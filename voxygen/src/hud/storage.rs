@@ -0,0 +1,238 @@
+use super::{
+    img_ids::{Imgs, ImgsRot},
+    item_imgs::ItemImgs,
+    slots::{ContainerSlot, SlotManager},
+    TEXT_COLOR, UI_HIGHLIGHT_0, UI_MAIN,
+};
+use crate::{
+    hud::get_quality_col,
+    i18n::VoxygenLocalization,
+    ui::{
+        fonts::ConrodVoxygenFonts,
+        slot::{ContentSize, SlotMaker},
+        ImageFrame, Tooltip, TooltipManager, Tooltipable,
+    },
+};
+use common::comp::{item::Quality, Inventory};
+use conrod_core::{
+    color,
+    widget::{self, Button, Image, Rectangle, Text},
+    widget_ids, Color, Colorable, Labelable, Positionable, Sizeable, Widget, WidgetCommon,
+};
+use vek::Vec2;
+
+widget_ids! {
+    pub struct Ids {
+        window,
+        window_frame,
+        close,
+        title,
+        align_slots,
+        slots[],
+        btn_pick_up,
+    }
+}
+
+pub enum Event {
+    PickUp,
+    Close,
+}
+
+/// Window showing the contents of an open storage container
+#[derive(WidgetCommon)]
+pub struct Storage<'a> {
+    imgs: &'a Imgs,
+    fonts: &'a ConrodVoxygenFonts,
+    localized_strings: &'a std::sync::Arc<VoxygenLocalization>,
+    rot_imgs: &'a ImgsRot,
+    tooltip_manager: &'a mut TooltipManager,
+    slot_manager: &'a mut SlotManager,
+    item_imgs: &'a ItemImgs,
+    container: &'a Inventory,
+    #[conrod(common_builder)]
+    common: widget::CommonBuilder,
+}
+#[allow(clippy::too_many_arguments)]
+impl<'a> Storage<'a> {
+    pub fn new(
+        imgs: &'a Imgs,
+        fonts: &'a ConrodVoxygenFonts,
+        localized_strings: &'a std::sync::Arc<VoxygenLocalization>,
+        rot_imgs: &'a ImgsRot,
+        tooltip_manager: &'a mut TooltipManager,
+        slot_manager: &'a mut SlotManager,
+        item_imgs: &'a ItemImgs,
+        container: &'a Inventory,
+    ) -> Self {
+        Self {
+            imgs,
+            fonts,
+            localized_strings,
+            rot_imgs,
+            tooltip_manager,
+            slot_manager,
+            item_imgs,
+            container,
+            common: widget::CommonBuilder::default(),
+        }
+    }
+}
+
+pub struct State {
+    ids: Ids,
+}
+
+impl<'a> Widget for Storage<'a> {
+    type Event = Vec<Event>;
+    type State = State;
+    type Style = ();
+
+    fn init_state(&self, id_gen: widget::id::Generator) -> Self::State {
+        State {
+            ids: Ids::new(id_gen),
+        }
+    }
+
+    #[allow(clippy::unused_unit)] // TODO: Pending review in #587
+    fn style(&self) -> Self::Style { () }
+
+    fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
+        let widget::UpdateArgs { state, ui, .. } = args;
+
+        if state.ids.slots.len() < self.container.len() {
+            state.update(|s| {
+                s.ids
+                    .slots
+                    .resize(self.container.len(), &mut ui.widget_id_generator())
+            });
+        }
+        let ids = &state.ids;
+
+        let mut events = Vec::new();
+
+        // Tooltips
+        let item_tooltip = Tooltip::new({
+            let edge = &self.rot_imgs.tt_side;
+            let corner = &self.rot_imgs.tt_corner;
+            ImageFrame::new(
+                [edge.cw180, edge.none, edge.cw270, edge.cw90],
+                [corner.none, corner.cw270, corner.cw90, corner.cw180],
+                Color::Rgba(0.08, 0.07, 0.04, 1.0),
+                5.0,
+            )
+        })
+        .title_font_size(self.fonts.cyri.scale(15))
+        .parent(ui.window)
+        .desc_font_size(self.fonts.cyri.scale(12))
+        .font_id(self.fonts.cyri.conrod_id)
+        .desc_text_color(TEXT_COLOR);
+
+        let rows = (self.container.len() + 8) / 9;
+        let height = 90.0 + rows as f64 * 40.0;
+        Image::new(self.imgs.crafting_window)
+            .bottom_right_with_margins_on(ui.window, 308.0, 450.0)
+            .color(Some(UI_MAIN))
+            .w_h(392.0, height)
+            .set(ids.window, ui);
+        Image::new(self.imgs.crafting_frame)
+            .middle_of(ids.window)
+            .color(Some(UI_HIGHLIGHT_0))
+            .w_h(392.0, height)
+            .set(ids.window_frame, ui);
+        // Close Button
+        if Button::image(self.imgs.close_button)
+            .w_h(24.0, 25.0)
+            .hover_image(self.imgs.close_button_hover)
+            .press_image(self.imgs.close_button_press)
+            .top_right_with_margins_on(ids.window, 0.0, 0.0)
+            .set(ids.close, ui)
+            .was_clicked()
+        {
+            events.push(Event::Close);
+        }
+
+        // Title
+        Text::new(&self.localized_strings.get("hud.storage"))
+            .mid_top_with_margin_on(ids.window_frame, 9.0)
+            .font_id(self.fonts.cyri.conrod_id)
+            .font_size(self.fonts.cyri.scale(20))
+            .color(TEXT_COLOR)
+            .set(ids.title, ui);
+
+        // Container Slots
+        Rectangle::fill_with([360.0, rows as f64 * 40.0], color::TRANSPARENT)
+            .mid_top_with_margin_on(ids.window_frame, 45.0)
+            .set(ids.align_slots, ui);
+        let mut slot_maker = SlotMaker {
+            empty_slot: self.imgs.inv_slot,
+            filled_slot: self.imgs.inv_slot,
+            selected_slot: self.imgs.inv_slot_sel,
+            background_color: Some(UI_MAIN),
+            content_size: ContentSize {
+                width_height_ratio: 1.0,
+                max_fraction: 0.75,
+            },
+            selected_content_scale: 1.067,
+            amount_font: self.fonts.cyri.conrod_id,
+            amount_margins: Vec2::new(-4.0, 0.0),
+            amount_font_size: self.fonts.cyri.scale(12),
+            amount_text_color: TEXT_COLOR,
+            content_source: self.container,
+            image_source: self.item_imgs,
+            slot_manager: Some(self.slot_manager),
+        };
+        for (i, item) in self.container.slots().iter().enumerate() {
+            let x = i % 9;
+            let y = i / 9;
+
+            let slot_widget = slot_maker
+                .fabricate(ContainerSlot(i), [40.0; 2])
+                .top_left_with_margins_on(ids.align_slots, y as f64 * 40.0, x as f64 * 40.0);
+            if let Some(item) = item {
+                let (title, desc) = super::util::item_text(item);
+                let quality_col = get_quality_col(item);
+                let quality_col_img = match item.quality() {
+                    Quality::Low => self.imgs.inv_slot_grey,
+                    Quality::Common => self.imgs.inv_slot,
+                    Quality::Moderate => self.imgs.inv_slot_green,
+                    Quality::High => self.imgs.inv_slot_blue,
+                    Quality::Epic => self.imgs.inv_slot_purple,
+                    Quality::Legendary => self.imgs.inv_slot_gold,
+                    Quality::Artifact => self.imgs.inv_slot_orange,
+                    _ => self.imgs.inv_slot_red,
+                };
+                slot_widget
+                    .filled_slot(quality_col_img)
+                    .with_tooltip(
+                        self.tooltip_manager,
+                        title,
+                        &*desc,
+                        &item_tooltip,
+                        quality_col,
+                    )
+                    .set(ids.slots[i], ui);
+            } else {
+                slot_widget.set(ids.slots[i], ui);
+            }
+        }
+
+        // Pick Up Button
+        if Button::image(self.imgs.button)
+            .w_h(105.0, 25.0)
+            .hover_image(self.imgs.button_hover)
+            .press_image(self.imgs.button_press)
+            .label(&self.localized_strings.get("hud.storage.pick_up"))
+            .label_y(conrod_core::position::Relative::Scalar(1.0))
+            .label_color(TEXT_COLOR)
+            .label_font_size(self.fonts.cyri.scale(12))
+            .label_font_id(self.fonts.cyri.conrod_id)
+            .mid_bottom_with_margin_on(ids.window_frame, 10.0)
+            .set(ids.btn_pick_up, ui)
+            .was_clicked()
+        {
+            events.push(Event::PickUp);
+        }
+
+        events
+    }
+}
@@ -1,7 +1,10 @@
-use common::comp::item::{
-    armor::{Armor, ArmorKind, Protection},
-    tool::{Tool, ToolKind},
-    ItemDesc, ItemKind,
+use common::comp::{
+    item::{
+        armor::{Armor, ArmorKind, Protection},
+        tool::{Tool, ToolKind},
+        Affix, ItemDesc, ItemKind, ItemState,
+    },
+    BuffKind, Element,
 };
 use std::borrow::Cow;
 
@@ -20,8 +23,8 @@ pub fn loadout_slot_text<'a>(
 
 pub fn item_text<'a>(item: &'a impl ItemDesc) -> (&'_ str, Cow<'a, str>) {
     let desc: Cow<str> = match item.kind() {
-        ItemKind::Armor(armor) => Cow::Owned(armor_desc(&armor, item.description())),
-        ItemKind::Tool(tool) => Cow::Owned(tool_desc(&tool, item.description())),
+        ItemKind::Armor(armor) => Cow::Owned(armor_desc(&armor, item.state(), item.description())),
+        ItemKind::Tool(tool) => Cow::Owned(tool_desc(&tool, item.state(), item.description())),
         ItemKind::Glider(_glider) => Cow::Owned(glider_desc(item.description())),
         ItemKind::Consumable { .. } => Cow::Owned(consumable_desc(item.description())),
         ItemKind::Throwable { .. } => Cow::Owned(throwable_desc(item.description())),
@@ -50,8 +53,42 @@ fn ingredient_desc(desc: &str) -> String { format!("Crafting Ingredient\n\n{}",
 
 fn lantern_desc(desc: &str) -> String { format!("Lantern\n\n{}\n\n<Right-Click to use>", desc) }
 
+// Durability and affixes of a particular item
+fn state_desc(state: Option<&ItemState>) -> String {
+    let state = match state {
+        Some(state) => state,
+        None => return String::new(),
+    };
+    let mut lines = Vec::new();
+    match state.durability {
+        Some(durability) if durability.is_broken() => lines.push("Broken".to_string()),
+        Some(durability) => lines.push(format!(
+            "Durability: {}/{}",
+            durability.current, durability.max
+        )),
+        None => {},
+    }
+    lines.extend(state.affixes.iter().map(|affix| match affix {
+        Affix::Power(multiplier) => format!("+{:0.0}% Power", (multiplier - 1.0) * 100.0),
+        Affix::Protection(protection) => format!("+{:0.0} Armor", protection),
+        Affix::Durability(multiplier) => format!("+{:0.0}% Durability", (multiplier - 1.0) * 100.0),
+    }));
+
+    lines.iter().map(|line| format!("\n{}", line)).collect()
+}
+
+fn element_name(element: Element) -> &'static str {
+    // TODO: localization
+    match element {
+        Element::Fire => "Fire",
+        Element::Frost => "Frost",
+        Element::Poison => "Poison",
+        Element::Arcane => "Arcane",
+    }
+}
+
 // Armor Description
-fn armor_desc(armor: &Armor, desc: &str) -> String {
+fn armor_desc(armor: &Armor, state: Option<&ItemState>, desc: &str) -> String {
     // TODO: localization
     let kind = match armor.kind {
         ArmorKind::Shoulder(_) => "Shoulders",
@@ -66,22 +103,40 @@ fn armor_desc(armor: &Armor, desc: &str) -> String {
         ArmorKind::Head(_) => "Head",
         ArmorKind::Tabard(_) => "Tabard",
     };
-    let armor = match armor.get_protection() {
+    let protection = state.map_or(armor.get_protection(), |state| {
+        state.protection(armor.get_protection())
+    });
+    let armor_resistances = armor.get_resistances();
+    let armor = match protection {
         Protection::Normal(a) => a.to_string(),
         Protection::Invincible => "Inf".to_string(),
     };
+    let resistances = state
+        .map_or(armor_resistances, |state| {
+            state.resistances(armor_resistances)
+        })
+        .iter()
+        .filter(|(_, resistance)| *resistance != 0.0)
+        .map(|(element, resistance)| {
+            format!("\n{} Resistance: {}", element_name(element), resistance)
+        })
+        .collect::<String>();
+    let state = state_desc(state);
 
     if !desc.is_empty() {
         format!(
-            "{}\n\nArmor: {}\n\n{}\n\n<Right-Click to use>",
-            kind, armor, desc
+            "{}\n\nArmor: {}{}{}\n\n{}\n\n<Right-Click to use>",
+            kind, armor, resistances, state, desc
         )
     } else {
-        format!("{}\n\nArmor: {}\n\n<Right-Click to use>", kind, armor)
+        format!(
+            "{}\n\nArmor: {}{}{}\n\n<Right-Click to use>",
+            kind, armor, resistances, state
+        )
     }
 }
 // Weapon/Tool Description
-fn tool_desc(tool: &Tool, desc: &str) -> String {
+fn tool_desc(tool: &Tool, state: Option<&ItemState>, desc: &str) -> String {
     // TODO: localization
     let kind = match tool.kind {
         ToolKind::Sword(_) => "Sword",
@@ -95,22 +150,32 @@ fn tool_desc(tool: &Tool, desc: &str) -> String {
         ToolKind::NpcWeapon(_) => "Npc Weapon",
         ToolKind::Debug(_) => "Debug",
         ToolKind::Farming(_) => "Farming Tool",
+        ToolKind::Pick(_) => "Pickaxe",
+        ToolKind::Hatchet(_) => "Hatchet",
         ToolKind::Empty => "Empty",
     };
-    let power = tool.base_power();
+    let power = tool.base_power() * state.map_or(1.0, |state| state.power_multiplier());
+    let element = tool.element().map_or(String::new(), |element| {
+        format!("\nElement: {}", element_name(element))
+    });
+    let state = state_desc(state);
 
     if !desc.is_empty() {
         format!(
-            "{}\n\nPower: {:0.1}\n\n{}\n\n<Right-Click to use>",
+            "{}\n\nPower: {:0.1}{}{}\n\n{}\n\n<Right-Click to use>",
             kind,
             power * 10.0,
+            element,
+            state,
             desc
         )
     } else {
         format!(
-            "{}\n\nPower: {:0.1}\n\n<Right-Click to use>",
+            "{}\n\nPower: {:0.1}{}{}\n\n<Right-Click to use>",
             kind,
-            power * 10.0
+            power * 10.0,
+            element,
+            state
         )
     }
 }
@@ -132,3 +197,47 @@ fn tool_desc(tool: &Tool, desc: &str) -> String {
 // Ingredient Description
 
 // Lantern Description
+
+/// Localization key of a buff's name
+pub fn buff_title_key(kind: BuffKind) -> &'static str {
+    match kind {
+        BuffKind::Regeneration => "buff.title.heal_test",
+        BuffKind::Bleeding => "debuff.title.bleed_test",
+        BuffKind::Cursed => "debuff.title.cursed",
+        BuffKind::Haste => "buff.title.haste",
+        BuffKind::Slowed => "debuff.title.slowed",
+        BuffKind::Empowered => "buff.title.empowered",
+        BuffKind::Weakened => "debuff.title.weakened",
+        BuffKind::Fortified => "buff.title.fortified",
+        BuffKind::Vulnerable => "debuff.title.vulnerable",
+        BuffKind::Invigorated => "buff.title.invigorated",
+        BuffKind::Stunned => "debuff.title.stunned",
+        BuffKind::Rooted => "debuff.title.rooted",
+        BuffKind::Silenced => "debuff.title.silenced",
+        BuffKind::Burning => "debuff.title.burning",
+        BuffKind::Chilled => "debuff.title.chilled",
+        BuffKind::Poisoned => "debuff.title.poisoned",
+    }
+}
+
+/// Localization key of a buff's description
+pub fn buff_desc_key(kind: BuffKind) -> &'static str {
+    match kind {
+        BuffKind::Regeneration => "buff.desc.heal_test",
+        BuffKind::Bleeding => "debuff.desc.bleed_test",
+        BuffKind::Cursed => "debuff.desc.cursed",
+        BuffKind::Haste => "buff.desc.haste",
+        BuffKind::Slowed => "debuff.desc.slowed",
+        BuffKind::Empowered => "buff.desc.empowered",
+        BuffKind::Weakened => "debuff.desc.weakened",
+        BuffKind::Fortified => "buff.desc.fortified",
+        BuffKind::Vulnerable => "debuff.desc.vulnerable",
+        BuffKind::Invigorated => "buff.desc.invigorated",
+        BuffKind::Stunned => "debuff.desc.stunned",
+        BuffKind::Rooted => "debuff.desc.rooted",
+        BuffKind::Silenced => "debuff.desc.silenced",
+        BuffKind::Burning => "debuff.desc.burning",
+        BuffKind::Chilled => "debuff.desc.chilled",
+        BuffKind::Poisoned => "debuff.desc.poisoned",
+    }
+}
@@ -1,10 +1,13 @@
-use common::assets::{self, Asset};
+pub mod format;
+
+pub use format::FormatArg;
+
+use common::assets::{self, Asset, AssetFile};
 use deunicode::deunicode;
 use ron::de::from_reader;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::{HashMap, HashSet},
-    fs::File,
     io::BufReader,
 };
 use tracing::warn;
@@ -81,6 +84,15 @@ impl VoxygenLocalization {
         }
     }
 
+    /// Get a localized text from the given key and format it with the given
+    /// arguments, see [`format`] for the supported syntax
+    ///
+    /// If the key is not present in the localization object
+    /// then the key is returned.
+    pub fn get_formatted(&self, key: &str, args: &[(&str, FormatArg)]) -> String {
+        format::format(self.get(key), &self.metadata.language_identifier, args)
+    }
+
     /// Get a variation of localized text from the given key
     ///
     /// `index` should be a random number from `0` to `u16::max()`
@@ -94,8 +106,12 @@ impl VoxygenLocalization {
         }
     }
 
-    /// Return the missing keys compared to the reference language
-    pub fn list_missing_entries(&self) -> (HashSet<String>, HashSet<String>) {
+    /// Return the missing keys compared to the reference language, along with
+    /// the keys whose arguments don't match the reference text and the reason
+    /// why
+    pub fn list_missing_entries(
+        &self,
+    ) -> (HashSet<String>, HashSet<String>, Vec<(String, String)>) {
         let reference_localization =
             VoxygenLocalization::load_expect(i18n_asset_key(REFERENCE_LANG).as_ref());
 
@@ -115,12 +131,22 @@ impl VoxygenLocalization {
             .cloned()
             .collect();
 
-        (strings, vectors)
+        let mut mismatches = self
+            .string_map
+            .iter()
+            .filter_map(|(key, text)| {
+                let reference = reference_localization.string_map.get(key)?;
+                placeholder_mismatch(reference, text).map(|reason| (key.clone(), reason))
+            })
+            .collect::<Vec<_>>();
+        mismatches.sort();
+
+        (strings, vectors, mismatches)
     }
 
     /// Log missing entries (compared to the reference language) as warnings
     pub fn log_missing_entries(&self) {
-        let (missing_strings, missing_vectors) = self.list_missing_entries();
+        let (missing_strings, missing_vectors, mismatches) = self.list_missing_entries();
         for missing_key in missing_strings {
             warn!(
                 "[{:?}] Missing string key {:?}",
@@ -133,6 +159,29 @@ impl VoxygenLocalization {
                 self.metadata.language_identifier, missing_key
             );
         }
+        for (key, reason) in mismatches {
+            warn!(
+                "[{:?}] Invalid string key {:?}: {}",
+                self.metadata.language_identifier, key, reason
+            );
+        }
+    }
+}
+
+/// Checks that a translation uses the same arguments as the reference text,
+/// returning a description of the problem if it doesn't
+fn placeholder_mismatch(reference: &str, translation: &str) -> Option<String> {
+    let expected = match format::placeholders(reference) {
+        Ok(expected) => expected,
+        Err(e) => return Some(format!("reference text is invalid: {}", e)),
+    };
+    match format::placeholders(translation) {
+        Ok(found) if found == expected => None,
+        Ok(found) => Some(format!(
+            "expected arguments {:?}, found {:?}",
+            expected, found
+        )),
+        Err(e) => Some(format!("invalid syntax: {}", e)),
     }
 }
 
@@ -142,7 +191,7 @@ impl Asset for VoxygenLocalization {
     /// Load the translations located in the input buffer and convert them
     /// into a `VoxygenLocalization` object.
     #[allow(clippy::into_iter_on_ref)] // TODO: Pending review in #587
-    fn parse(buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
         let mut asked_localization: VoxygenLocalization =
             from_reader(buf_reader).map_err(assets::Error::parse_error)?;
 
@@ -175,7 +224,7 @@ pub fn i18n_asset_key(language_id: &str) -> String { "voxygen.i18n.".to_string()
 
 #[cfg(test)]
 mod tests {
-    use super::VoxygenLocalization;
+    use super::{placeholder_mismatch, VoxygenLocalization};
     use git2::Repository;
     use ron::de::{from_bytes, from_reader};
     use std::{
@@ -523,6 +572,17 @@ mod tests {
                 }
             }
 
+            // Arguments have to match the reference text, or values would be lost
+            let mut mismatched_keys: Vec<&String> = current_loc.string_map.keys().collect();
+            mismatched_keys.sort();
+            for key in mismatched_keys {
+                if let Some(reason) = loc.string_map.get(key).and_then(|reference| {
+                    placeholder_mismatch(reference, &current_loc.string_map[key])
+                }) {
+                    println!("[{:9}] | {:60}| {}", "Arguments", key, reason);
+                }
+            }
+
             println!(
                 "\n{} up-to-date, {} outdated, {} unused, {} not found, {} unknown entries",
                 uptodate_entries,
@@ -0,0 +1,472 @@
+//! A subset of the ICU MessageFormat syntax for localized texts
+//!
+//! Supported are plain arguments (`{name}`), plurals with exact matches and
+//! the plural categories of the language (`{count, plural, =0 {no items} one
+//! {# item} other {# items}}`) and selects (`{gender, select, female {her}
+//! other {their}}`). Unlike ICU, apostrophes are not used for quoting, since
+//! many translations contain them as regular text.
+use std::{borrow::Cow, collections::BTreeSet, fmt};
+
+/// A value which can be inserted into a localized text
+#[derive(Clone, Debug, PartialEq)]
+pub enum FormatArg<'a> {
+    Text(Cow<'a, str>),
+    Number(i64),
+}
+
+impl<'a> From<&'a str> for FormatArg<'a> {
+    fn from(text: &'a str) -> Self { FormatArg::Text(Cow::Borrowed(text)) }
+}
+
+impl From<String> for FormatArg<'_> {
+    fn from(text: String) -> Self { FormatArg::Text(Cow::Owned(text)) }
+}
+
+impl From<i64> for FormatArg<'_> {
+    fn from(n: i64) -> Self { FormatArg::Number(n) }
+}
+
+impl From<i32> for FormatArg<'_> {
+    fn from(n: i32) -> Self { FormatArg::Number(i64::from(n)) }
+}
+
+impl From<u32> for FormatArg<'_> {
+    fn from(n: u32) -> Self { FormatArg::Number(i64::from(n)) }
+}
+
+impl From<u64> for FormatArg<'_> {
+    fn from(n: u64) -> Self { FormatArg::Number(n as i64) }
+}
+
+impl From<usize> for FormatArg<'_> {
+    fn from(n: usize) -> Self { FormatArg::Number(n as i64) }
+}
+
+impl fmt::Display for FormatArg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatArg::Text(text) => write!(f, "{}", text),
+            FormatArg::Number(n) => write!(f, "{}", n),
+        }
+    }
+}
+
+/// The CLDR plural categories
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl PluralCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PluralCategory::Zero => "zero",
+            PluralCategory::One => "one",
+            PluralCategory::Two => "two",
+            PluralCategory::Few => "few",
+            PluralCategory::Many => "many",
+            PluralCategory::Other => "other",
+        }
+    }
+
+    /// The plural category of the integer `n` in a language, identified by
+    /// the `language_identifier` of its metadata (e.g. "ru_RU")
+    pub fn of(language_identifier: &str, n: i64) -> Self {
+        let n = n.abs();
+        let language = language_identifier
+            .split('_')
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+        let (mod10, mod100) = (n % 10, n % 100);
+
+        match language.as_str() {
+            "zh" | "ja" | "ko" => PluralCategory::Other,
+            "fr" => {
+                if n <= 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            },
+            "pt" if language_identifier.eq_ignore_ascii_case("pt_BR") => {
+                if n <= 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            },
+            "ru" | "uk" => {
+                if mod10 == 1 && mod100 != 11 {
+                    PluralCategory::One
+                } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Many
+                }
+            },
+            "pl" => {
+                if n == 1 {
+                    PluralCategory::One
+                } else if (2..=4).contains(&mod10) && !(12..=14).contains(&mod100) {
+                    PluralCategory::Few
+                } else {
+                    PluralCategory::Many
+                }
+            },
+            // English, German, Spanish, Italian, Dutch, Swedish, Turkish, ...
+            _ => {
+                if n == 1 {
+                    PluralCategory::One
+                } else {
+                    PluralCategory::Other
+                }
+            },
+        }
+    }
+}
+
+/// An error in the syntax of a localized text
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormatError {
+    /// Character offset of the error
+    pub position: usize,
+    pub message: &'static str,
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at character {}", self.message, self.position)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Text(String),
+    Arg(String),
+    /// `#` inside of a plural case, replaced by the number
+    Number,
+    Plural {
+        arg: String,
+        cases: Vec<(String, Vec<Segment>)>,
+    },
+    Select {
+        arg: String,
+        cases: Vec<(String, Vec<Segment>)>,
+    },
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn error(&self, message: &'static str) -> FormatError {
+        FormatError {
+            position: self.pos,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<char> { self.chars.get(self.pos).copied() }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Reads up to (not including) whitespace or one of `ends`
+    fn word(&mut self, ends: &[char]) -> String {
+        self.skip_whitespace();
+        let start = self.pos;
+        while self
+            .peek()
+            .map_or(false, |c| !c.is_whitespace() && !ends.contains(&c))
+        {
+            self.pos += 1;
+        }
+        self.chars[start..self.pos].iter().collect()
+    }
+
+    fn expect(&mut self, c: char, message: &'static str) -> Result<(), FormatError> {
+        self.skip_whitespace();
+        if self.peek() == Some(c) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(message))
+        }
+    }
+
+    /// Parses text until the end of input or, if `nested`, the closing brace
+    /// of the enclosing case
+    fn message(&mut self, nested: bool, in_plural: bool) -> Result<Vec<Segment>, FormatError> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        loop {
+            match self.peek() {
+                None if nested => return Err(self.error("Unclosed '{'")),
+                None => break,
+                Some('}') if nested => break,
+                Some('}') => return Err(self.error("Unmatched '}'")),
+                Some('{') => {
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    self.pos += 1;
+                    segments.push(self.argument(in_plural)?);
+                },
+                Some('#') if in_plural => {
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    self.pos += 1;
+                    segments.push(Segment::Number);
+                },
+                Some(c) => {
+                    text.push(c);
+                    self.pos += 1;
+                },
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(segments)
+    }
+
+    /// Parses an argument after its opening brace, including the closing one
+    fn argument(&mut self, in_plural: bool) -> Result<Segment, FormatError> {
+        let arg = self.word(&[',', '}']);
+        if arg.is_empty() {
+            return Err(self.error("Missing argument name"));
+        }
+        self.skip_whitespace();
+        if self.peek() == Some('}') {
+            self.pos += 1;
+            return Ok(Segment::Arg(arg));
+        }
+        self.expect(',', "Expected ',' or '}' after argument name")?;
+
+        let kind = self.word(&[',', '}']);
+        match kind.as_str() {
+            "number" => {
+                self.expect('}', "Expected '}' after number argument")?;
+                Ok(Segment::Arg(arg))
+            },
+            "plural" | "select" => {
+                self.expect(',', "Expected ',' before cases")?;
+                let is_plural = kind == "plural";
+                let mut cases = Vec::new();
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some('}') {
+                        self.pos += 1;
+                        break;
+                    }
+                    let selector = self.word(&['{', '}']);
+                    if selector.is_empty() {
+                        return Err(self.error("Missing case selector"));
+                    }
+                    self.expect('{', "Expected '{' after case selector")?;
+                    let message = self.message(true, is_plural || in_plural)?;
+                    self.pos += 1;
+                    cases.push((selector, message));
+                }
+                if !cases.iter().any(|(selector, _)| selector == "other") {
+                    return Err(self.error("Missing 'other' case"));
+                }
+                Ok(if is_plural {
+                    Segment::Plural { arg, cases }
+                } else {
+                    Segment::Select { arg, cases }
+                })
+            },
+            _ => Err(self.error("Unknown argument type, expected 'plural' or 'select'")),
+        }
+    }
+}
+
+fn parse(pattern: &str) -> Result<Vec<Segment>, FormatError> {
+    Parser {
+        chars: pattern.chars().collect(),
+        pos: 0,
+    }
+    .message(false, false)
+}
+
+fn write_segments(
+    out: &mut String,
+    segments: &[Segment],
+    language_identifier: &str,
+    args: &[(&str, FormatArg)],
+    number: Option<i64>,
+) {
+    let find = |name: &str| args.iter().find(|(n, _)| *n == name).map(|(_, arg)| arg);
+
+    for segment in segments {
+        match segment {
+            Segment::Text(text) => out.push_str(text),
+            Segment::Arg(name) => match find(name) {
+                Some(arg) => out.push_str(&arg.to_string()),
+                // Leave missing arguments visible, like missing keys
+                None => {
+                    out.push('{');
+                    out.push_str(name);
+                    out.push('}');
+                },
+            },
+            Segment::Number => {
+                if let Some(n) = number {
+                    out.push_str(&n.to_string());
+                }
+            },
+            Segment::Plural { arg, cases } => {
+                let n = match find(arg) {
+                    Some(FormatArg::Number(n)) => *n,
+                    Some(FormatArg::Text(text)) => text.parse().unwrap_or(0),
+                    None => 0,
+                };
+                let exact = format!("={}", n);
+                let category = PluralCategory::of(language_identifier, n).as_str();
+                let case = cases
+                    .iter()
+                    .find(|(selector, _)| *selector == exact)
+                    .or_else(|| cases.iter().find(|(selector, _)| selector == category))
+                    .or_else(|| cases.iter().find(|(selector, _)| selector == "other"));
+                if let Some((_, message)) = case {
+                    write_segments(out, message, language_identifier, args, Some(n));
+                }
+            },
+            Segment::Select { arg, cases } => {
+                let value = find(arg).map(|arg| arg.to_string()).unwrap_or_default();
+                let case = cases
+                    .iter()
+                    .find(|(selector, _)| *selector == value)
+                    .or_else(|| cases.iter().find(|(selector, _)| selector == "other"));
+                if let Some((_, message)) = case {
+                    write_segments(out, message, language_identifier, args, number);
+                }
+            },
+        }
+    }
+}
+
+/// Formats a localized text with the given arguments. Texts with invalid
+/// syntax are returned unchanged.
+pub fn format(pattern: &str, language_identifier: &str, args: &[(&str, FormatArg)]) -> String {
+    match parse(pattern) {
+        Ok(segments) => {
+            let mut out = String::with_capacity(pattern.len());
+            write_segments(&mut out, &segments, language_identifier, args, None);
+            out
+        },
+        Err(_) => pattern.to_owned(),
+    }
+}
+
+/// The names of all arguments used in a localized text
+pub fn placeholders(pattern: &str) -> Result<BTreeSet<String>, FormatError> {
+    fn collect(segments: &[Segment], names: &mut BTreeSet<String>) {
+        for segment in segments {
+            match segment {
+                Segment::Arg(name) => {
+                    names.insert(name.clone());
+                },
+                Segment::Plural { arg, cases } | Segment::Select { arg, cases } => {
+                    names.insert(arg.clone());
+                    for (_, message) in cases {
+                        collect(message, names);
+                    }
+                },
+                Segment::Text(_) | Segment::Number => {},
+            }
+        }
+    }
+
+    let mut names = BTreeSet::new();
+    collect(&parse(pattern)?, &mut names);
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_arguments() {
+        assert_eq!(
+            format("{playername}'s Inventory", "en", &[(
+                "playername",
+                "Veloren".into()
+            )]),
+            "Veloren's Inventory"
+        );
+        assert_eq!(
+            format("Level {level_nb}", "en", &[("level_nb", 5u32.into())]),
+            "Level 5"
+        );
+        assert_eq!(format("Hello {name}", "en", &[]), "Hello {name}");
+    }
+
+    #[test]
+    fn plurals() {
+        let pattern = "{count, plural, =0 {no items} one {# item} other {# items}}";
+        let items = |n: i64| format(pattern, "en", &[("count", n.into())]);
+        assert_eq!(items(0), "no items");
+        assert_eq!(items(1), "1 item");
+        assert_eq!(items(7), "7 items");
+
+        let pattern =
+            "{n, plural, one {# предмет} few {# предмета} many {# предметов} other {# предмета}}";
+        let ru = |n: i64| format(pattern, "ru_RU", &[("n", n.into())]);
+        assert_eq!(ru(1), "1 предмет");
+        assert_eq!(ru(3), "3 предмета");
+        assert_eq!(ru(11), "11 предметов");
+        assert_eq!(ru(21), "21 предмет");
+    }
+
+    #[test]
+    fn select() {
+        let pattern = "{gender, select, female {She} male {He} other {They}} joined";
+        assert_eq!(
+            format(pattern, "en", &[("gender", "female".into())]),
+            "She joined"
+        );
+        assert_eq!(format(pattern, "en", &[]), "They joined");
+    }
+
+    #[test]
+    fn plural_rules() {
+        use PluralCategory::*;
+        assert_eq!(PluralCategory::of("en", 1), One);
+        assert_eq!(PluralCategory::of("fr_FR", 0), One);
+        assert_eq!(PluralCategory::of("pt_PT", 0), Other);
+        assert_eq!(PluralCategory::of("pt_BR", 0), One);
+        assert_eq!(PluralCategory::of("PL", 22), Few);
+        assert_eq!(PluralCategory::of("PL", 21), Many);
+        assert_eq!(PluralCategory::of("ru_RU", 21), One);
+        assert_eq!(PluralCategory::of("zh_CN", 1), Other);
+    }
+
+    #[test]
+    fn placeholder_validation() {
+        assert_eq!(
+            placeholders("{a} and {n, plural, one {# {b}} other {# {c}}}")
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            vec!["a", "b", "c", "n"]
+        );
+        assert!(placeholders("{unclosed").is_err());
+        assert!(placeholders("stray }").is_err());
+        assert!(placeholders("{n, plural, one {#}}").is_err());
+    }
+}
@@ -792,8 +792,10 @@ impl CharSelectionUi {
                     Text::new(
                         &self
                             .voxygen_i18n
-                            .get("char_selection.level_fmt")
-                            .replace("{level_nb}", &character_item.level.to_string()),
+                            .get_formatted("char_selection.level_fmt", &[(
+                                "level_nb",
+                                character_item.level.into(),
+                            )]),
                     )
                     .down_from(self.ids.character_names[i], 4.0)
                     .font_size(self.fonts.cyri.scale(17))
@@ -13,7 +13,7 @@ use super::{
     ShadowMapMode, ShadowMode, WrapMode,
 };
 use common::{
-    assets::{self, watch::ReloadIndicator, Asset},
+    assets::{self, watch::ReloadIndicator, Asset, AssetFile},
     span,
 };
 use core::convert::TryFrom;
@@ -25,10 +25,7 @@ use gfx::{
 };
 use glsl_include::Context as IncludeContext;
 use image::DynamicImage;
-use std::{
-    fs::File,
-    io::{BufReader, Read},
-};
+use std::io::{BufReader, Read};
 use tracing::{error, warn};
 use vek::*;
 
@@ -109,7 +106,10 @@ impl Asset for Glsl {
 
     const ENDINGS: &'static [&'static str] = &["glsl"];
 
-    fn parse(mut buf_reader: BufReader<File>, _specifier: &str) -> Result<String, assets::Error> {
+    fn parse(
+        mut buf_reader: BufReader<AssetFile>,
+        _specifier: &str,
+    ) -> Result<String, assets::Error> {
         let mut string = String::new();
         buf_reader.read_to_string(&mut string)?;
         Ok(string)
@@ -807,7 +807,7 @@ impl FigureMgr {
                                 skeleton_attr,
                             )
                         },
-                        CharacterState::BasicMelee(_) => {
+                        CharacterState::BasicMelee(_) | CharacterState::Mining(_) => {
                             anim::character::AlphaAnimation::update_skeleton(
                                 &target_base,
                                 (
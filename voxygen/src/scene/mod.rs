@@ -421,7 +421,7 @@ impl Scene {
                 },
                 fadeout: |timeout| timeout * 2.0,
             }),
-            Outcome::ProjectileShot { .. } => {},
+            Outcome::ProjectileShot { .. } | Outcome::Damage { .. } => {},
         }
     }
 
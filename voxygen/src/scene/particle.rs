@@ -123,7 +123,7 @@ impl ParticleMgr {
                     );
                 }
             },
-            Outcome::ProjectileShot { .. } => {},
+            Outcome::ProjectileShot { .. } | Outcome::Damage { .. } => {},
         }
     }
 
@@ -494,6 +494,15 @@ impl ParticleMgr {
                 mode: ParticleMode::Firefly,
                 cond: |sd| sd.state.get_day_period().is_dark(),
             },
+            // Caves are dark all day long, so glowing spores drift around there regardless
+            BlockParticles {
+                blocks: |boi| &boi.glow_mushrooms,
+                range: 4,
+                rate: 0.02,
+                lifetime: 30.0,
+                mode: ParticleMode::Firefly,
+                cond: |_| true,
+            },
             BlockParticles {
                 blocks: |boi| &boi.beehives,
                 range: 3,
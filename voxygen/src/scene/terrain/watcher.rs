@@ -13,6 +13,7 @@ pub struct BlocksOfInterest {
     pub beehives: Vec<Vec3<i32>>,
     pub reeds: Vec<Vec3<i32>>,
     pub flowers: Vec<Vec3<i32>>,
+    pub glow_mushrooms: Vec<Vec3<i32>>,
 }
 
 impl BlocksOfInterest {
@@ -24,6 +25,7 @@ impl BlocksOfInterest {
         let mut beehives = Vec::new();
         let mut reeds = Vec::new();
         let mut flowers = Vec::new();
+        let mut glow_mushrooms = Vec::new();
 
         chunk
             .vol_iter(
@@ -55,6 +57,7 @@ impl BlocksOfInterest {
                     Some(SpriteKind::WhiteFlower) => flowers.push(pos),
                     Some(SpriteKind::YellowFlower) => flowers.push(pos),
                     Some(SpriteKind::Sunflower) => flowers.push(pos),
+                    Some(SpriteKind::GlowMushroom) => glow_mushrooms.push(pos),
                     _ => {},
                 },
             });
@@ -66,6 +69,7 @@ impl BlocksOfInterest {
             beehives,
             reeds,
             flowers,
+            glow_mushrooms,
         }
     }
 }
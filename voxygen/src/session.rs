@@ -137,10 +137,10 @@ impl SessionState {
                         },
                         InventoryUpdateEvent::Collected(item) => {
                             self.hud.new_message(ChatMsg {
-                                message: self
-                                    .voxygen_i18n
-                                    .get("hud.chat.loot_msg")
-                                    .replace("{item}", item.name()),
+                                message: self.voxygen_i18n.get_formatted("hud.chat.loot_msg", &[(
+                                    "item",
+                                    item.name().into(),
+                                )]),
                                 chat_type: ChatType::Loot,
                             });
                         },
@@ -153,8 +153,7 @@ impl SessionState {
                         0 => String::from(self.voxygen_i18n.get("hud.chat.goodbye")),
                         _ => self
                             .voxygen_i18n
-                            .get("hud.chat.connection_lost")
-                            .replace("{time}", time.to_string().as_str()),
+                            .get_formatted("hud.chat.connection_lost", &[("time", time.into())]),
                     };
 
                     self.hud.new_message(ChatMsg {
@@ -178,6 +177,7 @@ impl SessionState {
                     global_state.settings.save_to_file_warn();
                 },
                 client::Event::Outcome(outcome) => outcomes.push(outcome),
+                client::Event::ContainerOpened => self.hud.open_container(),
             }
         }
 
@@ -263,6 +263,8 @@ impl PlayState for SessionState {
                 under_cursor(&self.client.borrow(), cam_pos, cam_dir);
             // Throw out distance info, it will be useful in the future
             self.target_entity = target_entity.map(|x| x.0);
+            // Tools that act on terrain (e.g. pickaxes) strike the block under the cursor
+            self.inputs.select_pos = select_pos;
 
             let can_build = self
                 .client
@@ -272,14 +274,14 @@ impl PlayState for SessionState {
                 .get(self.client.borrow().entity())
                 .is_some();
 
-            // Only highlight collectables
+            // Only highlight collectables and things that can be opened or closed
             self.scene.set_select_pos(select_pos.filter(|sp| {
                 self.client
                     .borrow()
                     .state()
                     .terrain()
                     .get(*sp)
-                    .map(|b| b.is_collectible() || can_build)
+                    .map(|b| b.is_collectible() || b.is_toggleable() || can_build)
                     .unwrap_or(false)
             }));
 
@@ -500,9 +502,17 @@ impl PlayState for SessionState {
                         if state {
                             let mut client = self.client.borrow_mut();
 
-                            // Collect terrain sprites
+                            // Collect terrain sprites, open and close gates, or open
+                            // storage containers
                             if let Some(select_pos) = self.scene.select_pos() {
-                                client.collect_block(select_pos);
+                                let block = client.state().terrain().get(select_pos).ok().copied();
+                                if block.map_or(false, |b| b.is_toggleable()) {
+                                    client.toggle_sprite(select_pos);
+                                } else if block.map_or(false, |b| b.is_container()) {
+                                    client.open_container(select_pos);
+                                } else {
+                                    client.collect_block(select_pos);
+                                }
                             }
 
                             // Collect lootable entities
@@ -1033,6 +1043,11 @@ impl PlayState for SessionState {
                     HudEvent::CraftRecipe(r) => {
                         self.client.borrow_mut().craft_recipe(&r);
                     },
+                    HudEvent::SalvageSlot(slot) => self.client.borrow_mut().salvage_slot(slot),
+                    HudEvent::Deposit(slot) => self.client.borrow_mut().deposit(slot),
+                    HudEvent::Withdraw(slot) => self.client.borrow_mut().withdraw(slot),
+                    HudEvent::CloseContainer => self.client.borrow_mut().close_container(),
+                    HudEvent::PickUpContainer(pos) => self.client.borrow_mut().collect_block(pos),
                     HudEvent::InviteMember(uid) => {
                         self.client.borrow_mut().send_group_invite(uid);
                     },
@@ -1176,7 +1191,7 @@ fn under_cursor(
 
     let cam_ray = terrain
         .ray(cam_pos, cam_pos + cam_dir * 100.0)
-        .until(|block| block.is_filled() || block.is_collectible())
+        .until(|block| block.is_filled() || block.is_collectible() || block.is_toggleable())
         .cast();
 
     let cam_dist = cam_ray.0;
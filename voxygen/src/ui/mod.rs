@@ -32,7 +32,11 @@ use crate::{
 #[rustfmt::skip]
 use ::image::GenericImageView;
 use cache::Cache;
-use common::{assets, span, util::srgba_to_linear};
+use common::{
+    assets::{self, AssetFile},
+    span,
+    util::srgba_to_linear,
+};
 use conrod_core::{
     event::Input,
     graph::{self, Graph},
@@ -47,7 +51,6 @@ use core::{convert::TryInto, f32, f64, ops::Range};
 use graphic::{Rotation, TexId};
 use hashbrown::hash_map::Entry;
 use std::{
-    fs::File,
     io::{BufReader, Read},
     sync::Arc,
     time::Duration,
@@ -105,7 +108,10 @@ pub struct Font(text::Font);
 impl assets::Asset for Font {
     const ENDINGS: &'static [&'static str] = &["ttf"];
 
-    fn parse(mut buf_reader: BufReader<File>, _specifier: &str) -> Result<Self, assets::Error> {
+    fn parse(
+        mut buf_reader: BufReader<AssetFile>,
+        _specifier: &str,
+    ) -> Result<Self, assets::Error> {
         let mut buf = Vec::new();
         buf_reader.read_to_end(&mut buf)?;
         Ok(Font(text::Font::from_bytes(buf).unwrap()))
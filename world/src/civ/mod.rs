@@ -5,7 +5,7 @@ mod econ;
 use self::{Occupation::*, Stock::*};
 use crate::{
     config::CONFIG,
-    sim::WorldSim,
+    sim::{WorldSim, CAVE_LAYERS},
     site::{Castle, Dungeon, Settlement, Site as WorldSite},
     util::{attempt, seed_expan, MapVec, CARDINALS, NEIGHBORS},
     Index,
@@ -27,6 +27,7 @@ use fxhash::{FxHasher32, FxHasher64};
 use hashbrown::{HashMap, HashSet};
 use rand::prelude::*;
 use rand_chacha::ChaChaRng;
+use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
 use vek::*;
 
@@ -39,7 +40,7 @@ const fn initial_civ_count(map_size_lg: MapSizeLg) -> u32 {
 }
 
 #[allow(clippy::type_complexity)] // TODO: Pending review in #587
-#[derive(Default)]
+#[derive(Default, Serialize, Deserialize)]
 pub struct Civs {
     civs: Store<Civ>,
     places: Store<Place>,
@@ -56,6 +57,9 @@ pub struct Civs {
     >,
 
     sites: Store<Site>,
+    /// Seeds each site in `sites` was laid out in the world with, so that they
+    /// can be laid out again when the civilisations are loaded from a map.
+    site_seeds: Vec<[u8; 32]>,
 }
 
 // Change this to get rid of particularly horrid seeds
@@ -67,9 +71,14 @@ pub struct GenCtx<'a, R: Rng> {
 }
 
 impl<'a, R: Rng> GenCtx<'a, R> {
-    pub fn reseed(&mut self) -> GenCtx<'_, impl Rng> {
+    fn reseed_entropy(&mut self) -> [u8; 32] {
         let mut entropy = self.rng.gen::<[u8; 32]>();
         entropy[0] = entropy[0].wrapping_add(SEED_SKIP); // Skip bad seeds
+        entropy
+    }
+
+    pub fn reseed(&mut self) -> GenCtx<'_, impl Rng> {
+        let entropy = self.reseed_entropy();
         GenCtx {
             sim: self.sim,
             rng: ChaChaRng::from_seed(entropy),
@@ -85,8 +94,12 @@ impl Civs {
         let mut ctx = GenCtx { sim, rng };
 
         // TODO: Care about world size when generating caves.
-        for _ in 0..100 {
-            this.generate_cave(&mut ctx);
+        // Each layer of the cave network runs deeper than the last but has fewer
+        // tunnels. Every tunnel comes up to the surface at both of its ends.
+        for layer in 0..CAVE_LAYERS {
+            for _ in 0..100 >> layer {
+                this.generate_cave(&mut ctx, layer);
+            }
         }
 
         for _ in 0..initial_civ_count {
@@ -191,24 +204,11 @@ impl Civs {
         let mut cnt = 0;
         for sim_site in this.sites.values() {
             cnt += 1;
-            let wpos = sim_site
-                .center
-                .map2(TerrainChunkSize::RECT_SIZE, |e, sz: u32| {
-                    e * sz as i32 + sz as i32 / 2
-                });
-
-            let mut rng = ctx.reseed().rng;
-            let site = index.sites.insert(match &sim_site.kind {
-                SiteKind::Settlement => {
-                    WorldSite::settlement(Settlement::generate(wpos, Some(ctx.sim), &mut rng))
-                },
-                SiteKind::Dungeon => {
-                    WorldSite::dungeon(Dungeon::generate(wpos, Some(ctx.sim), &mut rng))
-                },
-                SiteKind::Castle => {
-                    WorldSite::castle(Castle::generate(wpos, Some(ctx.sim), &mut rng))
-                },
-            });
+            let site_seed = ctx.reseed_entropy();
+            this.site_seeds.push(site_seed);
+            let site = index
+                .sites
+                .insert(sim_site.generate_world_site(ctx.sim, site_seed));
             let site_ref = &index.sites[site];
 
             let radius_chunks =
@@ -229,7 +229,7 @@ impl Civs {
     }
 
     // TODO: Move this
-    fn generate_cave(&self, ctx: &mut GenCtx<impl Rng>) {
+    fn generate_cave(&self, ctx: &mut GenCtx<impl Rng>, layer: usize) {
         let mut pos = ctx
             .sim
             .get_size()
@@ -270,28 +270,39 @@ impl Civs {
                 .expect("Track locations must be neighbors")
                 .0;
 
-            ctx.sim.get_mut(locs[0].0).unwrap().cave.0.neighbors |=
+            ctx.sim.get_mut(locs[0].0).unwrap().caves[layer].0.neighbors |=
                 1 << ((to_prev_idx as u8 + 4) % 8);
-            ctx.sim.get_mut(locs[1].0).unwrap().cave.0.neighbors |=
+            ctx.sim.get_mut(locs[1].0).unwrap().caves[layer].0.neighbors |=
                 (1 << (to_prev_idx as u8)) | (1 << (to_next_idx as u8));
-            ctx.sim.get_mut(locs[2].0).unwrap().cave.0.neighbors |=
+            ctx.sim.get_mut(locs[2].0).unwrap().caves[layer].0.neighbors |=
                 1 << ((to_next_idx as u8 + 4) % 8);
         }
 
         for loc in path.iter() {
-            let mut chunk = ctx.sim.get_mut(loc.0).unwrap();
-            let depth = loc.1 * 250.0 - 20.0;
-            chunk.cave.1.alt =
+            let chunk = ctx.sim.get_mut(loc.0).unwrap();
+            let (way, cave) = &mut chunk.caves[layer];
+            let depth = loc.1 * (250.0 + 150.0 * layer as f32) - 20.0;
+            cave.alt =
                 chunk.alt - depth + ctx.rng.gen_range(-4.0, 4.0) * (depth > 10.0) as i32 as f32;
-            chunk.cave.1.width = ctx.rng.gen_range(6.0, 32.0);
-            chunk.cave.0.offset = Vec2::new(ctx.rng.gen_range(-16, 17), ctx.rng.gen_range(-16, 17));
+            cave.width = ctx.rng.gen_range(6.0, 32.0);
+            way.offset = Vec2::new(ctx.rng.gen_range(-16, 17), ctx.rng.gen_range(-16, 17));
 
-            if chunk.cave.1.alt + chunk.cave.1.width + 5.0 > chunk.alt {
+            if cave.alt + cave.width + 5.0 > chunk.alt {
                 chunk.spawn_rate = 0.0;
             }
         }
     }
 
+    /// Lays out the world sites of civilisations loaded from a map again.  The
+    /// chunks were saved along with them, so they already refer to the sites.
+    pub fn place_loaded_sites(&self, sim: &mut WorldSim, index: &mut Index) {
+        for (sim_site, site_seed) in self.sites.values().zip(&self.site_seeds) {
+            index
+                .sites
+                .insert(sim_site.generate_world_site(sim, *site_seed));
+        }
+    }
+
     pub fn place(&self, id: Id<Place>) -> &Place { self.places.get(id) }
 
     pub fn sites(&self) -> impl Iterator<Item = &Site> + '_ { self.sites.values() }
@@ -736,20 +747,20 @@ fn find_site_loc(
     None
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Civ {
     capital: Id<Site>,
     homeland: Id<Place>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Place {
     center: Vec2<i32>,
     nat_res: NaturalResources,
 }
 
 // Productive capacity per year
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize, Deserialize)]
 pub struct NaturalResources {
     wood: f32,
     rock: f32,
@@ -785,6 +796,7 @@ impl NaturalResources {
     }
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct Track {
     /// Cost of using this track relative to other paths. This cost is an
     /// arbitrary unit and doesn't make sense unless compared to other track
@@ -793,7 +805,7 @@ pub struct Track {
     path: Path<Vec2<i32>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Site {
     pub kind: SiteKind,
     pub center: Vec2<i32>,
@@ -858,7 +870,7 @@ impl fmt::Display for Site {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub enum SiteKind {
     Settlement,
     Dungeon,
@@ -866,6 +878,21 @@ pub enum SiteKind {
 }
 
 impl Site {
+    fn generate_world_site(&self, sim: &mut WorldSim, site_seed: [u8; 32]) -> WorldSite {
+        let wpos = self.center.map2(TerrainChunkSize::RECT_SIZE, |e, sz: u32| {
+            e * sz as i32 + sz as i32 / 2
+        });
+
+        let mut rng = ChaChaRng::from_seed(site_seed);
+        match &self.kind {
+            SiteKind::Settlement => {
+                WorldSite::settlement(Settlement::generate(wpos, Some(sim), &mut rng))
+            },
+            SiteKind::Dungeon => WorldSite::dungeon(Dungeon::generate(wpos, Some(sim), &mut rng)),
+            SiteKind::Castle => WorldSite::castle(Castle::generate(wpos, Some(sim), &mut rng)),
+        }
+    }
+
     #[allow(clippy::let_and_return)] // TODO: Pending review in #587
     pub fn simulate(&mut self, years: f32, nat_res: &NaturalResources) {
         // Insert natural resources into the economy
@@ -1044,7 +1071,7 @@ impl Site {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 enum Occupation {
     Farmer = 0,
     Lumberjack = 1,
@@ -1055,7 +1082,7 @@ enum Occupation {
 }
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum Stock {
     Wheat = 0,
     Flour = 1,
@@ -1,7 +1,7 @@
 use crate::{
     all::ForestKind,
     block::StructureMeta,
-    sim::{local_cells, Cave, Path, RiverKind, SimChunk, WorldSim},
+    sim::{local_cells, Cave, Path, RiverKind, SimChunk, WorldSim, CAVE_LAYERS},
     util::Sampler,
     IndexRef, CONFIG,
 };
@@ -50,6 +50,10 @@ pub struct Colors {
 
     pub grass_high: (f32, f32, f32),
     pub tropical_high: (f32, f32, f32),
+
+    pub jungle_floor: (f32, f32, f32),
+    pub savannah_grass: (f32, f32, f32),
+    pub taiga_moss: (f32, f32, f32),
 }
 
 impl<'a> ColumnGen<'a> {
@@ -812,6 +816,9 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             warm_stone_high,
             grass_high,
             tropical_high,
+            jungle_floor,
+            savannah_grass,
+            taiga_moss,
         } = index.colors.column;
 
         let cold_grass = cold_grass.into();
@@ -831,6 +838,9 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
         let warm_stone_high = warm_stone_high.into();
         let grass_high = grass_high.into();
         let tropical_high = tropical_high.into();
+        let jungle_floor: Rgb<f32> = jungle_floor.into();
+        let savannah_grass: Rgb<f32> = savannah_grass.into();
+        let taiga_moss: Rgb<f32> = taiga_moss.into();
 
         let dirt = Lerp::lerp(dirt_low, dirt_high, marble);
         let tundra = Lerp::lerp(snow, snow_high, 0.4 + marble * 0.6);
@@ -980,6 +990,40 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             humidity.sub(CONFIG.jungle_hum).mul(1.0),
         );
 
+        // Jungles, savannahs and taigas (see `SimChunk::get_biome`) get their own
+        // ground, faded in at the edges of their climate so that there are no seams.
+        let tropical_factor = temp
+            .sub(CONFIG.tropical_temp)
+            .min(CONFIG.desert_temp.sub(temp))
+            .mul(8.0)
+            .clamped(0.0, 1.0);
+        let jungle_factor =
+            humidity.sub(CONFIG.forest_hum).mul(8.0).clamped(0.0, 1.0) * tropical_factor;
+        let savannah_factor =
+            CONFIG.forest_hum.sub(humidity).mul(8.0).clamped(0.0, 1.0) * tropical_factor;
+        let taiga_factor = CONFIG
+            .temperate_temp
+            .sub(temp)
+            .min(temp.sub(CONFIG.snow_temp))
+            .mul(8.0)
+            .clamped(0.0, 1.0)
+            * humidity.sub(CONFIG.desert_hum).mul(8.0).clamped(0.0, 1.0);
+        let ground = Rgb::lerp(
+            ground,
+            Rgb::lerp(jungle_floor, rainforest, marble.powf(1.5)),
+            jungle_factor * 0.8,
+        );
+        let ground = Rgb::lerp(
+            ground,
+            Rgb::lerp(savannah_grass, sand, marble_small * 0.5),
+            savannah_factor * 0.8,
+        );
+        let ground = Rgb::lerp(
+            ground,
+            Rgb::lerp(taiga_moss, moss, marble.powf(1.5)),
+            taiga_factor * 0.8,
+        );
+
         // Snow covering
         let snow_cover = temp
             .sub(CONFIG.snow_temp)
@@ -1019,7 +1063,10 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
         };
 
         let path = sim.get_nearest_path(wpos);
-        let cave = sim.get_nearest_cave(wpos);
+        let mut caves = [None; CAVE_LAYERS];
+        for (layer, cave) in caves.iter_mut().enumerate() {
+            *cave = sim.get_nearest_cave(wpos, layer);
+        }
 
         Some(ColumnSample {
             alt,
@@ -1067,7 +1114,7 @@ impl<'a> Sampler<'a> for ColumnGen<'a> {
             stone_col,
             water_dist,
             path,
-            cave,
+            caves,
 
             chunk: sim_chunk,
         })
@@ -1100,7 +1147,8 @@ pub struct ColumnSample<'a> {
     pub stone_col: Rgb<u8>,
     pub water_dist: Option<f32>,
     pub path: Option<(f32, Vec2<f32>, Path, Vec2<f32>)>,
-    pub cave: Option<(f32, Vec2<f32>, Cave, Vec2<f32>)>,
+    /// Nearest cave tunnel in each of the depth layers.
+    pub caves: [Option<(f32, Vec2<f32>, Cave, Vec2<f32>)>; CAVE_LAYERS],
 
     pub chunk: &'a SimChunk,
 }
@@ -20,7 +20,7 @@ use rand::prelude::*;
 use serde::Deserialize;
 use std::{
     f32,
-    ops::{Mul, Sub},
+    ops::{Add, Mul, Sub},
 };
 use vek::*;
 
@@ -28,6 +28,10 @@ use vek::*;
 pub struct Colors {
     pub bridge: (u8, u8, u8),
     pub stalagtite: (u8, u8, u8),
+    pub cave_moss: (u8, u8, u8),
+    pub copper_ore: (u8, u8, u8),
+    pub iron_ore: (u8, u8, u8),
+    pub gold_ore: (u8, u8, u8),
 }
 
 const EMPTY_AIR: Block = Block::air(SpriteKind::Empty);
@@ -123,6 +127,72 @@ pub fn apply_paths_to<'a>(
     }
 }
 
+/// How far into the rock around a cave ore veins can be found.
+const ORE_VEIN_REACH: f32 = 4.0;
+
+/// The kind of underground area that a cave passes through.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum CaveBiome {
+    Rock,
+    /// Low points of the tunnel are flooded.
+    Lake,
+    /// Moss-covered floors dotted with glowing mushrooms, only found deep down.
+    Mushroom,
+}
+
+impl CaveBiome {
+    fn at(index: IndexRef, wpos2d: Vec2<i32>, cave_depth: f32) -> Self {
+        let nz = index
+            .noise
+            .cave_nz
+            .get((wpos2d.map(|e| e as f64) * 0.004 + 1000.0).into_array());
+        if nz > 0.3 && cave_depth > 60.0 {
+            CaveBiome::Mushroom
+        } else if nz < -0.35 {
+            CaveBiome::Lake
+        } else {
+            CaveBiome::Rock
+        }
+    }
+}
+
+/// Choose the ore, if any, that makes up the rock at the given position.
+/// Rarer ores are only found further below the surface.
+fn ore_vein_at(index: IndexRef, wpos: Vec3<i32>, depth: f32) -> Option<Block> {
+    let vein = index
+        .noise
+        .ore_nz
+        .get(wpos.map(|e| e as f64 * 0.08).into_array());
+    if vein < 0.55 {
+        return None;
+    }
+
+    let colors = &index.colors.layer;
+    let ores = [
+        (15.0, BlockKind::CopperOre, colors.copper_ore),
+        (60.0, BlockKind::IronOre, colors.iron_ore),
+        (150.0, BlockKind::GoldOre, colors.gold_ore),
+    ];
+    let available = ores
+        .iter()
+        .take_while(|(min_depth, _, _)| depth > *min_depth)
+        .count();
+    if available == 0 {
+        return None;
+    }
+
+    // Neighbouring veins are usually of the same ore
+    let selector = index
+        .noise
+        .ore_nz
+        .get((wpos.map(|e| e as f64) * 0.01 + 1000.0).into_array())
+        .mul(0.5)
+        .add(0.5)
+        .clamped(0.0, 0.999);
+    let (_, kind, color) = ores[(selector * available as f64) as usize];
+    Some(Block::new(kind, color.into()))
+}
+
 pub fn apply_caves_to<'a>(
     wpos2d: Vec2<i32>,
     mut get_column: impl FnMut(Vec2<i32>) -> Option<&'a ColumnSample<'a>>,
@@ -143,8 +213,11 @@ pub fn apply_caves_to<'a>(
             };
             let surface_z = col_sample.riverless_alt.floor() as i32;
 
-            if let Some((cave_dist, _, cave, _)) = col_sample
-                .cave
+            for (cave_dist, _, cave, _) in col_sample
+                .caves
+                .iter()
+                .flatten()
+                .copied()
                 .filter(|(dist, _, cave, _)| *dist < cave.width)
             {
                 let cave_x = (cave_dist / cave.width).min(1.0);
@@ -193,6 +266,34 @@ pub fn apply_caves_to<'a>(
                 let cave_depth = (col_sample.alt - cave.alt).max(0.0);
                 let difficulty = cave_depth / 100.0;
 
+                match CaveBiome::at(index, wpos2d, cave_depth) {
+                    CaveBiome::Lake => {
+                        // Fill the lowest part of the tunnel with water
+                        let water_level = (cave.alt - cave.width * 0.25) as i32;
+                        for z in cave_base..water_level.min(cave_roof) {
+                            let _ = vol.set(
+                                Vec3::new(offs.x, offs.y, z),
+                                Block::water(SpriteKind::Empty),
+                            );
+                        }
+                    },
+                    CaveBiome::Mushroom => {
+                        let _ = vol.map(Vec3::new(offs.x, offs.y, cave_base - 1), |block| {
+                            if block.is_filled() {
+                                Block::new(block.kind(), index.colors.layer.cave_moss.into())
+                            } else {
+                                block
+                            }
+                        });
+                        if RandomField::new(index.seed + 2).chance(wpos2d.into(), 0.05) {
+                            let _ = vol.map(Vec3::new(offs.x, offs.y, cave_base), |block| {
+                                block.with_sprite(SpriteKind::GlowMushroom)
+                            });
+                        }
+                    },
+                    CaveBiome::Rock => {},
+                }
+
                 // Scatter things in caves
                 if RandomField::new(index.seed).chance(wpos2d.into(), 0.001 * difficulty.powf(1.5))
                     && cave_base < surface_z as i32 - 25
@@ -200,10 +301,39 @@ pub fn apply_caves_to<'a>(
                     let kind = *Lottery::<SpriteKind>::load_expect("common.cave_scatter")
                         .choose_seeded(RandomField::new(index.seed + 1).get(wpos2d.into()));
                     let _ = vol.map(Vec3::new(offs.x, offs.y, cave_base), |block| {
-                        block.with_sprite(kind)
+                        // Don't scatter things underwater
+                        if block.kind() == BlockKind::Air {
+                            block.with_sprite(kind)
+                        } else {
+                            block
+                        }
                     });
                 }
             }
+
+            // Ore veins in the rock around caves
+            for (_, _, cave, _) in col_sample
+                .caves
+                .iter()
+                .flatten()
+                .copied()
+                .filter(|(dist, _, cave, _)| *dist < cave.width + ORE_VEIN_REACH)
+            {
+                let min_z = (cave.alt - cave.width * 0.5 - ORE_VEIN_REACH) as i32;
+                let max_z = (cave.alt + cave.width + ORE_VEIN_REACH) as i32;
+                for z in min_z..max_z {
+                    let pos = Vec3::new(offs.x, offs.y, z);
+                    if vol
+                        .get(pos)
+                        .map_or(false, |block| block.kind() == BlockKind::Rock)
+                    {
+                        let wpos = Vec3::new(wpos2d.x, wpos2d.y, z);
+                        if let Some(ore) = ore_vein_at(index, wpos, col_sample.alt - z as f32) {
+                            let _ = vol.set(pos, ore);
+                        }
+                    }
+                }
+            }
         }
     }
 }
@@ -231,8 +361,11 @@ pub fn apply_caves_supplement<'a>(
             };
             let surface_z = col_sample.riverless_alt.floor() as i32;
 
-            if let Some((cave_dist, _, cave, _)) = col_sample
-                .cave
+            for (cave_dist, _, cave, _) in col_sample
+                .caves
+                .iter()
+                .flatten()
+                .copied()
                 .filter(|(dist, _, cave, _)| *dist < cave.width)
             {
                 let cave_x = (cave_dist / cave.width).min(1.0);
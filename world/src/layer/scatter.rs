@@ -11,6 +11,8 @@ fn close(x: f32, tgt: f32, falloff: f32) -> f32 {
     (1.0 - (x - tgt).abs() / falloff).max(0.0).powf(0.125)
 }
 const MUSH_FACT: f32 = 1.0e-4; // To balance everything around the mushroom spawning rate
+const SAVANNAH_HUM: f32 = (CONFIG.desert_hum + CONFIG.forest_hum) * 0.5;
+const TAIGA_TEMP: f32 = (CONFIG.snow_temp + CONFIG.temperate_temp) * 0.5;
 pub fn apply_scatter_to<'a>(
     wpos2d: Vec2<i32>,
     mut get_column: impl FnMut(Vec2<i32>) -> Option<&'a ColumnSample<'a>>,
@@ -151,6 +153,93 @@ pub fn apply_scatter_to<'a>(
                 None,
             )
         }),
+        // Jungle undergrowth
+        (LargeGrass, false, |c, _| {
+            (
+                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3).min(close(
+                    c.humidity,
+                    CONFIG.jungle_hum,
+                    0.35,
+                )) * 0.04,
+                Some((60.0, 0.3)),
+            )
+        }),
+        (LeafyPlant, false, |c, col| {
+            (
+                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3).min(close(
+                    c.humidity,
+                    CONFIG.jungle_hum,
+                    0.35,
+                )) * col.tree_density
+                    * 0.01,
+                Some((32.0, 0.4)),
+            )
+        }),
+        (Fern, false, |c, _| {
+            (
+                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3).min(close(
+                    c.humidity,
+                    CONFIG.jungle_hum,
+                    0.35,
+                )) * 0.008,
+                None,
+            )
+        }),
+        // Savannah grass and shrubs
+        (LongGrass, false, |c, _| {
+            (
+                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3).min(close(
+                    c.humidity,
+                    SAVANNAH_HUM,
+                    0.2,
+                )) * 0.08,
+                Some((48.0, 0.3)),
+            )
+        }),
+        (DeadBush, false, |c, _| {
+            (
+                close(c.temp, CONFIG.tropical_temp + 0.2, 0.3).min(close(
+                    c.humidity,
+                    SAVANNAH_HUM,
+                    0.2,
+                )) * MUSH_FACT
+                    * 20.0,
+                None,
+            )
+        }),
+        // Taiga berries, mushrooms and ferns
+        (Blueberry, false, |c, _| {
+            (
+                close(c.temp, TAIGA_TEMP, 0.25).min(close(c.humidity, CONFIG.forest_hum, 0.4))
+                    * MUSH_FACT
+                    * 6.0,
+                Some((64.0, 0.3)),
+            )
+        }),
+        (LingonBerry, false, |c, _| {
+            (
+                close(c.temp, TAIGA_TEMP, 0.25).min(close(c.humidity, CONFIG.forest_hum, 0.4))
+                    * MUSH_FACT
+                    * 4.0,
+                None,
+            )
+        }),
+        (Mushroom, false, |c, col| {
+            (
+                close(c.temp, TAIGA_TEMP, 0.25).min(close(c.humidity, CONFIG.forest_hum, 0.4))
+                    * col.tree_density
+                    * MUSH_FACT
+                    * 3.0,
+                None,
+            )
+        }),
+        (Fern, false, |c, _| {
+            (
+                close(c.temp, TAIGA_TEMP, 0.25).min(close(c.humidity, CONFIG.forest_hum, 0.4))
+                    * 0.004,
+                Some((48.0, 0.3)),
+            )
+        }),
         // Grass
         (ShortGrass, false, |c, _| {
             (
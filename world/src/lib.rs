@@ -39,10 +39,12 @@ use common::{
     comp::{self, bird_medium, quadruped_low, quadruped_medium, quadruped_small},
     generation::{ChunkSupplement, EntityInfo},
     msg::WorldMapMsg,
-    terrain::{Block, BlockKind, SpriteKind, TerrainChunk, TerrainChunkMeta, TerrainChunkSize},
+    terrain::{
+        BiomeKind, Block, BlockKind, SpriteKind, TerrainChunk, TerrainChunkMeta, TerrainChunkSize,
+    },
     vol::{ReadVol, RectVolSize, WriteVol},
 };
-use rand::Rng;
+use rand::{seq::SliceRandom, Rng};
 use serde::Deserialize;
 use std::time::Duration;
 use vek::*;
@@ -71,10 +73,29 @@ impl World {
         // NOTE: Generating index first in order to quickly fail if the color manifest
         // is broken.
         let (mut index, colors) = Index::new(seed);
-        let mut sim = sim::WorldSim::generate(seed, opts);
-        let civs = civ::Civs::generate(seed, &mut sim, &mut index);
+        let world_file = opts.world_file.clone();
+        let (mut sim, mut map) = sim::WorldSim::generate(seed, opts);
+        let civs = if let Some(snapshot) = map.as_mut().and_then(|map| map.snapshot.take()) {
+            // The snapshot already went through the history simulation, so only the
+            // sites have to be laid out again.
+            snapshot.civs.place_loaded_sites(&mut sim, &mut index);
+            for (site, economy) in index.sites.values_mut().zip(snapshot.economies) {
+                site.economy = economy;
+            }
+            index.time = snapshot.time;
+            snapshot.civs
+        } else {
+            let civs = civ::Civs::generate(seed, &mut sim, &mut index);
+
+            sim2::simulate(&mut index, &mut sim);
 
-        sim2::simulate(&mut index, &mut sim);
+            // Without a snapshot, the map is only returned if it should be saved.
+            if let Some(map) = map {
+                sim.save_with_snapshot(map, civs, &index, &world_file)
+            } else {
+                civs
+            }
+        };
 
         (Self { sim, civs }, IndexOwned::new(index, colors))
     }
@@ -237,46 +258,61 @@ impl World {
                 let is_giant = dynamic_rng.gen_range(0, 8) == 0;
                 let quadmed = comp::Body::QuadrupedMedium(quadruped_medium::Body::random()); // Not all of them are hostile so we have to do the rng here
                 let quadlow = comp::Body::QuadrupedLow(quadruped_low::Body::random()); // Not all of them are hostile so we have to do the rng here
+                let native_creature = biome_creature(sim_chunk.get_biome(), &mut dynamic_rng);
                 let entity = EntityInfo::at(gen_entity_pos(&mut dynamic_rng))
                     .do_if(is_giant, |e| e.into_giant())
-                    .with_body(match dynamic_rng.gen_range(0, 5) {
-                        0 => {
-                            match quadmed {
-                                comp::Body::QuadrupedMedium(quadruped_medium) => {
-                                    match quadruped_medium.species {
-                                        quadruped_medium::Species::Catoblepas => is_hostile = false,
-                                        quadruped_medium::Species::Mouflon => is_hostile = false,
-                                        quadruped_medium::Species::Tuskram => is_hostile = false,
-                                        quadruped_medium::Species::Deer => is_hostile = false,
-                                        quadruped_medium::Species::Hirdrasil => is_hostile = false,
-                                        _ => is_hostile = true,
-                                    }
-                                },
-                                _ => is_hostile = true,
-                            };
-                            quadmed
-                        },
-                        1 => {
-                            is_hostile = false;
-                            comp::Body::BirdMedium(bird_medium::Body::random())
+                    .with_body(match native_creature {
+                        Some((body, hostile)) => {
+                            is_hostile = hostile;
+                            body
                         },
-                        2 => {
-                            match quadlow {
-                                comp::Body::QuadrupedLow(quadruped_low) => {
-                                    match quadruped_low.species {
-                                        quadruped_low::Species::Crocodile => is_hostile = true,
-                                        quadruped_low::Species::Alligator => is_hostile = true,
-                                        quadruped_low::Species::Maneater => is_hostile = true,
-                                        _ => is_hostile = false,
-                                    }
-                                },
-                                _ => is_hostile = false,
-                            };
-                            quadlow
-                        },
-                        _ => {
-                            is_hostile = false;
-                            comp::Body::QuadrupedSmall(quadruped_small::Body::random())
+                        None => match dynamic_rng.gen_range(0, 5) {
+                            0 => {
+                                match quadmed {
+                                    comp::Body::QuadrupedMedium(quadruped_medium) => {
+                                        match quadruped_medium.species {
+                                            quadruped_medium::Species::Catoblepas => {
+                                                is_hostile = false
+                                            },
+                                            quadruped_medium::Species::Mouflon => {
+                                                is_hostile = false
+                                            },
+                                            quadruped_medium::Species::Tuskram => {
+                                                is_hostile = false
+                                            },
+                                            quadruped_medium::Species::Deer => is_hostile = false,
+                                            quadruped_medium::Species::Hirdrasil => {
+                                                is_hostile = false
+                                            },
+                                            _ => is_hostile = true,
+                                        }
+                                    },
+                                    _ => is_hostile = true,
+                                };
+                                quadmed
+                            },
+                            1 => {
+                                is_hostile = false;
+                                comp::Body::BirdMedium(bird_medium::Body::random())
+                            },
+                            2 => {
+                                match quadlow {
+                                    comp::Body::QuadrupedLow(quadruped_low) => {
+                                        match quadruped_low.species {
+                                            quadruped_low::Species::Crocodile => is_hostile = true,
+                                            quadruped_low::Species::Alligator => is_hostile = true,
+                                            quadruped_low::Species::Maneater => is_hostile = true,
+                                            _ => is_hostile = false,
+                                        }
+                                    },
+                                    _ => is_hostile = false,
+                                };
+                                quadlow
+                            },
+                            _ => {
+                                is_hostile = false;
+                                comp::Body::QuadrupedSmall(quadruped_small::Body::random())
+                            },
                         },
                     })
                     .with_alignment(if is_hostile {
@@ -324,3 +360,86 @@ impl World {
         Ok((chunk, supplement))
     }
 }
+
+/// Picks a creature native to the given biome, along with whether it should be
+/// hostile. Returns `None` for biomes without a dedicated spawn table (and
+/// occasionally for biomes with one), in which case the generic spawn table is
+/// used instead.
+fn biome_creature<R: Rng>(biome: BiomeKind, rng: &mut R) -> Option<(comp::Body, bool)> {
+    use bird_medium::Species as Bird;
+    use quadruped_low::Species as QuadLow;
+    use quadruped_medium::Species as QuadMed;
+    use quadruped_small::Species as QuadSmall;
+
+    // Let the generic spawn table through every so often so that biomes don't
+    // feel completely uniform.
+    if rng.gen_range(0, 4) == 0 {
+        return None;
+    }
+
+    let quadmed = |rng: &mut R, species: &[(QuadMed, bool)]| {
+        species.choose(rng).map(|(s, hostile)| {
+            (
+                comp::Body::QuadrupedMedium(quadruped_medium::Body::random_with(rng, s)),
+                *hostile,
+            )
+        })
+    };
+    let quadlow = |rng: &mut R, species: &[QuadLow]| {
+        species.choose(rng).map(|s| {
+            (
+                comp::Body::QuadrupedLow(quadruped_low::Body::random_with(rng, s)),
+                true,
+            )
+        })
+    };
+    let quadsmall = |rng: &mut R, species: &[QuadSmall]| {
+        species.choose(rng).map(|s| {
+            (
+                comp::Body::QuadrupedSmall(quadruped_small::Body::random_with(rng, s)),
+                false,
+            )
+        })
+    };
+    let bird = |rng: &mut R, species: &[Bird]| {
+        species.choose(rng).map(|s| {
+            (
+                comp::Body::BirdMedium(bird_medium::Body::random_with(rng, s)),
+                false,
+            )
+        })
+    };
+
+    match biome {
+        BiomeKind::Jungle => match rng.gen_range(0, 4) {
+            0 => quadmed(rng, &[(QuadMed::Tiger, true)]),
+            1 => quadlow(rng, &[QuadLow::Crocodile, QuadLow::Asp, QuadLow::Monitor]),
+            2 => bird(rng, &[Bird::Parrot, Bird::Peacock]),
+            _ => quadsmall(rng, &[
+                QuadSmall::Frog,
+                QuadSmall::Gecko,
+                QuadSmall::Axolotl,
+            ]),
+        },
+        BiomeKind::Savannah => match rng.gen_range(0, 4) {
+            0 => quadmed(rng, &[(QuadMed::Lion, true)]),
+            1 => quadmed(rng, &[
+                (QuadMed::Catoblepas, false),
+                (QuadMed::Tuskram, false),
+            ]),
+            2 => bird(rng, &[Bird::Eagle]),
+            _ => quadsmall(rng, &[QuadSmall::Hyena]),
+        },
+        BiomeKind::Taiga => match rng.gen_range(0, 4) {
+            0 => quadmed(rng, &[(QuadMed::Wolf, true), (QuadMed::Frostfang, true)]),
+            1 => quadmed(rng, &[(QuadMed::Deer, false), (QuadMed::Hirdrasil, false)]),
+            2 => bird(rng, &[Bird::Snowyowl]),
+            _ => quadsmall(rng, &[
+                QuadSmall::Fox,
+                QuadSmall::Squirrel,
+                QuadSmall::Beaver,
+            ]),
+        },
+        _ => None,
+    }
+}
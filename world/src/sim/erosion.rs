@@ -15,6 +15,7 @@ use num::{Float, Zero};
 use ordered_float::NotNan;
 use packed_simd::m32;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::{Ordering, Reverse},
     collections::BinaryHeap,
@@ -97,7 +98,7 @@ pub fn get_multi_drainage(
 }
 
 /// Kind of water on this tile.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum RiverKind {
     Ocean,
     Lake {
@@ -149,7 +150,7 @@ impl PartialOrd for RiverKind {
 /// this to rendering time.
 ///
 /// NOTE: This structure is 57 (or more likely 64) bytes, which is kind of big.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct RiverData {
     /// A velocity vector (in m / minute, i.e. voxels / second from a game
     /// perspective).
@@ -2,9 +2,10 @@ use core::hash::BuildHasherDefault;
 use fxhash::FxHasher64;
 use hashbrown::HashSet;
 use rand::{seq::SliceRandom, Rng};
+use serde::{Deserialize, Serialize};
 use vek::*;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Location {
     pub(crate) name: String,
     pub(crate) center: Vec2<i32>,
@@ -31,7 +32,7 @@ impl Location {
     pub fn kingdom(&self) -> Option<&Kingdom> { self.kingdom.as_ref() }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Kingdom {
     region_name: String,
 }
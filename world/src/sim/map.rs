@@ -118,7 +118,7 @@ pub fn sample_pos(
                 sample.river.river_kind,
                 sample.river.spline_derivative,
                 sample.path.0.is_way(),
-                sample.cave.0.is_way(),
+                sample.caves.iter().any(|(way, _)| way.is_way()),
                 sample.sites.iter().any(|site| {
                     index.sites[*site]
                         .get_origin()
@@ -25,11 +25,11 @@ pub use self::{
 use crate::{
     all::ForestKind,
     block::BlockGen,
-    civ::Place,
+    civ::{Civs, Place},
     column::ColumnGen,
-    site::Site,
+    site::{Economy, Site},
     util::{seed_expan, FastNoise, RandomField, Sampler, StructureGen2d, LOCALITY, NEIGHBORS},
-    IndexRef, CONFIG,
+    Index, IndexRef, CONFIG,
 };
 use common::{
     assets,
@@ -54,10 +54,11 @@ use std::{
     f32, f64,
     fs::File,
     io::{BufReader, BufWriter},
+    mem,
     ops::{Add, Div, Mul, Neg, Sub},
     path::PathBuf,
 };
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 use vek::*;
 
 /// Default base two logarithm of the world size, in chunks, per dimension.
@@ -72,6 +73,11 @@ const DEFAULT_WORLD_CHUNKS_LG: MapSizeLg =
         panic!("Default world chunk size does not satisfy required invariants.");
     };
 
+/// Number of depth layers the cave network is spread over.  Every chunk keeps
+/// the tunnel of each layer separately, so that tunnels crossing at different
+/// depths don't overwrite each other.
+pub const CAVE_LAYERS: usize = 3;
+
 /// A structure that holds cached noise values and cumulative distribution
 /// functions for the input that led to those values.  See the definition of
 /// InverseCdf for a description of how to interpret the types of its fields.
@@ -129,18 +135,29 @@ pub enum FileOpts {
     /// file (default).
     Generate,
     /// If set, generate the world map and save the world file (path is created
-    /// the same way screenshot paths are).
+    /// the same way screenshot paths are).  The file includes a snapshot of the
+    /// generated sites and economies, so loading it skips straight to placing
+    /// the sites.
     Save,
     /// If set, load the world file from this path in legacy format (errors if
     /// path not found).  This option may be removed at some point, since it
     /// only applies to maps generated before map saving was merged into
-    /// master.
+    /// master.  The map is converted to the latest format (with a snapshot)
+    /// and saved as a new world file, which should be loaded with `Load` from
+    /// then on.
     LoadLegacy(PathBuf),
     /// If set, load the world file from this path (errors if path not found).
+    /// A map without a valid snapshot is written back to the same path with
+    /// one once the world has been generated, so only the first load is slow.
     Load(PathBuf),
     /// If set, look for  the world file at this asset specifier (errors if
     /// asset is not found).
     ///
+    /// NOTE: Assets are never written to, so a map asset without a valid
+    /// snapshot has all of its sites and economies generated again on every
+    /// load.  Regenerate the asset (for instance by loading it once with
+    /// `Load` and copying the file back) to avoid that.
+    ///
     /// NOTE: Could stand to merge this with `Load` and construct an enum that
     /// can handle either a PathBuf or an asset specifier, at some point.
     LoadAsset(String),
@@ -150,10 +167,38 @@ impl Default for FileOpts {
     fn default() -> Self { Self::Generate }
 }
 
+/// Parameters for generating a fresh world map.  These are only used when no
+/// world file is loaded; a loaded map always keeps its saved size and scale.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GenOpts {
+    /// Base two logarithm of the world size, in chunks, per dimension.  The
+    /// two dimensions do not have to be equal.
+    pub map_size_lg: Vec2<u32>,
+    /// Approximate fraction of the map (between 0 and 1) that should lie below
+    /// sea level.  If not set, the amount of ocean is left up to the noise.
+    pub sea_level_fraction: Option<f32>,
+    /// Horizontal scale of continents and erosion features relative to the
+    /// default world.  Maps from version 0.5.0 used 2^(map_size_lg - 10) to
+    /// keep the same look at larger sizes.
+    pub continent_scale: f64,
+}
+
+impl Default for GenOpts {
+    fn default() -> Self {
+        Self {
+            map_size_lg: DEFAULT_WORLD_CHUNKS_LG.vec(),
+            sea_level_fraction: None,
+            continent_scale: 1.0,
+        }
+    }
+}
+
 pub struct WorldOpts {
     /// Set to false to disable seeding elements during worldgen.
     pub seed_elements: bool,
     pub world_file: FileOpts,
+    pub gen_opts: GenOpts,
 }
 
 impl Default for WorldOpts {
@@ -161,6 +206,7 @@ impl Default for WorldOpts {
         Self {
             seed_elements: true,
             world_file: Default::default(),
+            gen_opts: Default::default(),
         }
     }
 }
@@ -202,6 +248,49 @@ pub struct WorldMap_0_7_0 {
     pub basement: Box<[Alt]>,
 }
 
+/// Version of the world map intended for use in Veloren 0.8.0.
+#[derive(Serialize, Deserialize)]
+#[repr(C)]
+pub struct WorldMap_0_8_0 {
+    /// Saved map size.
+    pub map_size_lg: Vec2<u32>,
+    /// Saved continent_scale hack, to try to better approximate the correct
+    /// seed according to varying map size.
+    ///
+    /// TODO: Remove when generating new maps becomes more principled.
+    pub continent_scale_hack: f64,
+    /// Saved altitude height map.
+    pub alt: Box<[Alt]>,
+    /// Saved basement height map.
+    pub basement: Box<[Alt]>,
+    /// Everything generated after erosion, if it was saved.  When present, the
+    /// height maps above are only kept so the map can still be regenerated
+    /// from them if the snapshot ever has to be thrown away.
+    pub snapshot: Option<WorldSnapshot>,
+}
+
+/// The state of the world after erosion post-processing, civilisation
+/// generation and the economy simulation, so that loading a map can skip all of
+/// them.
+///
+/// NOTE: Like the rest of the map, this is not invalidated when worldgen
+/// changes; regenerate the map if chunks, sites or economies look out of date.
+#[derive(Serialize, Deserialize)]
+pub struct WorldSnapshot {
+    pub(crate) max_height: f32,
+    /// Chunks, including rivers, climate, paths and the sites they contain.
+    pub(crate) chunks: Vec<SimChunk>,
+    pub(crate) locations: Vec<Location>,
+    /// Civilisations, including the seeds their sites are laid out from when
+    /// loading.
+    pub(crate) civs: Civs,
+    /// Economy of each of the world's sites after the history simulation, in
+    /// order.
+    pub(crate) economies: Vec<Economy>,
+    /// Index time after the history simulation.
+    pub(crate) time: f32,
+}
+
 /// Errors when converting a map to the most recent type (currently,
 /// shared by the various map types, but at some point we might switch to
 /// version-specific errors if it feels worthwhile).
@@ -241,11 +330,12 @@ pub enum WorldFileError {
 pub enum WorldFile {
     Veloren0_5_0(WorldMap_0_5_0) = 0,
     Veloren0_7_0(WorldMap_0_7_0) = 1,
+    Veloren0_8_0(WorldMap_0_8_0) = 2,
 }
 
 /// Data for the most recent map type.  Update this when you add a new map
 /// version.
-pub type ModernMap = WorldMap_0_7_0;
+pub type ModernMap = WorldMap_0_8_0;
 
 /// The default world map.
 ///
@@ -315,6 +405,37 @@ impl WorldMap_0_7_0 {
             return Err(WorldFileError::WorldSizeInvalid);
         }
 
+        let map = WorldMap_0_8_0 {
+            map_size_lg: self.map_size_lg,
+            continent_scale_hack: self.continent_scale_hack,
+            alt: self.alt,
+            basement: self.basement,
+            snapshot: None,
+        };
+
+        map.into_modern()
+    }
+}
+
+impl WorldMap_0_8_0 {
+    #[inline]
+    pub fn into_modern(mut self) -> Result<ModernMap, WorldFileError> {
+        let chunks_len = 1 << (self.map_size_lg.x + self.map_size_lg.y);
+        if self.alt.len() != self.basement.len()
+            || self.alt.len() != chunks_len
+            || self.continent_scale_hack <= 0.0
+        {
+            return Err(WorldFileError::WorldSizeInvalid);
+        }
+        if let Some(snapshot) = &self.snapshot {
+            if snapshot.chunks.len() != chunks_len
+                || snapshot.economies.len() != snapshot.civs.sites().count()
+            {
+                warn!("World snapshot does not match the map, regenerating from the height map.");
+                self.snapshot = None;
+            }
+        }
+
         Ok(self)
     }
 }
@@ -325,7 +446,7 @@ impl WorldFile {
     /// variant we construct here to make sure we're using the latest map
     /// version.
 
-    pub fn new(map: ModernMap) -> Self { WorldFile::Veloren0_7_0(map) }
+    pub fn new(map: ModernMap) -> Self { WorldFile::Veloren0_8_0(map) }
 
     #[inline]
     /// Turns a WorldFile into the latest version.  Whenever a new map version
@@ -334,6 +455,7 @@ impl WorldFile {
         match self {
             WorldFile::Veloren0_5_0(map) => map.into_modern(),
             WorldFile::Veloren0_7_0(map) => map.into_modern(),
+            WorldFile::Veloren0_8_0(map) => map.into_modern(),
         }
     }
 }
@@ -353,9 +475,13 @@ pub struct WorldSim {
 }
 
 impl WorldSim {
+    /// Generates the world, or loads it from the world file.  Besides the
+    /// world, this returns the map which still has to be handled by the
+    /// caller: the loaded map if it contained a snapshot (whose chunks and
+    /// locations have already been moved into the world), or the new map if
+    /// it is to be saved once the rest of the world has been generated.
     #[allow(clippy::unnested_or_patterns)] // TODO: Pending review in #587
-
-    pub fn generate(seed: u32, opts: WorldOpts) -> Self {
+    pub fn generate(seed: u32, opts: WorldOpts) -> (Self, Option<ModernMap>) {
         // Parse out the contents of various map formats into the values we need.
         let parsed_world_file = (|| {
             let map = match opts.world_file {
@@ -444,16 +570,31 @@ impl WorldSim {
             }
         })();
 
-        // NOTE: Change 1.0 to 4.0 for a 4x
-        // improvement in world detail.  We also use this to automatically adjust
-        // grid_scale (multiplying by 4.0) and multiply mins_per_sec by
-        // 1.0 / (4.0 * 4.0) in ./erosion.rs, in order to get a similar rate of river
-        // formation.
+        // NOTE: Set this to 4.0 for a 4x improvement in world detail.  We also use
+        // this to automatically adjust grid_scale (multiplying by 4.0) and multiply
+        // mins_per_sec by 1.0 / (4.0 * 4.0) in ./erosion.rs, in order to get a
+        // similar rate of river formation.
         //
         // FIXME: This is a hack!  At some point we will hae a more principled way of
         // dealing with this.
-        let continent_scale_hack = 1.0/*4.0*/;
-        let (parsed_world_file, map_size_lg) = parsed_world_file
+        let gen_opts = &opts.gen_opts;
+        let continent_scale_hack = if gen_opts.continent_scale > 0.0 {
+            gen_opts.continent_scale
+        } else {
+            warn!(
+                "Continent scale {} must be positive, using the default.",
+                gen_opts.continent_scale
+            );
+            1.0
+        };
+        let gen_map_size_lg = MapSizeLg::new(gen_opts.map_size_lg).unwrap_or_else(|e| {
+            warn!(
+                "World size {:?} does not satisfy invariants, using the default: {:?}",
+                gen_opts.map_size_lg, e
+            );
+            DEFAULT_WORLD_CHUNKS_LG
+        });
+        let (mut parsed_world_file, map_size_lg) = parsed_world_file
             .and_then(|map| match MapSizeLg::new(map.map_size_lg) {
                 Ok(map_size_lg) => Some((Some(map), map_size_lg)),
                 Err(e) => {
@@ -461,7 +602,7 @@ impl WorldSim {
                     None
                 },
             })
-            .unwrap_or((None, DEFAULT_WORLD_CHUNKS_LG));
+            .unwrap_or((None, gen_map_size_lg));
         let continent_scale_hack = if let Some(map) = &parsed_world_file {
             map.continent_scale_hack
         } else {
@@ -544,6 +685,41 @@ impl WorldSim {
                 .set_range_function(RangeFunction::Euclidean),
         };
 
+        // A snapshot contains everything that would be generated from here on, so
+        // only the noise above has to be recreated.
+        if let Some(snapshot) = parsed_world_file
+            .as_mut()
+            .and_then(|map| map.snapshot.as_mut())
+        {
+            let this = Self {
+                seed,
+                map_size_lg,
+                max_height: snapshot.max_height,
+                chunks: mem::take(&mut snapshot.chunks),
+                locations: mem::take(&mut snapshot.locations),
+                gen_ctx,
+                rng,
+            };
+            return (this, parsed_world_file);
+        }
+
+        // Loaded maps without a snapshot are saved again with one, except for assets.
+        let save_map = match opts.world_file {
+            FileOpts::Save => true,
+            FileOpts::Load(_) | FileOpts::LoadLegacy(_) => parsed_world_file.is_some(),
+            FileOpts::LoadAsset(ref specifier) => {
+                if parsed_world_file.is_some() {
+                    warn!(
+                        ?specifier,
+                        "Map asset has no valid snapshot, so sites have to be generated on every \
+                         load.  Consider regenerating the asset."
+                    );
+                }
+                false
+            },
+            FileOpts::Generate => false,
+        };
+
         let river_seed = &gen_ctx.river_seed;
         let rock_strength_nz = &gen_ctx.rock_strength_nz;
 
@@ -702,7 +878,7 @@ impl WorldSim {
         //
         // No NaNs in these uniform vectors, since the original noise value always
         // returns Some.
-        let (alt_old, _) = uniform_noise(map_size_lg, |posi, wposf| {
+        let (mut alt_old, _) = uniform_noise(map_size_lg, |posi, wposf| {
             // This is the extension upwards from the base added to some extra noise from -1
             // to 1.
             //
@@ -777,6 +953,19 @@ impl WorldSim {
             )
         });
 
+        // Shift the heights so that the requested fraction of the map ends up below
+        // sea level.  The edge of the map must stay underwater, so we never shift
+        // down by more than the sea level.
+        if let Some(sea_level_fraction) = gen_opts.sea_level_fraction {
+            let mut alts = alt_old.iter().map(|&(_, alt)| alt).collect::<Vec<_>>();
+            alts.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let idx = ((alts.len() as f32 * sea_level_fraction.max(0.0).min(1.0)) as usize)
+                .min(alts.len() - 1);
+            let min_shift = -CONFIG.sea_level / CONFIG.mountain_scale + f32::EPSILON;
+            let shift = alts[idx].max(min_shift);
+            alt_old.iter_mut().for_each(|(_, alt)| *alt -= shift);
+        }
+
         // Calculate oceans.
         let is_ocean = get_oceans(map_size_lg, |posi: usize| alt_old[posi].1);
         // NOTE: Uncomment if you want oceans to exclusively be on the border of the
@@ -1084,56 +1273,19 @@ impl WorldSim {
             )
         };
 
-        // Save map, if necessary.
-        // NOTE: We wll always save a map with latest version.
-        let map = WorldFile::new(ModernMap {
-            continent_scale_hack,
-            map_size_lg: map_size_lg.vec(),
-            alt,
-            basement,
-        });
-        (|| {
-            if let FileOpts::Save = opts.world_file {
-                use std::time::SystemTime;
-                // Check if folder exists and create it if it does not
-                let mut path = PathBuf::from("./maps");
-                if !path.exists() {
-                    if let Err(e) = std::fs::create_dir(&path) {
-                        warn!(?e, ?path, "Couldn't create folder for map");
-                        return;
-                    }
-                }
-                path.push(format!(
-                    // TODO: Work out a nice bincode file extension.
-                    "map_{}.bin",
-                    SystemTime::now()
-                        .duration_since(SystemTime::UNIX_EPOCH)
-                        .map(|d| d.as_millis())
-                        .unwrap_or(0)
-                ));
-                let file = match File::create(path.clone()) {
-                    Ok(file) => file,
-                    Err(e) => {
-                        warn!(?e, ?path, "Couldn't create file for maps");
-                        return;
-                    },
-                };
-
-                let writer = BufWriter::new(file);
-                if let Err(e) = bincode::serialize_into(writer, &map) {
-                    warn!(?e, "Couldn't write map");
-                }
-            }
-        })();
-
-        // Skip validation--we just performed a no-op conversion for this map, so it had
-        // better be valid!
-        let ModernMap {
-            continent_scale_hack: _,
-            map_size_lg: _,
-            alt,
-            basement,
-        } = map.into_modern().unwrap();
+        // Keep the height maps if the map is going to be saved, which has to wait until
+        // the rest of the world has been generated.
+        let map = if save_map {
+            Some(ModernMap {
+                continent_scale_hack,
+                map_size_lg: map_size_lg.vec(),
+                alt: alt.clone(),
+                basement: basement.clone(),
+                snapshot: None,
+            })
+        } else {
+            None
+        };
 
         // Additional small-scale erosion after map load, only used during testing.
         let (alt, basement) = if n_post_load_steps == 0 {
@@ -1398,7 +1550,86 @@ impl WorldSim {
             this.seed_elements();
         }
 
-        this
+        (this, map)
+    }
+
+    /// Saves the map together with a snapshot of the generated world, so that
+    /// loading it can skip erosion and civilisation generation.  A map loaded
+    /// with [`FileOpts::Load`] is upgraded in place, any other map is saved as
+    /// a new file in `./maps`.
+    pub(crate) fn save_with_snapshot(
+        &mut self,
+        mut map: ModernMap,
+        civs: Civs,
+        index: &Index,
+        world_file: &FileOpts,
+    ) -> Civs {
+        map.snapshot = Some(WorldSnapshot {
+            max_height: self.max_height,
+            chunks: mem::take(&mut self.chunks),
+            locations: mem::take(&mut self.locations),
+            civs,
+            economies: index
+                .sites
+                .values()
+                .map(|site| site.economy.clone())
+                .collect(),
+            time: index.time,
+        });
+        // NOTE: We wll always save a map with latest version.
+        let map = WorldFile::new(map);
+        (|| {
+            use std::time::SystemTime;
+            let path = if let FileOpts::Load(path) = world_file {
+                path.clone()
+            } else {
+                // Check if folder exists and create it if it does not
+                let mut path = PathBuf::from("./maps");
+                if !path.exists() {
+                    if let Err(e) = std::fs::create_dir(&path) {
+                        warn!(?e, ?path, "Couldn't create folder for map");
+                        return;
+                    }
+                }
+                path.push(format!(
+                    // TODO: Work out a nice bincode file extension.
+                    "map_{}.bin",
+                    SystemTime::now()
+                        .duration_since(SystemTime::UNIX_EPOCH)
+                        .map(|d| d.as_millis())
+                        .unwrap_or(0)
+                ));
+                path
+            };
+            // Write to a temporary file first, so that a failed upgrade can't destroy
+            // the map being upgraded.
+            let tmp_path = path.with_extension("bin.tmp");
+            let file = match File::create(&tmp_path) {
+                Ok(file) => file,
+                Err(e) => {
+                    warn!(?e, ?tmp_path, "Couldn't create file for maps");
+                    return;
+                },
+            };
+
+            let writer = BufWriter::new(file);
+            if let Err(e) = bincode::serialize_into(writer, &map) {
+                warn!(?e, "Couldn't write map");
+                let _ = std::fs::remove_file(&tmp_path);
+                return;
+            }
+            match std::fs::rename(&tmp_path, &path) {
+                Ok(()) => info!(?path, "Saved map with a snapshot of the generated world"),
+                Err(e) => warn!(?e, ?path, "Couldn't move map into place"),
+            }
+        })();
+
+        // Skip validation--we just performed a no-op conversion for this map, so it had
+        // better be valid!
+        let snapshot = map.into_modern().unwrap().snapshot.unwrap();
+        self.chunks = snapshot.chunks;
+        self.locations = snapshot.locations;
+        snapshot.civs
     }
 
     #[inline(always)]
@@ -1994,12 +2225,16 @@ impl WorldSim {
         self.get_nearest_way(wpos, |chunk| Some(chunk.path))
     }
 
-    pub fn get_nearest_cave(&self, wpos: Vec2<i32>) -> Option<(f32, Vec2<f32>, Cave, Vec2<f32>)> {
-        self.get_nearest_way(wpos, |chunk| Some(chunk.cave))
+    pub fn get_nearest_cave(
+        &self,
+        wpos: Vec2<i32>,
+        layer: usize,
+    ) -> Option<(f32, Vec2<f32>, Cave, Vec2<f32>)> {
+        self.get_nearest_way(wpos, |chunk| Some(chunk.caves[layer]))
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SimChunk {
     pub chaos: f32,
     pub alt: f32,
@@ -2023,7 +2258,9 @@ pub struct SimChunk {
     pub place: Option<Id<Place>>,
 
     pub path: (Way, Path),
-    pub cave: (Way, Cave),
+    /// Cave tunnel through the chunk in each of the depth layers, from the
+    /// shallowest to the deepest.
+    pub caves: [(Way, Cave); CAVE_LAYERS],
 
     pub contains_waypoint: bool,
 }
@@ -2217,8 +2454,8 @@ impl SimChunk {
                         }
                         ForestKind::Mangrove
                     } else if humidity > CONFIG.forest_hum {
-                        // NOTE: Probably the wrong kind of tree for this climate.
-                        ForestKind::Oak
+                        // Jungle away from the wettest parts.
+                        ForestKind::Palm
                     } else if humidity > CONFIG.desert_hum {
                         // Low but not desert... need something besides savannah.
                         ForestKind::Savannah
@@ -2261,7 +2498,7 @@ impl SimChunk {
             sites: Vec::new(),
             place: None,
             path: Default::default(),
-            cave: Default::default(),
+            caves: Default::default(),
             contains_waypoint: false,
         }
     }
@@ -2294,6 +2531,12 @@ impl SimChunk {
             BiomeKind::Desert
         } else if self.temp < CONFIG.snow_temp {
             BiomeKind::Snowlands
+        } else if self.temp > CONFIG.tropical_temp && self.humidity > CONFIG.forest_hum {
+            BiomeKind::Jungle
+        } else if self.temp > CONFIG.tropical_temp {
+            BiomeKind::Savannah
+        } else if self.temp < CONFIG.temperate_temp && self.humidity > CONFIG.desert_hum {
+            BiomeKind::Taiga
         } else if self.tree_density > 0.65 {
             BiomeKind::Forest
         } else {
@@ -2301,3 +2544,105 @@ impl SimChunk {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{IndexOwned, World};
+
+    const SEED: u32 = 59686;
+
+    /// A small island, saved by a version of the game without world snapshots
+    fn map_without_snapshot() -> WorldFile {
+        let map_size_lg = MapSizeLg::new(Vec2::new(8, 8)).unwrap();
+        let center = map_size_lg.chunks().map(|e| f64::from(e) / 2.0);
+        let alt = (0..map_size_lg.chunks_len())
+            .map(|posi| {
+                let pos = uniform_idx_as_vec2(map_size_lg, posi).map(f64::from);
+                0.25 - 0.5 * ((pos - center) / center).magnitude()
+            })
+            .collect::<Box<[Alt]>>();
+        WorldFile::Veloren0_7_0(WorldMap_0_7_0 {
+            map_size_lg: map_size_lg.vec(),
+            continent_scale_hack: 1.0,
+            basement: alt.clone(),
+            alt,
+        })
+    }
+
+    fn temp_map_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("veloren_{}_{}.bin", name, std::process::id()))
+    }
+
+    fn write_map(path: &std::path::Path, map: &WorldFile) {
+        let file = File::create(path).expect("Couldn't create map file");
+        bincode::serialize_into(BufWriter::new(file), map).expect("Couldn't write map");
+    }
+
+    fn read_map(path: &std::path::Path) -> WorldFile {
+        let file = File::open(path).expect("Couldn't open map file");
+        bincode::deserialize_from(BufReader::new(file)).expect("Couldn't read map")
+    }
+
+    fn load_world(path: &std::path::Path) -> (World, IndexOwned) {
+        World::generate(SEED, WorldOpts {
+            seed_elements: true,
+            world_file: FileOpts::Load(path.to_owned()),
+            gen_opts: GenOpts::default(),
+        })
+    }
+
+    #[test]
+    fn maps_without_snapshot_are_upgraded() {
+        let path = temp_map_path("upgrade");
+        write_map(&path, &map_without_snapshot());
+
+        let _ = load_world(&path);
+        let map = read_map(&path);
+        let _ = std::fs::remove_file(&path);
+
+        assert!(matches!(map, WorldFile::Veloren0_8_0(_)));
+        let map = map.into_modern().expect("Upgraded map is invalid");
+        assert!(map.snapshot.is_some());
+    }
+
+    #[test]
+    fn snapshots_round_trip() {
+        let path = temp_map_path("round_trip");
+        write_map(&path, &map_without_snapshot());
+
+        // The first load generates the world and saves a snapshot of it, which the
+        // second one loads
+        let (generated, generated_index) = load_world(&path);
+        let (loaded, loaded_index) = load_world(&path);
+        let _ = std::fs::remove_file(&path);
+
+        // Compare the serialized data, since not all of it can be compared directly
+        let chunks = |world: &World| bincode::serialize(&world.sim().chunks).unwrap();
+        assert!(chunks(&generated) == chunks(&loaded), "Chunks differ");
+        let civs = |world: &World| bincode::serialize(world.civs()).unwrap();
+        assert!(civs(&generated) == civs(&loaded), "Civilisations differ");
+
+        let site_origins = |index: &Index| {
+            index
+                .sites
+                .values()
+                .map(|site| site.get_origin())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(site_origins(&generated_index), site_origins(&loaded_index));
+        let economies = |index: &Index| {
+            let economies = index
+                .sites
+                .values()
+                .map(|site| &site.economy)
+                .collect::<Vec<_>>();
+            bincode::serialize(&economies).unwrap()
+        };
+        assert!(
+            economies(&generated_index) == economies(&loaded_index),
+            "Economies differ"
+        );
+        assert!((generated_index.time - loaded_index.time).abs() < f32::EPSILON);
+    }
+}
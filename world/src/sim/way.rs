@@ -1,6 +1,7 @@
+use serde::{Deserialize, Serialize};
 use vek::*;
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Way {
     /// Offset from chunk center in blocks (no more than half chunk width)
     pub offset: Vec2<i8>,
@@ -14,7 +15,7 @@ impl Way {
     pub fn clear(&mut self) { self.neighbors = 0; }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Path {
     pub width: f32, // Actually radius
 }
@@ -45,7 +46,7 @@ impl Path {
     pub fn surface_color(&self, col: Rgb<u8>) -> Rgb<u8> { col.map(|e| (e as f32 * 0.7) as u8) }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
 pub struct Cave {
     pub width: f32, // Actually radius
     pub alt: f32,   // Actually radius
@@ -9,9 +9,10 @@ use crate::{
     IndexRef,
 };
 use common::{
-    generation::ChunkSupplement,
-    terrain::{Block, BlockKind, SpriteKind},
-    vol::{BaseVol, ReadVol, RectSizedVol, WriteVol},
+    comp::{self, Item},
+    generation::{ChunkSupplement, EntityInfo},
+    terrain::{Block, BlockKind, SpriteKind, TerrainChunkSize},
+    vol::{BaseVol, ReadVol, RectSizedVol, RectVolSize, WriteVol},
 };
 use core::f32;
 use rand::prelude::*;
@@ -297,6 +298,21 @@ impl Castle {
                     // Boundary wall
                     let wall_z = wpos.z - wall_alt;
                     if z < head_space {
+                        // Put a gate where paths pass through the wall
+                        if z >= 0 && wall_dist == 0 && head_space > 1 {
+                            let pos = Vec3::new(offs.x, offs.y, wpos.z);
+                            if vol.get(pos).map_or(false, |b| !b.is_filled()) {
+                                let gate = Block::air(SpriteKind::DropGate);
+                                let _ = vol.set(
+                                    pos,
+                                    gate.with_ori(match wall_ori {
+                                        Ori::East => 2,
+                                        Ori::North => 0,
+                                    })
+                                    .unwrap_or(gate),
+                                );
+                            }
+                        }
                         continue;
                     }
 
@@ -413,15 +429,84 @@ impl Castle {
         }
     }
 
-    #[allow(clippy::or_fun_call)] // TODO: Pending review in #587
+    /// Points just inside the boundary wall at each tower, which guards walk
+    /// between.
+    fn wall_patrol_points(&self) -> Vec<Vec3<f32>> {
+        self.towers
+            .iter()
+            .map(|tower| {
+                let inner = self.origin + tower.offset.map(|e| (e as f32 * 0.85) as i32);
+                Vec3::new(inner.x as f32, inner.y as f32, tower.alt as f32)
+            })
+            .collect()
+    }
+
     pub fn apply_supplement<'a>(
         &'a self,
         // NOTE: Used only for dynamic elements like chests and entities!
-        _dynamic_rng: &mut impl Rng,
-        _wpos2d: Vec2<i32>,
-        _get_column: impl FnMut(Vec2<i32>) -> Option<&'a ColumnSample<'a>>,
-        _supplement: &mut ChunkSupplement,
+        dynamic_rng: &mut impl Rng,
+        wpos2d: Vec2<i32>,
+        mut get_column: impl FnMut(Vec2<i32>) -> Option<&'a ColumnSample<'a>>,
+        supplement: &mut ChunkSupplement,
     ) {
-        // TODO
+        let area = Aabr {
+            min: wpos2d,
+            max: wpos2d + TerrainChunkSize::RECT_SIZE.map(|e| e as i32),
+        };
+
+        let wall_points = self.wall_patrol_points();
+
+        // One guard walking each stretch of wall, and a pair circling each keep
+        let wall_guards = wall_points.iter().enumerate().map(|(i, start)| {
+            let end = wall_points[(i + 1) % wall_points.len()];
+            (*start, vec![end, *start])
+        });
+        let keep_guards = self.keeps.iter().flat_map(|keep| {
+            let center = self.origin + keep.offset;
+            let radius = (keep.locus + 6) as f32;
+            let route = (0..4)
+                .map(|i| {
+                    let angle = i as f32 / 4.0 * f32::consts::PI * 2.0;
+                    Vec3::new(
+                        center.x as f32 + angle.cos() * radius,
+                        center.y as f32 + angle.sin() * radius,
+                        keep.alt as f32,
+                    )
+                })
+                .collect::<Vec<_>>();
+            (0..2).map(move |i| {
+                let mut route = route.clone();
+                route.rotate_left(i * 2);
+                (route[route.len() - 1], route)
+            })
+        });
+
+        for (spawn, route) in wall_guards.chain(keep_guards) {
+            let spawn2d = spawn.xy().map(|e| e as i32);
+            if !area.contains_point(spawn2d) {
+                continue;
+            }
+            let alt = get_column(spawn2d - wpos2d).map_or(spawn.z, |col| col.alt);
+
+            let entity = EntityInfo::at(Vec3::new(spawn.x, spawn.y, alt + 2.0))
+                .with_body(comp::Body::Humanoid(comp::humanoid::Body::random()))
+                .with_alignment(if self.evil {
+                    comp::Alignment::Enemy
+                } else {
+                    comp::Alignment::Npc
+                })
+                .with_name("Castle Guard")
+                .with_level(dynamic_rng.gen_range(10, 16))
+                .with_main_tool(Item::new_from_asset_expect(
+                    match dynamic_rng.gen_range(0, 3) {
+                        0 => "common.items.npc_weapons.bow.horn_longbow-0",
+                        1 => "common.items.npc_weapons.hammer.starter_hammer",
+                        _ => "common.items.npc_weapons.sword.starter_sword",
+                    },
+                ))
+                .with_patrol_route(route);
+
+            supplement.add_entity(entity);
+        }
     }
 }
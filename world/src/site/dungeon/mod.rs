@@ -4,15 +4,15 @@ use crate::{
     column::ColumnSample,
     sim::WorldSim,
     site::BlockMask,
-    util::{attempt, Grid, RandomField, Sampler, CARDINALS, DIRS},
+    util::{attempt, seed_expan, Grid, RandomField, Sampler, CARDINALS, DIRS},
     IndexRef,
 };
 use common::{
-    assets::Asset,
+    assets::{self, Asset, AssetFile},
     astar::Astar,
-    comp::{self},
+    comp::{self, biped_large, golem, humanoid, quadruped_low},
     generation::{ChunkSupplement, EntityInfo},
-    lottery::Lottery,
+    loot::{LootContext, LootSite},
     store::{Id, Store},
     terrain::{Block, BlockKind, SpriteKind, Structure, TerrainChunkSize},
     vol::{BaseVol, ReadVol, RectSizedVol, RectVolSize, WriteVol},
@@ -21,8 +21,9 @@ use core::{f32, hash::BuildHasherDefault};
 use fxhash::FxHasher64;
 use lazy_static::lazy_static;
 use rand::prelude::*;
+use rand_chacha::ChaChaRng;
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{io::BufReader, sync::Arc};
 use vek::*;
 
 pub struct Dungeon {
@@ -31,6 +32,7 @@ pub struct Dungeon {
     seed: u32,
     #[allow(dead_code)]
     noise: RandomField,
+    theme: DungeonTheme,
     floors: Vec<Floor>,
 }
 
@@ -42,6 +44,177 @@ pub struct GenCtx<'a, R: Rng> {
 #[derive(Deserialize)]
 pub struct Colors {
     pub stone: (u8, u8, u8),
+    pub crypt_stone: (u8, u8, u8),
+    pub mine_stone: (u8, u8, u8),
+}
+
+/// The overall style of a dungeon. This decides the look of its floors, the
+/// enemies that inhabit it and the boss waiting on the deepest floor.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DungeonTheme {
+    Crypt,
+    Mine,
+    CultistTemple,
+}
+
+impl DungeonTheme {
+    fn generate(sim: Option<&WorldSim>, wpos: Vec2<i32>, rng: &mut impl Rng) -> Self {
+        // Mines are dug into rugged, mountainous terrain
+        let mine_weight = match sim.and_then(|sim| sim.get_wpos(wpos)) {
+            Some(chunk) if chunk.chaos > 0.3 => 3,
+            _ => 1,
+        };
+        match rng.gen_range(0, 2 + mine_weight) {
+            0 => DungeonTheme::Crypt,
+            1 => DungeonTheme::CultistTemple,
+            _ => DungeonTheme::Mine,
+        }
+    }
+
+    fn spec(&self) -> Arc<ThemeSpec> {
+        ThemeSpec::load_expect(match self {
+            DungeonTheme::Crypt => "world.dungeon.crypt",
+            DungeonTheme::Mine => "world.dungeon.mine",
+            DungeonTheme::CultistTemple => "world.dungeon.cultist_temple",
+        })
+    }
+
+    fn stone(&self, colors: &Colors) -> Rgb<u8> {
+        match self {
+            DungeonTheme::Crypt => colors.crypt_stone,
+            DungeonTheme::Mine => colors.mine_stone,
+            DungeonTheme::CultistTemple => colors.stone,
+        }
+        .into()
+    }
+
+    /// One in how many rooms should have pillars.
+    fn pillar_rarity(&self) -> u32 {
+        match self {
+            DungeonTheme::Crypt => 2,
+            DungeonTheme::Mine => 3,
+            DungeonTheme::CultistTemple => 4,
+        }
+    }
+
+    fn tunnel_height(&self) -> f32 {
+        match self {
+            DungeonTheme::Mine => 10.0,
+            _ => 8.0,
+        }
+    }
+
+    fn floor_sprite(&self, r: u32) -> SpriteKind {
+        match self {
+            DungeonTheme::Crypt => match r % 20 {
+                0 => SpriteKind::VeloriteFrag,
+                1..=10 => SpriteKind::Mushroom,
+                _ => SpriteKind::Stones,
+            },
+            DungeonTheme::Mine => match r % 20 {
+                0..=2 => SpriteKind::Velorite,
+                3..=8 => SpriteKind::VeloriteFrag,
+                9..=10 => SpriteKind::Crate,
+                _ => SpriteKind::Stones,
+            },
+            DungeonTheme::CultistTemple => match r % 20 {
+                0 => SpriteKind::Apple,
+                1 => SpriteKind::VeloriteFrag,
+                2 => SpriteKind::Velorite,
+                3..=8 => SpriteKind::Mushroom,
+                _ => SpriteKind::ShortGrass,
+            },
+        }
+    }
+}
+
+/// The body of an NPC defined in a dungeon theme.
+#[derive(Clone, Debug, Deserialize)]
+pub enum NpcBody {
+    /// A humanoid of the given species, or of a random species if `None`.
+    Humanoid(Option<humanoid::Species>),
+    BipedLarge(biped_large::Species),
+    Golem(golem::Species),
+    QuadrupedLow(quadruped_low::Species),
+}
+
+impl NpcBody {
+    fn random_body(&self, rng: &mut impl Rng) -> comp::Body {
+        match self {
+            NpcBody::Humanoid(Some(species)) => {
+                comp::Body::Humanoid(humanoid::Body::random_with(rng, species))
+            },
+            NpcBody::Humanoid(None) => comp::Body::Humanoid(humanoid::Body::random()),
+            NpcBody::BipedLarge(species) => {
+                comp::Body::BipedLarge(biped_large::Body::random_with(rng, species))
+            },
+            NpcBody::Golem(species) => comp::Body::Golem(golem::Body::random_with(rng, species)),
+            NpcBody::QuadrupedLow(species) => {
+                comp::Body::QuadrupedLow(quadruped_low::Body::random_with(rng, species))
+            },
+        }
+    }
+}
+
+/// A regular enemy found throughout a dungeon.
+#[derive(Clone, Debug, Deserialize)]
+pub struct EnemySpec {
+    pub name: String,
+    pub body: NpcBody,
+    /// Item specifiers, one of which is picked as the main tool. May be empty
+    /// for bodies that get their weapon from the loadout builder.
+    pub main_tools: Vec<String>,
+}
+
+/// The boss guarding the deepest floor of a dungeon.
+#[derive(Clone, Debug, Deserialize)]
+pub struct BossSpec {
+    pub name: String,
+    pub body: NpcBody,
+    /// Inclusive range of levels the boss can spawn with.
+    pub level: (u32, u32),
+    /// Specifier of the `LootTable` the boss' drop is rolled from.
+    pub loot_table: String,
+}
+
+/// The inhabitants of a dungeon theme, loaded from `world.dungeon.<theme>`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ThemeSpec {
+    pub enemies: Vec<EnemySpec>,
+    pub boss: BossSpec,
+}
+
+impl Asset for ThemeSpec {
+    const ENDINGS: &'static [&'static str] = &["ron"];
+
+    fn parse(buf_reader: BufReader<AssetFile>, _specifier: &str) -> Result<Self, assets::Error> {
+        let spec: Self = ron::de::from_reader(buf_reader).map_err(assets::Error::parse_error)?;
+        if spec.enemies.is_empty() {
+            return Err(assets::Error::parse_error(
+                "Dungeon theme has no enemies".to_string(),
+            ));
+        }
+        let (min, max) = spec.boss.level;
+        if min > max {
+            return Err(assets::Error::parse_error(format!(
+                "Boss level range {}..={} is empty",
+                min, max
+            )));
+        }
+        Ok(spec)
+    }
+}
+
+/// Specifier of the `LootTable` that enemies on the given floor roll their
+/// drops from. Deeper floors have better rewards.
+fn floor_loot_table(level: i32) -> String { format!("common.loot_tables.dungeon.floor_{}", level) }
+
+fn dungeon_loot_context(level: u32) -> LootContext {
+    LootContext {
+        level: Some(level),
+        biome: None,
+        site: Some(LootSite::Dungeon),
+    }
 }
 
 const ALT_OFFSET: i32 = -2;
@@ -52,6 +225,15 @@ impl Dungeon {
     #[allow(clippy::let_and_return)] // TODO: Pending review in #587
     pub fn generate(wpos: Vec2<i32>, sim: Option<&WorldSim>, rng: &mut impl Rng) -> Self {
         let mut ctx = GenCtx { sim, rng };
+        let seed = ctx.rng.gen();
+        let noise = RandomField::new(ctx.rng.gen());
+        // The theme is drawn from the dungeon's own seed rather than `rng`, so that
+        // everything else generated from `rng` stays the same.
+        let theme = DungeonTheme::generate(
+            ctx.sim,
+            wpos,
+            &mut ChaChaRng::from_seed(seed_expan::rng_state(seed)),
+        );
         let this = Self {
             origin: wpos - TILE_SIZE / 2,
             alt: ctx
@@ -59,11 +241,12 @@ impl Dungeon {
                 .and_then(|sim| sim.get_alt_approx(wpos))
                 .unwrap_or(0.0) as i32
                 + 6,
-            seed: ctx.rng.gen(),
-            noise: RandomField::new(ctx.rng.gen()),
+            seed,
+            noise,
+            theme,
             floors: (0..LEVELS)
                 .scan(Vec2::zero(), |stair_tile, level| {
-                    let (floor, st) = Floor::generate(&mut ctx, *stair_tile, level as i32);
+                    let (floor, st) = Floor::generate(&mut ctx, theme, *stair_tile, level as i32);
                     *stair_tile = st;
                     Some(floor)
                 })
@@ -75,6 +258,8 @@ impl Dungeon {
 
     pub fn get_origin(&self) -> Vec2<i32> { self.origin }
 
+    pub fn theme(&self) -> DungeonTheme { self.theme }
+
     pub fn radius(&self) -> f32 { 1200.0 }
 
     #[allow(clippy::needless_update)] // TODO: Pending review in #587
@@ -218,6 +403,8 @@ pub struct Room {
 }
 
 struct Floor {
+    theme: DungeonTheme,
+    level: i32,
     tile_offset: Vec2<i32>,
     tiles: Grid<Tile>,
     rooms: Store<Room>,
@@ -233,6 +420,7 @@ const FLOOR_SIZE: Vec2<i32> = Vec2::new(18, 18);
 impl Floor {
     fn generate(
         ctx: &mut GenCtx<impl Rng>,
+        theme: DungeonTheme,
         stair_tile: Vec2<i32>,
         level: i32,
     ) -> (Self, Vec2<i32>) {
@@ -252,6 +440,8 @@ impl Floor {
 
         let tile_offset = -FLOOR_SIZE / 2;
         let mut this = Floor {
+            theme,
+            level,
             tile_offset,
             tiles: Grid::new(FLOOR_SIZE, Tile::Solid),
             rooms: Store::default(),
@@ -360,7 +550,7 @@ impl Floor {
                 boss: false,
                 area,
                 height: ctx.rng.gen_range(10, 15),
-                pillars: if ctx.rng.gen_range(0, 4) == 0 {
+                pillars: if ctx.rng.gen_range(0, self.theme.pillar_rarity()) == 0 {
                     Some(2)
                 } else {
                     None
@@ -470,14 +660,12 @@ impl Floor {
                         .unwrap_or(false)
                         && !tile_is_pillar
                     {
-                        // Bad
-                        let chosen =
-                            Lottery::<String>::load_expect(match dynamic_rng.gen_range(0, 5) {
-                                0 => "common.loot_tables.loot_table_humanoids",
-                                1 => "common.loot_tables.loot_table_armor_misc",
-                                _ => "common.loot_tables.loot_table_cultists",
-                            });
-                        let chosen = chosen.choose();
+                        let spec = self.theme.spec();
+                        let enemy = spec
+                            .enemies
+                            .choose(dynamic_rng)
+                            .expect("Dungeon themes without enemies fail to load");
+                        let level = dynamic_rng.gen_range(1, 9) + self.level as u32 * 2;
                         let entity = EntityInfo::at(
                             tile_wcenter.map(|e| e as f32)
                             // Randomly displace them a little
@@ -486,18 +674,18 @@ impl Floor {
                                 .map(|e| e as f32 / 16.0),
                         )
                         .do_if(RandomField::new(room.seed.wrapping_add(1)).chance(Vec3::from(tile_pos), 0.2) && !room.boss, |e| e.into_giant())
+                        // Deeper floors have tougher enemies
+                        .with_level(level)
                         .with_alignment(comp::Alignment::Enemy)
-                        .with_body(comp::Body::Humanoid(comp::humanoid::Body::random()))
-                        .with_name("Cultist Acolyte")
-                        .with_loot_drop(comp::Item::new_from_asset_expect(chosen))
-                        .with_main_tool(comp::Item::new_from_asset_expect(match dynamic_rng.gen_range(0, 6) {
-                            0 => "common.items.npc_weapons.axe.malachite_axe-0",
-                            1 => "common.items.npc_weapons.sword.cultist_purp_2h-0",
-                            2 => "common.items.npc_weapons.sword.cultist_purp_2h-0",
-                            3 => "common.items.npc_weapons.hammer.cultist_purp_2h-0",
-                            4 => "common.items.npc_weapons.staff.cultist_staff",
-                            _ => "common.items.npc_weapons.bow.horn_longbow-0",
-                        }));
+                        .with_body(enemy.body.random_body(dynamic_rng))
+                        .with_name(enemy.name.clone())
+                        .with_loot_table(&floor_loot_table(self.level), &dungeon_loot_context(level), dynamic_rng);
+                        let entity = match enemy.main_tools.choose(dynamic_rng) {
+                            Some(tool) => {
+                                entity.with_main_tool(comp::Item::new_from_asset_expect(tool))
+                            },
+                            None => entity,
+                        };
 
                         supplement.add_entity(entity);
                     }
@@ -517,19 +705,19 @@ impl Floor {
                             boss_spawn_tile + if boss_tile_is_pillar { 1 } else { 0 };
 
                         if tile_pos == boss_spawn_tile && tile_wcenter.xy() == wpos2d {
-                            let chosen = Lottery::<String>::load_expect(
-                                "common.loot_tables.loot_table_boss_cultist-leader",
-                            );
-                            let chosen = chosen.choose();
+                            let spec = self.theme.spec();
+                            let boss = &spec.boss;
+                            let level = dynamic_rng.gen_range(boss.level.0, boss.level.1 + 1);
                             let entity = EntityInfo::at(tile_wcenter.map(|e| e as f32))
-                                .with_level(dynamic_rng.gen_range(1, 5))
+                                .with_level(level)
                                 .with_alignment(comp::Alignment::Enemy)
-                                .with_body(comp::Body::Golem(comp::golem::Body::random_with(
+                                .with_body(boss.body.random_body(dynamic_rng))
+                                .with_name(boss.name.clone())
+                                .with_loot_table(
+                                    &boss.loot_table,
+                                    &dungeon_loot_context(level),
                                     dynamic_rng,
-                                    &comp::golem::Species::StoneGolem,
-                                )))
-                                .with_name("Stonework Defender".to_string())
-                                .with_loot_drop(comp::Item::new_from_asset_expect(chosen));
+                                );
 
                             supplement.add_entity(entity);
                         }
@@ -574,12 +762,12 @@ impl Floor {
         let tile_center = tile_pos * TILE_SIZE + TILE_SIZE / 2;
         let rtile_pos = rpos - tile_center;
 
-        let colors = &index.colors.site.dungeon;
+        let stone_color = self.theme.stone(&index.colors.site.dungeon);
 
         let vacant = BlockMask::new(with_sprite(SpriteKind::Empty), 1);
 
         let make_staircase = move |pos: Vec3<i32>, radius: f32, inner_radius: f32, stretch: f32| {
-            let stone = BlockMask::new(Block::new(BlockKind::Rock, colors.stone.into()), 5);
+            let stone = BlockMask::new(Block::new(BlockKind::Rock, stone_color), 5);
 
             if (pos.xy().magnitude_squared() as f32) < inner_radius.powf(2.0) {
                 stone
@@ -609,13 +797,8 @@ impl Floor {
         let floor_sprite = if RandomField::new(7331).chance(Vec3::from(pos), 0.00005) {
             BlockMask::new(
                 with_sprite(
-                    match (RandomField::new(1337).get(Vec3::from(pos)) / 2) % 20 {
-                        0 => SpriteKind::Apple,
-                        1 => SpriteKind::VeloriteFrag,
-                        2 => SpriteKind::Velorite,
-                        3..=8 => SpriteKind::Mushroom,
-                        _ => SpriteKind::ShortGrass,
-                    },
+                    self.theme
+                        .floor_sprite(RandomField::new(1337).get(Vec3::from(pos)) / 2),
                 ),
                 1,
             )
@@ -632,7 +815,11 @@ impl Floor {
             vacant
         };
 
-        let tunnel_height = if self.final_level { 16.0 } else { 8.0 };
+        let tunnel_height = if self.final_level {
+            16.0
+        } else {
+            self.theme.tunnel_height()
+        };
 
         move |z| match self.tiles.get(tile_pos) {
             Some(Tile::Solid) => BlockMask::nothing(),
@@ -1,7 +1,8 @@
 use crate::util::{DHashMap, MapVec};
+use serde::{Deserialize, Serialize};
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum Good {
     Wheat = 0,
     Flour = 1,
@@ -17,7 +18,7 @@ pub enum Good {
 use Good::*;
 
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
 pub enum Labor {
     Farmer = 0,
     Lumberjack = 1,
@@ -28,6 +29,7 @@ pub enum Labor {
 }
 use Labor::*;
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Economy {
     pub pop: f32,
 
@@ -84,6 +84,14 @@ impl Site {
         }
     }
 
+    /// Area of the town at this site, if it has one
+    pub fn town_bounds(&self) -> Option<Aabr<i32>> {
+        match &self.kind {
+            SiteKind::Settlement(s) => s.town_bounds(),
+            SiteKind::Dungeon(_) | SiteKind::Castle(_) => None,
+        }
+    }
+
     pub fn spawn_rules(&self, wpos: Vec2<i32>) -> SpawnRules {
         match &self.kind {
             SiteKind::Settlement(s) => s.spawn_rules(wpos),
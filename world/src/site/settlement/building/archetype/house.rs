@@ -550,6 +550,17 @@ impl Archetype for House {
                                     SpriteKind::Crate
                                 }
                             },
+                            // Crafting stations
+                            5 => match self.noise.get(Vec3::new(
+                                center_offset.x,
+                                center_offset.y,
+                                z + 200,
+                            )) % 3
+                            {
+                                0 => SpriteKind::Anvil,
+                                1 => SpriteKind::Cauldron,
+                                _ => SpriteKind::Loom,
+                            },
                             6 => SpriteKind::DrawerMedium,
                             7 => SpriteKind::DrawerSmall,
                             8 => SpriteKind::TableSide,
@@ -187,6 +187,27 @@ impl Settlement {
 
     pub fn get_origin(&self) -> Vec2<i32> { self.origin }
 
+    /// Area covered by the town's districts and buildings, in world coordinates
+    pub fn town_bounds(&self) -> Option<Aabr<i32>> {
+        let districts = self
+            .town
+            .iter()
+            .flat_map(|town| town.districts().values())
+            .map(|district| Aabr {
+                min: district.aabr.min * AREA_SIZE as i32,
+                max: district.aabr.max * AREA_SIZE as i32,
+            });
+        districts
+            .chain(self.structures.iter().map(Structure::bounds_2d))
+            .fold(None, |bounds: Option<Aabr<i32>>, b| {
+                Some(bounds.map_or(b, |bounds| bounds.union(b)))
+            })
+            .map(|bounds| Aabr {
+                min: self.origin + bounds.min,
+                max: self.origin + bounds.max,
+            })
+    }
+
     /// Designate hazardous terrain based on world data
     #[allow(clippy::blocks_in_if_conditions)] // TODO: Pending review in #587
     pub fn designate_from_world(&mut self, sim: &WorldSim, rng: &mut impl Rng) {
@@ -929,6 +950,45 @@ impl Settlement {
 
                     supplement.add_entity(entity);
                 }
+
+                // Guards walking a loop around the town centre
+                if let (Some(Plot::Town { .. }), Some(town)) = (sample.plot, &self.town) {
+                    if RandomField::new(self.seed + 2)
+                        .chance(Vec3::from(wpos2d), 1.0 / (50.0 * 40.0 * 4.0))
+                    {
+                        let center =
+                            self.origin + town.base_tile * AREA_SIZE as i32 + AREA_SIZE as i32 / 2;
+                        let rcenter = (wpos2d - center).map(|e| e as f32);
+                        let radius = rcenter.magnitude().max(16.0);
+                        let start_angle = rcenter.y.atan2(rcenter.x);
+                        let route = (1..=6)
+                            .map(|i| {
+                                let angle = start_angle + i as f32 / 6.0 * f32::consts::PI * 2.0;
+                                Vec3::new(
+                                    center.x as f32 + angle.cos() * radius,
+                                    center.y as f32 + angle.sin() * radius,
+                                    entity_wpos.z,
+                                )
+                            })
+                            .collect();
+
+                        let entity = EntityInfo::at(entity_wpos)
+                            .with_body(comp::Body::Humanoid(humanoid::Body::random()))
+                            .with_alignment(comp::Alignment::Npc)
+                            .with_name("Guard")
+                            .with_level(dynamic_rng.gen_range(8, 14))
+                            .with_main_tool(Item::new_from_asset_expect(
+                                match dynamic_rng.gen_range(0, 3) {
+                                    0 => "common.items.npc_weapons.bow.horn_longbow-0",
+                                    1 => "common.items.npc_weapons.hammer.starter_hammer",
+                                    _ => "common.items.npc_weapons.sword.starter_sword",
+                                },
+                            ))
+                            .with_patrol_route(route);
+
+                        supplement.add_entity(entity);
+                    }
+                }
             }
         }
     }
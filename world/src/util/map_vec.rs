@@ -1,7 +1,12 @@
 use crate::util::DHashMap;
+use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize + Eq + Hash, T: Serialize",
+    deserialize = "K: Deserialize<'de> + Eq + Hash, T: Deserialize<'de>"
+))]
 pub struct MapVec<K, T> {
     /// We use this hasher (FxHasher32) because
     /// (1) we don't care about DDOS attacks (ruling out SipHash);